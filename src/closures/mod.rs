@@ -0,0 +1,231 @@
+use crate::contours::trace;
+use crate::irap::{Irap, Polygons};
+use crate::utils::{find_root, neighbours, shoelace};
+use rayon::prelude::*;
+
+/// How undefined nodes take part in the fill-and-spill analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UndefinedNodes {
+    /// Undefined nodes are sealing boundaries that closures can rest against.
+    Boundary,
+    /// Undefined nodes are sinks, like the edge of the lattice: a closure
+    /// spills at the first node next to one.
+    Sink,
+}
+
+/// Structural closure of a depth surface: the region around a crest that
+/// traps fluids rising from below, filled down to its spill point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Closure {
+    /// Shallowest node as (col, row).
+    pub crest: (usize, usize),
+    pub crest_depth: f64,
+    /// Node where the closure spills, as (col, row).
+    pub spill: (usize, usize),
+    pub spill_depth: f64,
+    /// Area inside the outline, excluding deeper islands, in world units.
+    pub area: f64,
+    /// Spill depth contour around the closure in world coordinates, as a
+    /// closed ring with the first point repeated at the end.
+    pub outline: Vec<[f64; 2]>,
+    /// Index of the closure formed when this one merges with a neighbour at
+    /// its spill point, if that closes before spilling.
+    pub parent: Option<usize>,
+}
+
+/// Connected region of the nodes processed so far, tracked during the sweep.
+struct Component {
+    crest: usize,
+    /// False once the region has spilled, after which anything joining it spills too.
+    alive: bool,
+    /// Closures that merged into this region.
+    children: Vec<usize>,
+}
+
+impl Irap {
+    /// Structural closures found by a fill-and-spill analysis, with depths
+    /// increasing downwards.
+    ///
+    /// Nodes are visited from shallowest to deepest, growing a region around
+    /// each crest. A region spills when it reaches the edge of the lattice,
+    /// joins a region that has already spilled, or meets another region at a
+    /// saddle. In the last case the two closures are combined into a larger
+    /// one, reported as their parent. Nodes are 4-connected, and regions that
+    /// never spill, being sealed by undefined nodes, are not reported.
+    pub fn closures(&self, undefined: UndefinedNodes) -> Vec<Closure> {
+        let header = &self.header;
+        let ncol = header.ncol as usize;
+        let nrow = header.nrow as usize;
+
+        let mut order: Vec<usize> = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .collect();
+        order.sort_by(|&a, &b| self.values[a].total_cmp(&self.values[b]).then(a.cmp(&b)));
+
+        let mut parent: Vec<usize> = (0..self.values.len()).collect();
+        let mut processed = vec![false; self.values.len()];
+        let mut components: Vec<Option<Component>> = (0..self.values.len()).map(|_| None).collect();
+        // (crest, spill, children) of each closure in the order they close
+        let mut found: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+
+        for &idx in &order {
+            let (col, row) = (idx / nrow, idx % nrow);
+            let mut outlet = col == 0 || row == 0 || col + 1 == ncol || row + 1 == nrow;
+            let mut roots: Vec<usize> = Vec::new();
+            for (c, r) in neighbours(col, row, ncol, nrow) {
+                let n = header.index(c, r);
+                if self.values[n].is_nan() {
+                    outlet |= undefined == UndefinedNodes::Sink;
+                } else if processed[n] {
+                    let root = find_root(&mut parent, n);
+                    if !roots.contains(&root) {
+                        roots.push(root);
+                    }
+                }
+            }
+            processed[idx] = true;
+
+            let alive: Vec<usize> = roots
+                .iter()
+                .copied()
+                .filter(|&r| components[r].as_ref().is_some_and(|c| c.alive))
+                .collect();
+            let spills = outlet || alive.len() != roots.len() || alive.len() > 1;
+
+            let mut merged = Component {
+                crest: roots
+                    .iter()
+                    .filter_map(|&r| components[r].as_ref().map(|c| c.crest))
+                    .min_by(|&a, &b| self.values[a].total_cmp(&self.values[b]))
+                    .unwrap_or(idx),
+                alive: true,
+                children: Vec::new(),
+            };
+            if spills {
+                for &r in &alive {
+                    if let Some(component) = components[r].take() {
+                        merged.children.push(found.len());
+                        found.push((component.crest, idx, component.children));
+                    }
+                }
+                merged.alive = !outlet && alive.len() == roots.len();
+            } else if let Some(&r) = alive.first()
+                && let Some(component) = components[r].take()
+            {
+                merged.children = component.children;
+            }
+
+            for &r in &roots {
+                components[r] = None;
+                parent[r] = idx;
+            }
+            components[idx] = Some(merged);
+        }
+
+        let outlines: Vec<Option<(f64, Vec<[f64; 2]>)>> = found
+            .par_iter()
+            .map(|&(crest, spill, _)| self.outline(crest, self.values[spill]))
+            .collect();
+
+        // Closures without height are skipped, so indices are remapped
+        let mut index = vec![None; found.len()];
+        let mut closures = Vec::new();
+        for (k, ((crest, spill, _), outline)) in found.iter().zip(outlines).enumerate() {
+            let Some((area, outline)) = outline else {
+                continue;
+            };
+            index[k] = Some(closures.len());
+            closures.push(Closure {
+                crest: (crest / nrow, crest % nrow),
+                crest_depth: self.values[*crest] as f64,
+                spill: (spill / nrow, spill % nrow),
+                spill_depth: self.values[*spill] as f64,
+                area,
+                outline,
+                parent: None,
+            });
+        }
+        for (k, (_, _, children)) in found.iter().enumerate() {
+            for &child in children {
+                if let Some(child) = index[child] {
+                    closures[child].parent = index[k];
+                }
+            }
+        }
+        closures
+    }
+
+    /// Area and outer ring of the nodes shallower than `spill_depth`
+    /// connected to `crest`, or `None` if there are none.
+    fn outline(&self, crest: usize, spill_depth: f32) -> Option<(f64, Vec<[f64; 2]>)> {
+        let header = &self.header;
+        let ncol = header.ncol as usize;
+        let nrow = header.nrow as usize;
+        if self.values[crest] >= spill_depth {
+            return None;
+        }
+
+        let mut inside = vec![false; self.values.len()];
+        inside[crest] = true;
+        let mut stack = vec![crest];
+        while let Some(idx) = stack.pop() {
+            for (c, r) in neighbours(idx / nrow, idx % nrow, ncol, nrow) {
+                let n = header.index(c, r);
+                if !inside[n] && self.values[n] < spill_depth {
+                    inside[n] = true;
+                    stack.push(n);
+                }
+            }
+        }
+
+        // Everything outside the region is raised to at least the spill
+        // depth, so the spill contour only follows the region's boundary.
+        let field: Vec<f32> = self
+            .values
+            .iter()
+            .zip(&inside)
+            .map(|(&v, &inside)| if inside { v } else { v.max(spill_depth) })
+            .collect();
+        let rings: Vec<(f64, Vec<[f64; 2]>)> = trace(&field, ncol, nrow, spill_depth as f64)
+            .into_iter()
+            .filter(|line| line.closed)
+            .map(|line| (shoelace(&line.points).abs(), line.points))
+            .collect();
+        let exterior = rings
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.0.total_cmp(&b.1.0))?
+            .0;
+        let holes: f64 = rings
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| k != exterior)
+            .map(|(_, (area, _))| area)
+            .sum();
+        let (area, ring) = &rings[exterior];
+        let cell_area = (header.xinc * header.yinc).abs();
+        let outline = ring
+            .iter()
+            .map(|&[col, row]| {
+                let (x, y) = header.node_xy(col, row);
+                [x, y]
+            })
+            .collect();
+        Some(((area - holes) * cell_area, outline))
+    }
+}
+
+/// Closure outlines as polygons, with the spill depth as z value.
+pub fn to_polygons(closures: &[Closure]) -> Polygons {
+    Polygons {
+        polygons: closures
+            .iter()
+            .map(|c| {
+                c.outline
+                    .iter()
+                    .map(|&[x, y]| [x, y, c.spill_depth])
+                    .collect()
+            })
+            .collect(),
+    }
+}
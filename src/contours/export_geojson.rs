@@ -0,0 +1,96 @@
+use super::{Contour, Isoband};
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn write_features<W: Write>(contours: &[Contour], out: &mut W) -> std::io::Result<()> {
+    write!(out, "{{\"type\":\"FeatureCollection\",\"features\":[")?;
+    for (i, contour) in contours.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(
+            out,
+            "{{\"type\":\"Feature\",\"properties\":{{\"level\":{}}},\"geometry\":{{\"type\":\"LineString\",\"coordinates\":",
+            contour.level
+        )?;
+        write_ring(&contour.points, out)?;
+        write!(out, "}}}}")?;
+    }
+    writeln!(out, "]}}")?;
+    Ok(())
+}
+
+fn write_ring<W: Write>(ring: &[[f64; 2]], out: &mut W) -> std::io::Result<()> {
+    write!(out, "[")?;
+    for (k, [x, y]) in ring.iter().enumerate() {
+        if k > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, "[{},{}]", x, y)?;
+    }
+    write!(out, "]")
+}
+
+fn write_isoband_features<W: Write>(isobands: &[Isoband], out: &mut W) -> std::io::Result<()> {
+    write!(out, "{{\"type\":\"FeatureCollection\",\"features\":[")?;
+    for (i, band) in isobands.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(
+            out,
+            "{{\"type\":\"Feature\",\"properties\":{{\"lower\":{},\"upper\":{},\"area\":{}}},\"geometry\":{{\"type\":\"MultiPolygon\",\"coordinates\":[",
+            band.lower, band.upper, band.area
+        )?;
+        for (k, polygon) in band.polygons.iter().enumerate() {
+            if k > 0 {
+                write!(out, ",")?;
+            }
+            write!(out, "[")?;
+            write_ring(&polygon.exterior, out)?;
+            for hole in &polygon.holes {
+                write!(out, ",")?;
+                write_ring(hole, out)?;
+            }
+            write!(out, "]")?;
+        }
+        write!(out, "]}}}}")?;
+    }
+    writeln!(out, "]}}")?;
+    Ok(())
+}
+
+pub fn to_geojson_file(path: String, contours: &[Contour]) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    write_features(contours, &mut writer)?;
+
+    Ok(())
+}
+
+pub fn to_geojson_string(contours: &[Contour]) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_features(contours, &mut buffer)?;
+
+    Ok(String::from_utf8(buffer)?)
+}
+
+pub fn isobands_to_geojson_file(path: String, isobands: &[Isoband]) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    write_isoband_features(isobands, &mut writer)?;
+
+    Ok(())
+}
+
+pub fn isobands_to_geojson_string(isobands: &[Isoband]) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_isoband_features(isobands, &mut buffer)?;
+
+    Ok(String::from_utf8(buffer)?)
+}
@@ -0,0 +1,270 @@
+use crate::irap::Irap;
+use crate::utils::{point_in_polygon, shoelace};
+use std::collections::{HashMap, HashSet};
+
+/// Polygon with holes in world coordinates. Rings are closed, repeating the
+/// first point at the end.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BandPolygon {
+    pub exterior: Vec<[f64; 2]>,
+    pub holes: Vec<Vec<[f64; 2]>>,
+}
+
+/// Region where `lower <= value < upper`, and its area in world units.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Isoband {
+    pub lower: f64,
+    pub upper: f64,
+    pub area: f64,
+    pub polygons: Vec<BandPolygon>,
+}
+
+/// Vertex of a band piece: either a point of the triangulation or the point
+/// at a level on the triangle edge between two points. Points are lattice
+/// nodes, by value index, and cell centres, offset by the number of nodes.
+/// Keys are shared between neighbouring triangles, so the common edges of
+/// pieces cancel exactly.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Vertex {
+    Node(usize),
+    Crossing(usize, usize, bool),
+}
+
+#[derive(Clone, Copy)]
+struct BandVertex {
+    key: Vertex,
+    pos: [f64; 2],
+}
+
+impl Irap {
+    /// Filled bands between consecutive `levels`, each with its polygons and area.
+    ///
+    /// Every defined lattice cell is split into four triangles around its
+    /// centre, which takes the mean of the corners, and the surface is linear
+    /// on each. Band boundaries therefore cross the lattice edges where the
+    /// contours do and resolve saddle cells the same way, and areas are exact
+    /// for that surface. Cells with an undefined corner are excluded.
+    pub fn isobands(&self, levels: &[f64]) -> Vec<Isoband> {
+        let mut levels = levels.to_vec();
+        levels.sort_by(f64::total_cmp);
+        levels
+            .windows(2)
+            .map(|w| self.isoband(w[0], w[1]))
+            .collect()
+    }
+
+    fn isoband(&self, lower: f64, upper: f64) -> Isoband {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let cell_area = (self.header.xinc * self.header.yinc).abs();
+
+        let mut boundary: HashSet<(Vertex, Vertex)> = HashSet::new();
+        let mut positions: HashMap<Vertex, [f64; 2]> = HashMap::new();
+        let mut area = 0.0;
+
+        for i in 0..ncol.saturating_sub(1) {
+            for j in 0..nrow.saturating_sub(1) {
+                let nodes = [
+                    self.header.index(i, j),
+                    self.header.index(i + 1, j),
+                    self.header.index(i + 1, j + 1),
+                    self.header.index(i, j + 1),
+                ];
+                if nodes.iter().any(|&n| self.values[n].is_nan()) {
+                    continue;
+                }
+                let centre = self.values.len() + nodes[0];
+                for k in 0..4 {
+                    let triangle = [nodes[k], nodes[(k + 1) % 4], centre];
+                    let piece = self.band_piece(triangle, lower, upper);
+                    if piece.len() < 3 {
+                        continue;
+                    }
+
+                    let ring: Vec<[f64; 2]> = piece.iter().map(|v| v.pos).collect();
+                    area += shoelace(&ring) * cell_area;
+                    for k in 0..piece.len() {
+                        let from = piece[k].key;
+                        let to = piece[(k + 1) % piece.len()].key;
+                        if from == to {
+                            continue;
+                        }
+                        positions.insert(from, piece[k].pos);
+                        if !boundary.remove(&(to, from)) {
+                            boundary.insert((from, to));
+                        }
+                    }
+                }
+            }
+        }
+
+        let polygons = assemble(boundary, &positions)
+            .into_iter()
+            .map(|p| BandPolygon {
+                exterior: self.to_world(&p.exterior),
+                holes: p.holes.iter().map(|h| self.to_world(h)).collect(),
+            })
+            .collect();
+
+        Isoband {
+            lower,
+            upper,
+            area,
+            polygons,
+        }
+    }
+
+    /// Part of a counter-clockwise triangle where `lower <= value < upper`.
+    ///
+    /// The surface is linear on the triangle, so the part is convex, and its
+    /// vertices are the triangle's points inside the band and the level
+    /// crossings on its edges, in order around the triangle.
+    fn band_piece(&self, triangle: [usize; 3], lower: f64, upper: f64) -> Vec<BandVertex> {
+        let mut piece = Vec::with_capacity(5);
+        for k in 0..3 {
+            let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+            let (pos, va) = self.point(a);
+            let vb = self.point(b).1;
+            if lower <= va && va < upper {
+                piece.push(BandVertex {
+                    key: Vertex::Node(a),
+                    pos,
+                });
+            }
+            let mut crossings: Vec<(f64, BandVertex)> = [(lower, false), (upper, true)]
+                .into_iter()
+                .filter(|&(level, _)| (va >= level) != (vb >= level))
+                .map(|(level, is_upper)| {
+                    (
+                        (level - va) / (vb - va),
+                        self.crossing(a, b, level, is_upper),
+                    )
+                })
+                .collect();
+            crossings.sort_by(|x, y| x.0.total_cmp(&y.0));
+            piece.extend(crossings.into_iter().map(|(_, v)| v));
+        }
+        piece
+    }
+
+    /// Position in lattice coordinates and value of a point of the triangulation.
+    fn point(&self, id: usize) -> ([f64; 2], f64) {
+        let nrow = self.header.nrow as usize;
+        if id < self.values.len() {
+            let pos = [(id / nrow) as f64, (id % nrow) as f64];
+            return (pos, self.values[id] as f64);
+        }
+        let node = id - self.values.len();
+        let (i, j) = (node / nrow, node % nrow);
+        let value = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)]
+            .iter()
+            .map(|&(c, r)| self.values[self.header.index(c, r)] as f64)
+            .sum::<f64>()
+            / 4.0;
+        ([i as f64 + 0.5, j as f64 + 0.5], value)
+    }
+
+    /// Point at `level` on the triangle edge between points `a` and `b`,
+    /// computed from the lower id so both triangles sharing the edge agree.
+    fn crossing(&self, a: usize, b: usize, level: f64, upper: bool) -> BandVertex {
+        let (a, b) = (a.min(b), a.max(b));
+        let (pa, va) = self.point(a);
+        let (pb, vb) = self.point(b);
+        let t = ((level - va) / (vb - va)).clamp(0.0, 1.0);
+        BandVertex {
+            key: Vertex::Crossing(a, b, upper),
+            pos: [pa[0] + t * (pb[0] - pa[0]), pa[1] + t * (pb[1] - pa[1])],
+        }
+    }
+
+    fn to_world(&self, ring: &[[f64; 2]]) -> Vec<[f64; 2]> {
+        ring.iter()
+            .map(|&[col, row]| {
+                let (x, y) = self.header.node_xy(col, row);
+                [x, y]
+            })
+            .collect()
+    }
+}
+
+struct GridPolygon {
+    exterior: Vec<[f64; 2]>,
+    holes: Vec<Vec<[f64; 2]>>,
+}
+
+/// Link the remaining boundary edges into rings. Counter-clockwise rings are
+/// exteriors and clockwise rings are holes, which are assigned to the
+/// smallest exterior containing them.
+fn assemble(
+    boundary: HashSet<(Vertex, Vertex)>,
+    positions: &HashMap<Vertex, [f64; 2]>,
+) -> Vec<GridPolygon> {
+    let order = |v: &Vertex| match *v {
+        Vertex::Node(n) => (n, 0, 0),
+        Vertex::Crossing(a, b, upper) => (a, b, 1 + upper as usize),
+    };
+    let mut edges: Vec<(Vertex, Vertex)> = boundary.into_iter().collect();
+    edges.sort_by_key(|(from, to)| (order(from), order(to)));
+
+    let mut outgoing: HashMap<Vertex, Vec<Vertex>> = HashMap::new();
+    let mut starts = Vec::new();
+    for (from, to) in edges {
+        if starts.last() != Some(&from) {
+            starts.push(from);
+        }
+        outgoing.entry(from).or_default().push(to);
+    }
+
+    let mut exteriors: Vec<(Vec<[f64; 2]>, f64)> = Vec::new();
+    let mut holes: Vec<Vec<[f64; 2]>> = Vec::new();
+
+    for start in starts {
+        while outgoing.get(&start).is_some_and(|o| !o.is_empty()) {
+            let mut ring = vec![positions[&start]];
+            let mut current = start;
+            while let Some(next) = outgoing.get_mut(&current).and_then(|o| o.pop()) {
+                let pos = positions[&next];
+                if ring.last() != Some(&pos) {
+                    ring.push(pos);
+                }
+                current = next;
+                if current == start {
+                    break;
+                }
+            }
+            if ring.len() < 4 {
+                continue;
+            }
+            let area = shoelace(&ring);
+            if area > 0.0 {
+                exteriors.push((ring, area));
+            } else if area < 0.0 {
+                holes.push(ring);
+            }
+        }
+    }
+
+    let mut polygons: Vec<GridPolygon> = exteriors
+        .iter()
+        .map(|(ring, _)| GridPolygon {
+            exterior: ring.clone(),
+            holes: Vec::new(),
+        })
+        .collect();
+    for hole in holes {
+        let probe = [
+            (hole[0][0] + hole[1][0]) / 2.0,
+            (hole[0][1] + hole[1][1]) / 2.0,
+        ];
+        let owner = exteriors
+            .iter()
+            .enumerate()
+            .filter(|(_, (ring, _))| point_in_polygon(ring, probe))
+            .min_by(|a, b| a.1.1.total_cmp(&b.1.1))
+            .map(|(k, _)| k);
+        if let Some(k) = owner {
+            polygons[k].holes.push(hole);
+        }
+    }
+    polygons
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+/// A contour line in fractional lattice coordinates (col, row).
+pub struct GridLine {
+    pub points: Vec<[f64; 2]>,
+    pub closed: bool,
+}
+
+// Cell edges in counter-clockwise order starting at the bottom edge.
+const BOTTOM: usize = 0;
+const RIGHT: usize = 1;
+const TOP: usize = 2;
+const LEFT: usize = 3;
+
+/// Segments crossing a cell, indexed by which corners are at or above the
+/// level: bit 0 is (i, j), bit 1 (i + 1, j), bit 2 (i + 1, j + 1) and bit 3
+/// (i, j + 1). Saddles (5 and 10) are resolved separately.
+const SEGMENTS: [&[(usize, usize)]; 16] = [
+    &[],
+    &[(LEFT, BOTTOM)],
+    &[(BOTTOM, RIGHT)],
+    &[(LEFT, RIGHT)],
+    &[(RIGHT, TOP)],
+    &[],
+    &[(BOTTOM, TOP)],
+    &[(LEFT, TOP)],
+    &[(TOP, LEFT)],
+    &[(BOTTOM, TOP)],
+    &[],
+    &[(RIGHT, TOP)],
+    &[(RIGHT, LEFT)],
+    &[(BOTTOM, RIGHT)],
+    &[(LEFT, BOTTOM)],
+    &[],
+];
+
+// Segments cutting off the corners at (i, j) and (i + 1, j + 1), or at
+// (i + 1, j) and (i, j + 1).
+const CUT_A_AND_C: &[(usize, usize)] = &[(LEFT, BOTTOM), (RIGHT, TOP)];
+const CUT_B_AND_D: &[(usize, usize)] = &[(BOTTOM, RIGHT), (TOP, LEFT)];
+
+/// Marching squares over a lattice stored as `values[col * nrow + row]`.
+///
+/// Cells with an undefined corner produce no segments, which breaks lines at
+/// undefined nodes. Nodes equal to the level count as above it.
+pub fn trace(values: &[f32], ncol: usize, nrow: usize, level: f64) -> Vec<GridLine> {
+    let value = |i: usize, j: usize| values[i * nrow + j] as f64;
+    // Horizontal edge from (i, j) to (i + 1, j) and vertical from (i, j) to (i, j + 1).
+    let horizontal = |i: usize, j: usize| 2 * (i * nrow + j);
+    let vertical = |i: usize, j: usize| 2 * (i * nrow + j) + 1;
+
+    let mut segments: Vec<[usize; 2]> = Vec::new();
+    for i in 0..ncol.saturating_sub(1) {
+        for j in 0..nrow.saturating_sub(1) {
+            let corners = [
+                value(i, j),
+                value(i + 1, j),
+                value(i + 1, j + 1),
+                value(i, j + 1),
+            ];
+            if corners.iter().any(|v| v.is_nan()) {
+                continue;
+            }
+            let case = corners
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (k, &v)| acc | ((v >= level) as usize) << k);
+            let pairs = match case {
+                5 | 10 => {
+                    let center = corners.iter().sum::<f64>() / 4.0;
+                    // When the center is on the same side as the diagonal that
+                    // is above, that diagonal is connected and the other two
+                    // corners are cut off.
+                    match (case == 5, center >= level) {
+                        (true, true) | (false, false) => CUT_B_AND_D,
+                        _ => CUT_A_AND_C,
+                    }
+                }
+                _ => SEGMENTS[case],
+            };
+            let edges = [
+                horizontal(i, j),
+                vertical(i + 1, j),
+                horizontal(i, j + 1),
+                vertical(i, j),
+            ];
+            for &(from, to) in pairs {
+                segments.push([edges[from], edges[to]]);
+            }
+        }
+    }
+
+    let crossing = |edge: usize| -> [f64; 2] {
+        let node = edge / 2;
+        let (i, j) = (node / nrow, node % nrow);
+        let (i1, j1) = if edge.is_multiple_of(2) {
+            (i + 1, j)
+        } else {
+            (i, j + 1)
+        };
+        let (v0, v1) = (value(i, j), value(i1, j1));
+        let t = if v1 == v0 {
+            0.5
+        } else {
+            ((level - v0) / (v1 - v0)).clamp(0.0, 1.0)
+        };
+        [
+            i as f64 + t * (i1 - i) as f64,
+            j as f64 + t * (j1 - j) as f64,
+        ]
+    };
+
+    join(&segments)
+        .into_iter()
+        .map(|(edges, closed)| {
+            let mut points: Vec<[f64; 2]> = Vec::with_capacity(edges.len());
+            for edge in edges {
+                let p = crossing(edge);
+                if points.last() != Some(&p) {
+                    points.push(p);
+                }
+            }
+            GridLine { points, closed }
+        })
+        .filter(|line| line.points.len() > 1)
+        .collect()
+}
+
+/// Join segments sharing an edge into chains of edges. Every edge is shared
+/// by at most two segments, one from each neighbouring cell.
+fn join(segments: &[[usize; 2]]) -> Vec<(Vec<usize>, bool)> {
+    let mut by_edge: HashMap<usize, [usize; 2]> = HashMap::with_capacity(segments.len() * 2);
+    for (s, seg) in segments.iter().enumerate() {
+        for &edge in seg {
+            by_edge
+                .entry(edge)
+                .and_modify(|e| e[1] = s)
+                .or_insert([s, usize::MAX]);
+        }
+    }
+    let other_segment = |edge: usize, s: usize| -> Option<usize> {
+        let [a, b] = by_edge[&edge];
+        let next = if a == s { b } else { a };
+        (next != usize::MAX).then_some(next)
+    };
+
+    let mut visited = vec![false; segments.len()];
+    let mut lines = Vec::new();
+    for start in 0..segments.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+
+        // Walk forward from the end of the start segment
+        let mut forward = vec![segments[start][0], segments[start][1]];
+        let mut closed = false;
+        let (mut s, mut edge) = (start, segments[start][1]);
+        while let Some(next) = other_segment(edge, s) {
+            if next == start {
+                closed = true;
+                break;
+            }
+            visited[next] = true;
+            edge = if segments[next][0] == edge {
+                segments[next][1]
+            } else {
+                segments[next][0]
+            };
+            forward.push(edge);
+            s = next;
+        }
+
+        // Then backward from its start, unless the line is a ring
+        if !closed {
+            let mut backward = Vec::new();
+            let (mut s, mut edge) = (start, segments[start][0]);
+            while let Some(next) = other_segment(edge, s) {
+                visited[next] = true;
+                edge = if segments[next][0] == edge {
+                    segments[next][1]
+                } else {
+                    segments[next][0]
+                };
+                backward.push(edge);
+                s = next;
+            }
+            backward.reverse();
+            backward.extend(forward);
+            forward = backward;
+        }
+        lines.push((forward, closed));
+    }
+    lines
+}
@@ -0,0 +1,75 @@
+mod export_geojson;
+mod isobands;
+mod marching_squares;
+
+pub use export_geojson::{
+    isobands_to_geojson_file, isobands_to_geojson_string, to_geojson_file, to_geojson_string,
+};
+pub use isobands::{BandPolygon, Isoband};
+pub(crate) use marching_squares::trace;
+
+use crate::irap::{Irap, Polygons};
+
+/// A contour polyline in world coordinates.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Contour {
+    pub level: f64,
+    pub points: Vec<[f64; 2]>,
+    /// True when the line is a closed ring, in which case the first point is
+    /// repeated at the end.
+    pub closed: bool,
+}
+
+impl Irap {
+    /// Contour lines at each of `levels` using marching squares.
+    ///
+    /// Lines are broken at undefined nodes, and saddle cells are resolved by
+    /// the mean of the four corners.
+    pub fn contours(&self, levels: &[f64]) -> Vec<Contour> {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let mut contours = Vec::new();
+        for &level in levels {
+            for line in trace(&self.values, ncol, nrow, level) {
+                let points = line
+                    .points
+                    .iter()
+                    .map(|&[col, row]| {
+                        let (x, y) = self.header.node_xy(col, row);
+                        [x, y]
+                    })
+                    .collect();
+                contours.push(Contour {
+                    level,
+                    points,
+                    closed: line.closed,
+                });
+            }
+        }
+        contours
+    }
+}
+
+/// Contours as polygons, with the level as z value.
+pub fn to_polygons(contours: &[Contour]) -> Polygons {
+    Polygons {
+        polygons: contours
+            .iter()
+            .map(|c| c.points.iter().map(|&[x, y]| [x, y, c.level]).collect())
+            .collect(),
+    }
+}
+
+/// Isoband rings as polygons, with the lower level of the band as z value.
+/// Exteriors are counter-clockwise and holes clockwise.
+pub fn isobands_to_polygons(isobands: &[Isoband]) -> Polygons {
+    let mut polygons = Vec::new();
+    for band in isobands {
+        for polygon in &band.polygons {
+            for ring in std::iter::once(&polygon.exterior).chain(&polygon.holes) {
+                polygons.push(ring.iter().map(|&[x, y]| [x, y, band.lower]).collect());
+            }
+        }
+    }
+    Polygons { polygons }
+}
@@ -0,0 +1,169 @@
+use crate::irap::Irap;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Velocity of one layer of a layer-cake model, in depth units per second of
+/// one-way time.
+#[derive(Clone, Copy, Debug)]
+pub enum Velocity<'a> {
+    /// Constant interval velocity.
+    Interval(f64),
+    /// Interval velocity at each node, on the lattice of the surfaces.
+    Surface(&'a Irap),
+    /// Instantaneous velocity `v0 + k * z`, increasing linearly with depth.
+    Linear { v0: f64, k: f64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeUnit {
+    Milliseconds,
+    Seconds,
+}
+
+/// How times are measured on the time surfaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeConvention {
+    pub unit: TimeUnit,
+    /// Whether times are two-way rather than one-way travel times.
+    pub two_way: bool,
+}
+
+impl Default for TimeConvention {
+    /// Two-way time in milliseconds.
+    fn default() -> Self {
+        TimeConvention {
+            unit: TimeUnit::Milliseconds,
+            two_way: true,
+        }
+    }
+}
+
+impl TimeConvention {
+    /// One-way seconds per unit of time.
+    fn seconds(&self) -> f64 {
+        let unit = match self.unit {
+            TimeUnit::Milliseconds => 1e-3,
+            TimeUnit::Seconds => 1.0,
+        };
+        if self.two_way { unit / 2.0 } else { unit }
+    }
+}
+
+/// Depth surfaces from time surfaces in a layer-cake model.
+///
+/// `times` are the bases of consecutive layers from the top down, the first
+/// layer starting at zero time and depth, and `velocities` has one entry per
+/// layer. Undefined nodes, on a time surface or a velocity surface, make the
+/// node undefined on that surface and all those below it.
+pub fn time_to_depth(
+    times: &[Irap],
+    velocities: &[Velocity],
+    convention: TimeConvention,
+) -> Result<Vec<Irap>> {
+    check_model(times, velocities)?;
+    let seconds = convention.seconds();
+    Ok(convert(times, |idx, column| {
+        let (mut t_top, mut z_top) = (0.0, 0.0);
+        for (k, velocity) in velocities.iter().enumerate() {
+            let t = times[k].values[idx] as f64 * seconds;
+            z_top = layer_depth(velocity, idx, z_top, t - t_top);
+            t_top = t;
+            column[k] = z_top as f32;
+        }
+    }))
+}
+
+/// Time surfaces from depth surfaces, the inverse of `time_to_depth`.
+pub fn depth_to_time(
+    depths: &[Irap],
+    velocities: &[Velocity],
+    convention: TimeConvention,
+) -> Result<Vec<Irap>> {
+    check_model(depths, velocities)?;
+    let seconds = convention.seconds();
+    Ok(convert(depths, |idx, column| {
+        let (mut t_top, mut z_top) = (0.0, 0.0);
+        for (k, velocity) in velocities.iter().enumerate() {
+            let z = depths[k].values[idx] as f64;
+            t_top += layer_time(velocity, idx, z_top, z - z_top);
+            z_top = z;
+            column[k] = (t_top / seconds) as f32;
+        }
+    }))
+}
+
+fn check_model(surfaces: &[Irap], velocities: &[Velocity]) -> Result<()> {
+    if surfaces.len() != velocities.len() {
+        return Err(format!(
+            "Expected one velocity per surface, got {} surfaces and {} velocities",
+            surfaces.len(),
+            velocities.len()
+        )
+        .into());
+    }
+    for (k, (surface, velocity)) in surfaces.iter().zip(velocities).enumerate() {
+        if !surface.header.same_lattice(&surfaces[0].header) {
+            return Err(format!("Surface {} is not on the same lattice as surface 0", k).into());
+        }
+        match *velocity {
+            Velocity::Interval(v) if !(v > 0.0 && v.is_finite()) => {
+                return Err(format!("Invalid interval velocity in layer {}: {}", k, v).into());
+            }
+            Velocity::Surface(v) if !v.header.same_lattice(&surfaces[0].header) => {
+                return Err(format!(
+                    "Velocity surface of layer {} is not on the same lattice as the surfaces",
+                    k
+                )
+                .into());
+            }
+            Velocity::Linear { v0, k: gradient }
+                if !(v0 > 0.0 && v0.is_finite() && gradient.is_finite()) =>
+            {
+                return Err(format!(
+                    "Invalid linear velocity in layer {}: v0 = {}, k = {}",
+                    k, v0, gradient
+                )
+                .into());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Surfaces on the lattice of `surfaces`, with `node` filling in the values
+/// of all of them at a node.
+fn convert(surfaces: &[Irap], node: impl Fn(usize, &mut [f32])) -> Vec<Irap> {
+    let mut converted: Vec<Irap> = surfaces.to_vec();
+    let mut column = vec![0.0; surfaces.len()];
+    for idx in 0..surfaces.first().map_or(0, |s| s.values.len()) {
+        node(idx, &mut column);
+        for (surface, &v) in converted.iter_mut().zip(&column) {
+            surface.values[idx] = v;
+        }
+    }
+    converted
+}
+
+/// Depth at the base of a layer with top at `z_top` and one-way thickness
+/// `dt` seconds.
+fn layer_depth(velocity: &Velocity, idx: usize, z_top: f64, dt: f64) -> f64 {
+    match *velocity {
+        Velocity::Interval(v) => z_top + v * dt,
+        Velocity::Surface(v) => z_top + v.values[idx] as f64 * dt,
+        Velocity::Linear { v0, k: 0.0 } => z_top + v0 * dt,
+        // Solution of dz/dt = v0 + k * z starting from z_top
+        Velocity::Linear { v0, k } => z_top + (z_top + v0 / k) * (k * dt).exp_m1(),
+    }
+}
+
+/// One-way time in seconds through a layer with top at `z_top` and thickness
+/// `dz`, the inverse of `layer_depth`.
+fn layer_time(velocity: &Velocity, idx: usize, z_top: f64, dz: f64) -> f64 {
+    match *velocity {
+        Velocity::Interval(v) => dz / v,
+        Velocity::Surface(v) => dz / v.values[idx] as f64,
+        Velocity::Linear { v0, k: 0.0 } => dz / v0,
+        Velocity::Linear { v0, k } => (dz / (z_top + v0 / k)).ln_1p() / k,
+    }
+}
@@ -0,0 +1,87 @@
+use crate::irap::Irap;
+
+/// First and second partial derivatives at a node along the lattice axes:
+/// u runs along columns (spacing xinc) and v along rows (spacing yinc).
+pub struct Partials {
+    pub zu: f64,
+    pub zv: f64,
+    pub zuu: f64,
+    pub zvv: f64,
+    pub zuv: f64,
+}
+
+fn value(irap: &Irap, i: isize, j: isize) -> f64 {
+    let ncol = irap.header.ncol as isize;
+    let nrow = irap.header.nrow as isize;
+    if i < 0 || j < 0 || i >= ncol || j >= nrow {
+        return f64::NAN;
+    }
+    irap.values[irap.header.index(i as usize, j as usize)] as f64
+}
+
+/// First derivative from the values before, at and after a node. Falls back
+/// to a one-sided difference when one neighbour is undefined.
+fn first(before: f64, at: f64, after: f64, h: f64) -> f64 {
+    match (before.is_nan(), after.is_nan()) {
+        (false, false) => (after - before) / (2.0 * h),
+        (true, false) => (after - at) / h,
+        (false, true) => (at - before) / h,
+        (true, true) => f64::NAN,
+    }
+}
+
+/// Second derivative along one axis, using the centred stencil when possible
+/// and a shifted one when a neighbour is undefined.
+fn second(values: [f64; 5], h: f64) -> f64 {
+    let [m2, m1, at, p1, p2] = values;
+    let stencil = if !m1.is_nan() && !p1.is_nan() {
+        m1 - 2.0 * at + p1
+    } else if !p1.is_nan() && !p2.is_nan() {
+        at - 2.0 * p1 + p2
+    } else if !m1.is_nan() && !m2.is_nan() {
+        m2 - 2.0 * m1 + at
+    } else {
+        f64::NAN
+    };
+    stencil / (h * h)
+}
+
+pub fn partials(irap: &Irap, col: usize, row: usize) -> Option<Partials> {
+    let (i, j) = (col as isize, row as isize);
+    let at = value(irap, i, j);
+    if at.is_nan() {
+        return None;
+    }
+    let (hu, hv) = (irap.header.xinc, irap.header.yinc);
+    let along_u = |k: isize| value(irap, i + k, j);
+    let along_v = |k: isize| value(irap, i, j + k);
+
+    let zu = first(along_u(-1), at, along_u(1), hu);
+    let zv = first(along_v(-1), at, along_v(1), hv);
+    let zuu = second([along_u(-2), along_u(-1), at, along_u(1), along_u(2)], hu);
+    let zvv = second([along_v(-2), along_v(-1), at, along_v(1), along_v(2)], hv);
+
+    // Mixed derivative averaged over the defined quadrants around the node
+    let mut sum = 0.0;
+    let mut count = 0;
+    for (di, dj) in [(1, 1), (-1, 1), (-1, -1), (1, -1)] {
+        let q = value(irap, i + di, j + dj) - value(irap, i + di, j) - value(irap, i, j + dj) + at;
+        if !q.is_nan() {
+            sum += q * (di * dj) as f64;
+            count += 1;
+        }
+    }
+    let zuv = if count > 0 {
+        sum / (count as f64 * hu * hv)
+    } else {
+        f64::NAN
+    };
+
+    Some(Partials {
+        zu,
+        zv,
+        zuu,
+        zvv,
+        zuv,
+    })
+}
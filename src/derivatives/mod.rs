@@ -0,0 +1,81 @@
+mod finite_differences;
+
+use crate::irap::Irap;
+use finite_differences::{Partials, partials};
+use rayon::prelude::*;
+
+/// Kind of curvature computed by `curvature`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curvature {
+    Mean,
+    Gaussian,
+    /// Largest principal curvature.
+    MostPositive,
+    /// Smallest principal curvature.
+    MostNegative,
+}
+
+fn map_nodes(irap: &Irap, f: impl Fn(&Partials) -> f64 + Sync) -> Irap {
+    let nrow = irap.header.nrow as usize;
+    let values = (0..irap.values.len())
+        .into_par_iter()
+        .map(|idx| match partials(irap, idx / nrow, idx % nrow) {
+            Some(p) => f(&p) as f32,
+            None => f32::NAN,
+        })
+        .collect();
+    Irap {
+        header: irap.header.clone(),
+        values,
+    }
+}
+
+/// Gradient in world coordinates, rotating the lattice derivatives by the
+/// header rotation.
+fn world_gradient(irap: &Irap, p: &Partials) -> (f64, f64) {
+    let (sin, cos) = irap.header.rot.to_radians().sin_cos();
+    (p.zu * cos - p.zv * sin, p.zu * sin + p.zv * cos)
+}
+
+/// Dip magnitude in degrees from horizontal.
+pub fn slope(irap: &Irap) -> Irap {
+    map_nodes(irap, |p| {
+        let (zx, zy) = world_gradient(irap, p);
+        zx.hypot(zy).atan().to_degrees()
+    })
+}
+
+/// Geographic azimuth, in degrees clockwise from north, of the direction in
+/// which values increase. On a depth surface this is the dip azimuth.
+/// Undefined where the surface is flat.
+pub fn aspect(irap: &Irap) -> Irap {
+    map_nodes(irap, |p| {
+        let (zx, zy) = world_gradient(irap, p);
+        if zx == 0.0 && zy == 0.0 {
+            return f64::NAN;
+        }
+        zx.atan2(zy).to_degrees().rem_euclid(360.0)
+    })
+}
+
+/// Curvature of the surface in 1 / world units, positive where the surface
+/// is concave up in the direction of increasing values.
+///
+/// Curvatures do not depend on the orientation of the axes, so they are
+/// computed directly from the lattice derivatives.
+pub fn curvature(irap: &Irap, kind: Curvature) -> Irap {
+    map_nodes(irap, |p| {
+        let g = 1.0 + p.zu * p.zu + p.zv * p.zv;
+        let mean = ((1.0 + p.zv * p.zv) * p.zuu - 2.0 * p.zu * p.zv * p.zuv
+            + (1.0 + p.zu * p.zu) * p.zvv)
+            / (2.0 * g.powf(1.5));
+        let gaussian = (p.zuu * p.zvv - p.zuv * p.zuv) / (g * g);
+        let spread = (mean * mean - gaussian).max(0.0).sqrt();
+        match kind {
+            Curvature::Mean => mean,
+            Curvature::Gaussian => gaussian,
+            Curvature::MostPositive => mean + spread,
+            Curvature::MostNegative => mean - spread,
+        }
+    })
+}
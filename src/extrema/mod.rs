@@ -0,0 +1,195 @@
+use crate::irap::Irap;
+use crate::utils::find_root;
+
+/// Kind of extremum searched for by `extrema`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtremumKind {
+    Maximum,
+    Minimum,
+    /// Node whose eight neighbours alternate at least twice between above
+    /// and below it.
+    Saddle,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extremum {
+    pub kind: ExtremumKind,
+    pub col: usize,
+    pub row: usize,
+    pub x: f64,
+    pub y: f64,
+    pub value: f64,
+    /// Height of a maximum above the highest col connecting it to a higher
+    /// maximum, or depth of a minimum likewise. The most prominent maximum
+    /// or minimum is measured against the opposite extreme of the surface.
+    /// NaN for saddles.
+    pub prominence: f64,
+}
+
+const NEIGHBOURS: [(isize, isize); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+impl Irap {
+    /// Local extrema of `kind`, ignoring undefined nodes.
+    ///
+    /// A maximum is higher than every defined node within `window` nodes
+    /// along each axis, with ties going to the lowest index, and has a
+    /// positive prominence of at least `min_prominence`; minima likewise.
+    /// Saddles are found from the eight neighbours only, and `min_prominence`
+    /// does not apply to them. Results are sorted by decreasing prominence.
+    pub fn extrema(&self, kind: ExtremumKind, min_prominence: f64, window: usize) -> Vec<Extremum> {
+        let nrow = self.header.nrow as usize;
+        let sign = match kind {
+            ExtremumKind::Maximum => 1.0,
+            ExtremumKind::Minimum => -1.0,
+            ExtremumKind::Saddle => return self.saddles(),
+        };
+        let value = |idx: usize| sign * self.values[idx];
+        let prominence = self.prominence(sign);
+
+        let mut found: Vec<Extremum> = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .filter(|&idx| prominence[idx] > 0.0 && prominence[idx] >= min_prominence)
+            .filter(|&idx| {
+                let (col, row) = (idx / nrow, idx % nrow);
+                self.window_nodes(col, row, window).all(|n| {
+                    let (a, b) = (value(idx), value(n));
+                    b.is_nan() || a > b || (a == b && idx <= n)
+                })
+            })
+            .map(|idx| self.extremum(kind, idx, prominence[idx]))
+            .collect();
+        found.sort_by(|a, b| b.prominence.total_cmp(&a.prominence));
+        found
+    }
+
+    fn extremum(&self, kind: ExtremumKind, idx: usize, prominence: f64) -> Extremum {
+        let nrow = self.header.nrow as usize;
+        let (col, row) = (idx / nrow, idx % nrow);
+        let (x, y) = self.header.node_xy(col as f64, row as f64);
+        Extremum {
+            kind,
+            col,
+            row,
+            x,
+            y,
+            value: self.values[idx] as f64,
+            prominence,
+        }
+    }
+
+    /// Indices of the nodes within `window` of (col, row), excluding itself.
+    fn window_nodes(&self, col: usize, row: usize, window: usize) -> impl Iterator<Item = usize> {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let cols = col.saturating_sub(window)..=(col + window).min(ncol - 1);
+        cols.flat_map(move |c| {
+            (row.saturating_sub(window)..=(row + window).min(nrow - 1))
+                .filter(move |&r| (c, r) != (col, row))
+                .map(move |r| c * nrow + r)
+        })
+    }
+
+    /// Prominence of each peak met when sweeping the nodes from high to low
+    /// `sign * value`, NaN for other nodes.
+    ///
+    /// Peaks start 8-connected regions. When regions meet, all but the one
+    /// with the highest peak end there, and their prominence is the drop from
+    /// their peak to the meeting node.
+    fn prominence(&self, sign: f32) -> Vec<f64> {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let value = |idx: usize| (sign * self.values[idx]) as f64;
+
+        let mut order: Vec<usize> = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .collect();
+        order.sort_by(|&a, &b| value(b).total_cmp(&value(a)).then(a.cmp(&b)));
+
+        let mut prominence = vec![f64::NAN; self.values.len()];
+        let mut parent: Vec<usize> = (0..self.values.len()).collect();
+        // Peak of each region, stored at its root
+        let mut peak: Vec<usize> = (0..self.values.len()).collect();
+        let mut processed = vec![false; self.values.len()];
+
+        for &idx in &order {
+            processed[idx] = true;
+            let (col, row) = (idx / nrow, idx % nrow);
+            let mut roots: Vec<usize> = Vec::new();
+            for (dc, dr) in NEIGHBOURS {
+                let (Some(c), Some(r)) = (col.checked_add_signed(dc), row.checked_add_signed(dr))
+                else {
+                    continue;
+                };
+                if c < ncol && r < nrow && processed[c * nrow + r] {
+                    let root = find_root(&mut parent, c * nrow + r);
+                    if !roots.contains(&root) {
+                        roots.push(root);
+                    }
+                }
+            }
+            // Peaks earlier in the sweep are higher, or equal with a lower index
+            let Some(&highest) = roots.iter().min_by(|&&a, &&b| {
+                value(peak[b])
+                    .total_cmp(&value(peak[a]))
+                    .then(peak[a].cmp(&peak[b]))
+            }) else {
+                peak[idx] = idx;
+                continue;
+            };
+            for &r in &roots {
+                if r != highest {
+                    prominence[peak[r]] = value(peak[r]) - value(idx);
+                    parent[r] = highest;
+                }
+            }
+            parent[idx] = highest;
+        }
+
+        if let Some(&lowest) = order.last() {
+            for &idx in &order {
+                if parent[idx] == idx {
+                    prominence[peak[idx]] = value(peak[idx]) - value(lowest);
+                }
+            }
+        }
+        prominence
+    }
+
+    fn saddles(&self) -> Vec<Extremum> {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        (0..self.values.len())
+            .filter(|&idx| {
+                let (col, row) = (idx / nrow, idx % nrow);
+                let v = self.values[idx];
+                if v.is_nan() || col == 0 || row == 0 || col + 1 == ncol || row + 1 == nrow {
+                    return false;
+                }
+                let above: Vec<Option<bool>> = NEIGHBOURS
+                    .iter()
+                    .map(|&(dc, dr)| {
+                        let n = self.values[self
+                            .header
+                            .index(col.wrapping_add_signed(dc), row.wrapping_add_signed(dr))];
+                        (!n.is_nan()).then_some(n > v)
+                    })
+                    .collect();
+                if above.iter().any(|a| a.is_none()) {
+                    return false;
+                }
+                let changes = (0..8).filter(|&k| above[k] != above[(k + 1) % 8]).count();
+                changes >= 4
+            })
+            .map(|idx| self.extremum(ExtremumKind::Saddle, idx, f64::NAN))
+            .collect()
+    }
+}
@@ -0,0 +1,193 @@
+use crate::irap::Irap;
+use crate::spatial::KdTree;
+use crate::utils::neighbours;
+use rayon::prelude::*;
+use std::collections::VecDeque;
+
+/// How undefined nodes are given values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FillMethod {
+    /// Value of the closest defined node.
+    Nearest,
+    /// Inverse distance weighting of the closest defined nodes.
+    InverseDistance { power: f64, max_neighbours: usize },
+    /// Harmonic inpainting: the filled values solve Laplace's equation with
+    /// the surrounding defined nodes as boundary values.
+    Laplace,
+}
+
+/// Which undefined nodes are candidates for filling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillExtent {
+    /// Only holes completely enclosed by defined nodes, i.e. undefined
+    /// regions that do not reach the edge of the lattice.
+    Enclosed,
+    /// All undefined nodes, extrapolating out to the edge of the lattice.
+    Extrapolate,
+}
+
+const LAPLACE_OMEGA: f64 = 1.8;
+const LAPLACE_MAX_ITERATIONS: usize = 10000;
+const LAPLACE_TOLERANCE: f64 = 1e-6;
+
+impl Irap {
+    /// Fill undefined nodes, returning the filled surface and a mask of the
+    /// nodes that were filled.
+    ///
+    /// Only nodes within `max_distance` (world units) of a defined node are
+    /// filled; pass `f64::INFINITY` for no limit.
+    pub fn fill_undefined(
+        &self,
+        method: &FillMethod,
+        max_distance: f64,
+        extent: FillExtent,
+    ) -> (Irap, Vec<bool>) {
+        let header = &self.header;
+        let nrow = header.nrow as usize;
+
+        let mut candidates: Vec<bool> = self.values.iter().map(|v| v.is_nan()).collect();
+        if extent == FillExtent::Enclosed {
+            self.exclude_open_regions(&mut candidates);
+        }
+
+        let defined: Vec<usize> = (0..self.values.len())
+            .filter(|&i| !self.values[i].is_nan())
+            .collect();
+        if defined.is_empty() {
+            return (self.clone(), vec![false; self.values.len()]);
+        }
+        let node_xy = |idx: usize| header.node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+        let points: Vec<[f64; 2]> = defined
+            .iter()
+            .map(|&i| {
+                let (x, y) = node_xy(i);
+                [x, y]
+            })
+            .collect();
+        let tree = KdTree::new(&points);
+
+        let neighbours = match method {
+            FillMethod::InverseDistance { max_neighbours, .. } => *max_neighbours,
+            _ => 1,
+        };
+        let filled_values: Vec<Option<f32>> = (0..self.values.len())
+            .into_par_iter()
+            .map(|idx| {
+                if !candidates[idx] {
+                    return None;
+                }
+                let (x, y) = node_xy(idx);
+                let found = tree.k_nearest(x, y, neighbours, max_distance);
+                if found.is_empty() {
+                    return None;
+                }
+                let value = match method {
+                    FillMethod::InverseDistance { power, .. } => {
+                        let (sum, weights) = found.iter().fold((0.0, 0.0), |(s, w), &(i, d2)| {
+                            let weight = d2.powf(-0.5 * power);
+                            (s + weight * self.values[defined[i]] as f64, w + weight)
+                        });
+                        sum / weights
+                    }
+                    _ => self.values[defined[found[0].0]] as f64,
+                };
+                Some(value as f32)
+            })
+            .collect();
+
+        let mut values = self.values.clone();
+        let mut mask = vec![false; values.len()];
+        for (idx, filled) in filled_values.into_iter().enumerate() {
+            if let Some(v) = filled {
+                values[idx] = v;
+                mask[idx] = true;
+            }
+        }
+
+        if *method == FillMethod::Laplace {
+            self.solve_laplace(&mut values, &mask);
+        }
+
+        (
+            Irap {
+                header: header.clone(),
+                values,
+            },
+            mask,
+        )
+    }
+
+    /// Remove undefined regions connected to the lattice edge from the candidates.
+    fn exclude_open_regions(&self, candidates: &mut [bool]) {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let mut queue = VecDeque::new();
+        for col in 0..ncol {
+            for row in 0..nrow {
+                let on_edge = col == 0 || row == 0 || col == ncol - 1 || row == nrow - 1;
+                let idx = col * nrow + row;
+                if on_edge && candidates[idx] {
+                    candidates[idx] = false;
+                    queue.push_back((col, row));
+                }
+            }
+        }
+        while let Some((col, row)) = queue.pop_front() {
+            for (c, r) in neighbours(col, row, ncol, nrow) {
+                let idx = c * nrow + r;
+                if candidates[idx] {
+                    candidates[idx] = false;
+                    queue.push_back((c, r));
+                }
+            }
+        }
+    }
+
+    /// Successive over-relaxation of the filled nodes towards the discrete
+    /// Laplace equation. Neighbours that are still undefined or outside the
+    /// lattice are left out, giving zero flux across those boundaries.
+    fn solve_laplace(&self, values: &mut [f32], filled: &[bool]) {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let wu = 1.0 / (self.header.xinc * self.header.xinc);
+        let wv = 1.0 / (self.header.yinc * self.header.yinc);
+
+        let (lo, hi) = values
+            .iter()
+            .filter(|v| !v.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v as f64), hi.max(v as f64))
+            });
+        let tolerance = LAPLACE_TOLERANCE * (hi - lo).max(f64::EPSILON);
+
+        let nodes: Vec<usize> = (0..values.len()).filter(|&i| filled[i]).collect();
+        let mut work: Vec<f64> = values.iter().map(|&v| v as f64).collect();
+        for _ in 0..LAPLACE_MAX_ITERATIONS {
+            let mut max_change: f64 = 0.0;
+            for &idx in &nodes {
+                let (col, row) = (idx / nrow, idx % nrow);
+                let mut sum = 0.0;
+                let mut weights = 0.0;
+                for (c, r) in neighbours(col, row, ncol, nrow) {
+                    let v = work[c * nrow + r];
+                    if !v.is_nan() {
+                        let w = if c != col { wu } else { wv };
+                        sum += w * v;
+                        weights += w;
+                    }
+                }
+                if weights > 0.0 {
+                    let change = LAPLACE_OMEGA * (sum / weights - work[idx]);
+                    work[idx] += change;
+                    max_change = max_change.max(change.abs());
+                }
+            }
+            if max_change <= tolerance {
+                break;
+            }
+        }
+        for &idx in &nodes {
+            values[idx] = work[idx] as f32;
+        }
+    }
+}
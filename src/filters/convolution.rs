@@ -0,0 +1,68 @@
+use crate::irap::IrapHeader;
+use rayon::prelude::*;
+
+/// Normalized convolution with a separable kernel. Undefined nodes get zero
+/// weight, and each result is divided by the sum of weights that actually
+/// contributed, so holes do not spread and edges are not pulled to zero.
+pub fn separable(
+    values: &[f32],
+    header: &IrapHeader,
+    kernel_u: &[f64],
+    kernel_v: &[f64],
+) -> Vec<f32> {
+    let ncol = header.ncol as usize;
+    let nrow = header.nrow as usize;
+
+    let mut weighted: Vec<f64> = values
+        .iter()
+        .map(|&v| if v.is_nan() { 0.0 } else { v as f64 })
+        .collect();
+    let mut weights: Vec<f64> = values
+        .iter()
+        .map(|v| if v.is_nan() { 0.0 } else { 1.0 })
+        .collect();
+
+    // Rows are contiguous within a column, so filter along v first, then
+    // transpose to filter along u.
+    for data in [&mut weighted, &mut weights] {
+        convolve_lines(data, nrow, kernel_v);
+        let mut transposed = transpose(data, ncol, nrow);
+        convolve_lines(&mut transposed, ncol, kernel_u);
+        *data = transpose(&transposed, nrow, ncol);
+    }
+
+    values
+        .iter()
+        .zip(weighted.iter().zip(&weights))
+        .map(|(&v, (&s, &w))| {
+            if v.is_nan() || w <= 0.0 {
+                f32::NAN
+            } else {
+                (s / w) as f32
+            }
+        })
+        .collect()
+}
+
+fn convolve_lines(data: &mut [f64], len: usize, kernel: &[f64]) {
+    let half = kernel.len() / 2;
+    data.par_chunks_mut(len).for_each(|line| {
+        let source = line.to_vec();
+        for (k, out) in line.iter_mut().enumerate() {
+            let lo = k.saturating_sub(half);
+            let hi = (k + half).min(len - 1);
+            *out = (lo..=hi).map(|m| source[m] * kernel[m + half - k]).sum();
+        }
+    });
+}
+
+/// Transpose a lattice stored as `ncol` lines of `nrow` values.
+fn transpose(data: &[f64], ncol: usize, nrow: usize) -> Vec<f64> {
+    let mut out = vec![0.0; data.len()];
+    for i in 0..ncol {
+        for j in 0..nrow {
+            out[j * ncol + i] = data[i * nrow + j];
+        }
+    }
+    out
+}
@@ -0,0 +1,178 @@
+mod convolution;
+
+use crate::irap::{Irap, IrapHeader};
+use convolution::separable;
+use rayon::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Filter size, either in nodes or in world units. World sizes are converted
+/// to nodes separately along each lattice axis using xinc and yinc.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Radius {
+    Nodes(f64),
+    World(f64),
+}
+
+impl Radius {
+    fn along_axes(&self, header: &IrapHeader) -> Result<(f64, f64)> {
+        match *self {
+            Radius::Nodes(n) | Radius::World(n) if n < 0.0 || !n.is_finite() => {
+                Err(format!("Invalid filter size: {}", n).into())
+            }
+            Radius::Nodes(n) => Ok((n, n)),
+            Radius::World(r) => {
+                for inc in [header.xinc, header.yinc] {
+                    if inc == 0.0 || !inc.is_finite() {
+                        return Err(format!("Invalid lattice increment: {}", inc).into());
+                    }
+                }
+                Ok((r / header.xinc.abs(), r / header.yinc.abs()))
+            }
+        }
+    }
+
+    /// Half widths in nodes, limited to the size of the lattice as windows
+    /// never reach further.
+    fn half_widths(&self, header: &IrapHeader) -> Result<(usize, usize)> {
+        let (ru, rv) = self.along_axes(header)?;
+        Ok((
+            ru.round().min(header.ncol as f64) as usize,
+            rv.round().min(header.nrow as f64) as usize,
+        ))
+    }
+}
+
+/// Truncate the Gaussian kernel at this many standard deviations.
+const GAUSSIAN_TRUNCATE: f64 = 3.0;
+
+/// Scale factor making the median absolute deviation a consistent estimate
+/// of the standard deviation for normally distributed values.
+const MAD_SCALE: f64 = 1.4826;
+
+impl Irap {
+    /// Mean over a rectangular window of half width `radius`.
+    ///
+    /// Undefined nodes are left out and the remaining weights renormalized;
+    /// undefined nodes stay undefined.
+    pub fn mean_filter(&self, radius: Radius) -> Result<Irap> {
+        let (ru, rv) = radius.half_widths(&self.header)?;
+        Ok(self.with_values(separable(
+            &self.values,
+            &self.header,
+            &vec![1.0; 2 * ru + 1],
+            &vec![1.0; 2 * rv + 1],
+        )))
+    }
+
+    /// Gaussian smoothing with standard deviation `sigma`, truncated at three
+    /// standard deviations. Undefined nodes are handled as in `mean_filter`.
+    /// The standard deviation must be positive.
+    pub fn gaussian_filter(&self, sigma: Radius) -> Result<Irap> {
+        let (su, sv) = sigma.along_axes(&self.header)?;
+        if su == 0.0 || sv == 0.0 {
+            return Err("The standard deviation must be positive".into());
+        }
+        Ok(self.with_values(separable(
+            &self.values,
+            &self.header,
+            &gaussian_kernel(su, self.header.ncol as usize),
+            &gaussian_kernel(sv, self.header.nrow as usize),
+        )))
+    }
+
+    /// Median of the defined nodes in a rectangular window of half width `radius`.
+    pub fn median_filter(&self, radius: Radius) -> Result<Irap> {
+        let (ru, rv) = radius.half_widths(&self.header)?;
+        let values = (0..self.values.len())
+            .into_par_iter()
+            .map(|idx| {
+                if self.values[idx].is_nan() {
+                    return f32::NAN;
+                }
+                let mut window = self.window(idx, ru, rv);
+                median(&mut window) as f32
+            })
+            .collect();
+        Ok(self.with_values(values))
+    }
+
+    /// Replace outliers by the median of their window. A node is an outlier
+    /// when it deviates from the window median by more than `threshold`
+    /// robust standard deviations, estimated from the median absolute
+    /// deviation of the window.
+    pub fn despike(&self, radius: Radius, threshold: f64) -> Result<Irap> {
+        let (ru, rv) = radius.half_widths(&self.header)?;
+        let values = (0..self.values.len())
+            .into_par_iter()
+            .map(|idx| {
+                let v = self.values[idx];
+                if v.is_nan() {
+                    return v;
+                }
+                let mut window = self.window(idx, ru, rv);
+                let med = median(&mut window);
+                let mut deviations: Vec<f64> = window.iter().map(|w| (w - med).abs()).collect();
+                let spread = MAD_SCALE * median(&mut deviations);
+                if (v as f64 - med).abs() > threshold * spread {
+                    med as f32
+                } else {
+                    v
+                }
+            })
+            .collect();
+        Ok(self.with_values(values))
+    }
+
+    /// Defined values in the window around node `idx`.
+    fn window(&self, idx: usize, ru: usize, rv: usize) -> Vec<f64> {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let (col, row) = (idx / nrow, idx % nrow);
+        let mut window = Vec::with_capacity((2 * ru + 1) * (2 * rv + 1));
+        for i in col.saturating_sub(ru)..=(col + ru).min(ncol - 1) {
+            for j in row.saturating_sub(rv)..=(row + rv).min(nrow - 1) {
+                let v = self.values[i * nrow + j];
+                if !v.is_nan() {
+                    window.push(v as f64);
+                }
+            }
+        }
+        window
+    }
+
+    fn with_values(&self, values: Vec<f32>) -> Irap {
+        Irap {
+            header: self.header.clone(),
+            values,
+        }
+    }
+}
+
+/// Gaussian weights, reaching at most `max_half` nodes from the centre.
+fn gaussian_kernel(sigma: f64, max_half: usize) -> Vec<f64> {
+    if sigma <= 0.0 {
+        return vec![1.0];
+    }
+    let half = (GAUSSIAN_TRUNCATE * sigma).ceil().min(max_half as f64) as isize;
+    (-half..=half)
+        .map(|k| (-0.5 * (k as f64 / sigma).powi(2)).exp())
+        .collect()
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    let n = values.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+    let (_, &mut upper, _) = values.select_nth_unstable_by(n / 2, f64::total_cmp);
+    if n % 2 == 1 {
+        upper
+    } else {
+        let lower = values[..n / 2]
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        (lower + upper) / 2.0
+    }
+}
@@ -0,0 +1,179 @@
+mod routing;
+
+use crate::closures::{Closure, UndefinedNodes};
+use crate::irap::Irap;
+use routing::{Route, routes};
+
+/// How flow is routed between nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlowMethod {
+    /// All flow goes to the steepest of the eight neighbours.
+    D8,
+    /// Flow goes in the steepest direction over the eight triangular facets
+    /// around the node, split between the two neighbours bounding it.
+    DInfinity,
+}
+
+/// Nodes draining into each closure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drainage {
+    pub closures: Vec<Closure>,
+    /// Index of the closure whose crest each node's flow path ends at, NaN
+    /// for paths ending elsewhere and undefined nodes.
+    pub labels: Irap,
+    /// Area draining into each closure, including the closures it contains.
+    pub areas: Vec<f64>,
+}
+
+impl Irap {
+    /// Geographic azimuth, in degrees clockwise from north, of the steepest
+    /// ascent on a depth surface, i.e. towards smaller values. Undefined at
+    /// local highs.
+    pub fn flow_direction(&self, method: FlowMethod) -> Irap {
+        let (sin, cos) = self.header.rot.to_radians().sin_cos();
+        let values = routes(self, method)
+            .iter()
+            .map(|route| {
+                if route.count == 0 {
+                    return f32::NAN;
+                }
+                let [u, v] = route.direction;
+                let (east, north) = (u * cos - v * sin, u * sin + v * cos);
+                east.atan2(north).to_degrees().rem_euclid(360.0) as f32
+            })
+            .collect();
+        Irap {
+            header: self.header.clone(),
+            values,
+        }
+    }
+
+    /// Area, in world units, of the cells whose flow passes through each
+    /// node, including its own.
+    pub fn flow_accumulation(&self, method: FlowMethod) -> Irap {
+        let routes = routes(self, method);
+        let cell_area = (self.header.xinc * self.header.yinc).abs();
+
+        // Receivers are shallower, so visiting the deepest nodes first passes
+        // on complete totals.
+        let mut order: Vec<usize> = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .collect();
+        order.sort_by(|&a, &b| self.values[b].total_cmp(&self.values[a]));
+
+        let mut accumulation: Vec<f64> = self
+            .values
+            .iter()
+            .map(|v| if v.is_nan() { f64::NAN } else { cell_area })
+            .collect();
+        for idx in order {
+            let total = accumulation[idx];
+            for &(receiver, fraction) in routes[idx].receivers() {
+                accumulation[receiver] += fraction * total;
+            }
+        }
+        Irap {
+            header: self.header.clone(),
+            values: accumulation.into_iter().map(|v| v as f32).collect(),
+        }
+    }
+
+    /// Steepest ascent path from each start point, as polylines in world
+    /// coordinates.
+    ///
+    /// A path goes from the start point to the closest node, then follows
+    /// the main receiver of each node until a local high or the edge of the
+    /// defined surface. Start points outside the lattice give empty paths.
+    pub fn flow_paths(&self, starts: &[[f64; 2]], method: FlowMethod) -> Vec<Vec<[f64; 2]>> {
+        let routes = routes(self, method);
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let node_xy = |idx: usize| {
+            let (x, y) = self
+                .header
+                .node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+            [x, y]
+        };
+
+        starts
+            .iter()
+            .map(|&[x, y]| {
+                let (col, row) = self.header.world_to_grid(x, y);
+                let (col, row) = (col.round(), row.round());
+                if !(col >= 0.0 && row >= 0.0 && col < ncol as f64 && row < nrow as f64) {
+                    return Vec::new();
+                }
+                let mut idx = self.header.index(col as usize, row as usize);
+                if self.values[idx].is_nan() {
+                    return Vec::new();
+                }
+                let mut path = vec![[x, y], node_xy(idx)];
+                while let Some(next) = main_receiver(&routes[idx]) {
+                    idx = next;
+                    path.push(node_xy(idx));
+                }
+                path
+            })
+            .collect()
+    }
+
+    /// Closures and the area draining into each of them along D8 flow paths.
+    pub fn drainage(&self, undefined: UndefinedNodes) -> Drainage {
+        let closures = self.closures(undefined);
+        let cell_area = (self.header.xinc * self.header.yinc).abs();
+
+        // Closures contained in others share their crest, so the first one
+        // found, which is the innermost, gets the flow.
+        let mut by_crest = vec![None; self.values.len()];
+        for (k, closure) in closures.iter().enumerate() {
+            let (col, row) = closure.crest;
+            by_crest[self.header.index(col, row)].get_or_insert(k);
+        }
+
+        let routes = routes(self, FlowMethod::D8);
+        let mut end: Vec<Option<usize>> = vec![None; self.values.len()];
+        let mut order: Vec<usize> = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .collect();
+        // Shallowest first, so each node's receiver is already resolved
+        order.sort_by(|&a, &b| self.values[a].total_cmp(&self.values[b]));
+        for idx in order {
+            end[idx] = match main_receiver(&routes[idx]) {
+                Some(receiver) => end[receiver],
+                None => by_crest[idx],
+            };
+        }
+
+        let mut areas = vec![0.0; closures.len()];
+        for k in end.iter().flatten() {
+            areas[*k] += cell_area;
+        }
+        // Children close before their parents
+        for k in 0..closures.len() {
+            if let Some(parent) = closures[k].parent {
+                areas[parent] += areas[k];
+            }
+        }
+
+        let labels = Irap {
+            header: self.header.clone(),
+            values: (0..self.values.len())
+                .map(|idx| end[idx].map_or(f32::NAN, |k| k as f32))
+                .collect(),
+        };
+        Drainage {
+            closures,
+            labels,
+            areas,
+        }
+    }
+}
+
+/// Receiver taking the largest share of the flow.
+fn main_receiver(route: &Route) -> Option<usize> {
+    route
+        .receivers()
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|&(idx, _)| idx)
+}
@@ -0,0 +1,146 @@
+use super::FlowMethod;
+use crate::irap::Irap;
+use rayon::prelude::*;
+
+/// Where the flow from a node goes: up to two shallower receivers with the
+/// fraction sent to each, and the flow direction in metric lattice
+/// coordinates. Nodes without receivers are local highs or undefined.
+#[derive(Clone, Copy, Default)]
+pub struct Route {
+    pub receivers: [(usize, f64); 2],
+    pub count: usize,
+    pub direction: [f64; 2],
+}
+
+impl Route {
+    pub fn receivers(&self) -> &[(usize, f64)] {
+        &self.receivers[..self.count]
+    }
+}
+
+const CARDINALS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// Steepest ascent routes over a depth surface, going towards smaller values.
+/// Undefined nodes receive no flow.
+pub fn routes(irap: &Irap, method: FlowMethod) -> Vec<Route> {
+    let nrow = irap.header.nrow as usize;
+    (0..irap.values.len())
+        .into_par_iter()
+        .map(|idx| {
+            if irap.values[idx].is_nan() {
+                return Route::default();
+            }
+            let (col, row) = (idx / nrow, idx % nrow);
+            match method {
+                FlowMethod::D8 => d8(irap, col, row),
+                FlowMethod::DInfinity => d_infinity(irap, col, row),
+            }
+        })
+        .collect()
+}
+
+/// Value and index of the node at an offset, if it is on the lattice and defined.
+fn node(irap: &Irap, col: usize, row: usize, dc: isize, dr: isize) -> Option<(usize, f64)> {
+    let c = col.checked_add_signed(dc)?;
+    let r = row.checked_add_signed(dr)?;
+    if c >= irap.header.ncol as usize || r >= irap.header.nrow as usize {
+        return None;
+    }
+    let idx = irap.header.index(c, r);
+    let v = irap.values[idx];
+    (!v.is_nan()).then_some((idx, v as f64))
+}
+
+fn d8(irap: &Irap, col: usize, row: usize) -> Route {
+    let (xinc, yinc) = (irap.header.xinc.abs(), irap.header.yinc.abs());
+    let z = irap.values[irap.header.index(col, row)] as f64;
+    let mut best: Option<(f64, usize, [f64; 2])> = None;
+    for dc in -1..=1 {
+        for dr in -1..=1 {
+            if dc == 0 && dr == 0 {
+                continue;
+            }
+            let Some((n, v)) = node(irap, col, row, dc, dr) else {
+                continue;
+            };
+            let offset = [dc as f64 * xinc, dr as f64 * yinc];
+            let slope = (z - v) / offset[0].hypot(offset[1]);
+            if slope > 0.0 && best.is_none_or(|(s, _, _)| slope > s) {
+                best = Some((slope, n, offset));
+            }
+        }
+    }
+    match best {
+        Some((_, n, direction)) => Route {
+            receivers: [(n, 1.0), (0, 0.0)],
+            count: 1,
+            direction,
+        },
+        None => Route::default(),
+    }
+}
+
+/// D-infinity (Tarboton 1997): the steepest direction over the eight
+/// triangular facets around the node, with the flow split between the two
+/// nodes bounding that direction in proportion to the angle.
+fn d_infinity(irap: &Irap, col: usize, row: usize) -> Route {
+    let (xinc, yinc) = (irap.header.xinc.abs(), irap.header.yinc.abs());
+    let z = irap.values[irap.header.index(col, row)] as f64;
+    let mut best: Option<(f64, Route)> = None;
+
+    for (dc, dr) in CARDINALS {
+        for side in [-1, 1] {
+            let (pc, pr) = (-dr * side, dc * side);
+            let (Some((n1, z1)), Some((n2, z2))) = (
+                node(irap, col, row, dc, dr),
+                node(irap, col, row, dc + pc, dr + pr),
+            ) else {
+                continue;
+            };
+            let d1 = if dc != 0 { xinc } else { yinc };
+            let d2 = if pc != 0 { xinc } else { yinc };
+            let width = (d2 / d1).atan();
+
+            let s1 = (z - z1) / d1;
+            let s2 = (z1 - z2) / d2;
+            let (mut r, mut s) = (s2.atan2(s1), s1.hypot(s2));
+            if r < 0.0 {
+                r = 0.0;
+                s = s1;
+            } else if r > width {
+                r = width;
+                s = (z - z2) / d1.hypot(d2);
+            }
+            if s <= 0.0 || best.as_ref().is_some_and(|(b, _)| s <= *b) {
+                continue;
+            }
+
+            let unit = [dc as f64, dr as f64];
+            let perp = [pc as f64, pr as f64];
+            let direction = [
+                unit[0] * r.cos() + perp[0] * r.sin(),
+                unit[1] * r.cos() + perp[1] * r.sin(),
+            ];
+            let fraction = r / width;
+            let mut route = Route {
+                direction,
+                ..Default::default()
+            };
+            for (n, v, f) in [(n1, z1, 1.0 - fraction), (n2, z2, fraction)] {
+                // Only strictly shallower receivers, so flow always rises
+                if f > 0.0 && v < z {
+                    route.receivers[route.count] = (n, f);
+                    route.count += 1;
+                }
+            }
+            let total: f64 = route.receivers().iter().map(|r| r.1).sum();
+            if total > 0.0 {
+                for receiver in &mut route.receivers[..route.count] {
+                    receiver.1 /= total;
+                }
+                best = Some((s, route));
+            }
+        }
+    }
+    best.map(|(_, route)| route).unwrap_or_default()
+}
@@ -0,0 +1,42 @@
+use super::node_coordinates;
+use crate::irap::IrapHeader;
+use crate::spatial::KdTree;
+use rayon::prelude::*;
+
+pub fn grid(
+    xy: &[[f64; 2]],
+    z: &[f64],
+    header: &IrapHeader,
+    power: f64,
+    radius: f64,
+    max_neighbours: usize,
+) -> Vec<f32> {
+    let tree = KdTree::new(xy);
+    node_coordinates(header)
+        .into_par_iter()
+        .map(|(x, y)| {
+            let neighbours = tree.k_nearest(x, y, max_neighbours, radius);
+            weighted_mean(&neighbours, z, power) as f32
+        })
+        .collect()
+}
+
+/// Inverse distance weighted mean of `z` over neighbours given as
+/// (index, squared distance). NaN when there are no neighbours.
+pub fn weighted_mean(neighbours: &[(usize, f64)], z: &[f64], power: f64) -> f64 {
+    let mut sum = 0.0;
+    let mut weights = 0.0;
+    for &(i, d2) in neighbours {
+        if d2 == 0.0 {
+            return z[i];
+        }
+        let w = d2.powf(-0.5 * power);
+        sum += w * z[i];
+        weights += w;
+    }
+    if weights > 0.0 {
+        sum / weights
+    } else {
+        f64::NAN
+    }
+}
@@ -0,0 +1,225 @@
+use super::{Result, node_coordinates};
+use crate::irap::{Irap, IrapHeader};
+use crate::spatial::KdTree;
+use crate::utils::solve_linear;
+use crate::variogram::VariogramModel;
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KrigingType {
+    /// Kriging around a known mean.
+    Simple { mean: f64 },
+    /// Kriging with the mean estimated locally from the neighbours.
+    Ordinary,
+}
+
+/// Geometric anisotropy, with the model range along the major axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anisotropy {
+    /// Direction of the major axis in degrees clockwise from north.
+    pub azimuth: f64,
+    /// Range across the major axis.
+    pub minor_range: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KrigingOptions {
+    pub kind: KrigingType,
+    pub model: VariogramModel,
+    /// Isotropic when `None`.
+    pub anisotropy: Option<Anisotropy>,
+    /// Search radius along the major axis; the search ellipse has the same
+    /// shape as the anisotropy.
+    pub radius: f64,
+    /// Largest number of neighbours used for each node.
+    pub max_points: usize,
+    /// Whether to compute the kriging standard deviation.
+    pub std: bool,
+}
+
+/// Kriged surface with its standard deviation, when requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kriging {
+    pub surface: Irap,
+    pub std: Option<Irap>,
+}
+
+/// Krige scattered (x, y, z) points onto the lattice given by `header`.
+///
+/// Each node is estimated from its closest points within the search ellipse,
+/// in parallel over the nodes. Without neighbours, simple kriging gives the
+/// mean and ordinary kriging an undefined node, as it does when duplicate
+/// points make the kriging system singular. Points with undefined
+/// coordinates or values are ignored.
+pub fn krige(
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    header: &IrapHeader,
+    options: &KrigingOptions,
+) -> Result<Kriging> {
+    if xs.len() != ys.len() || xs.len() != zs.len() {
+        return Err(format!(
+            "Coordinate arrays differ in length: x={}, y={}, z={}",
+            xs.len(),
+            ys.len(),
+            zs.len()
+        )
+        .into());
+    }
+    let (xy, z): (Vec<[f64; 2]>, Vec<f64>) = (0..xs.len())
+        .filter(|&i| xs[i].is_finite() && ys[i].is_finite() && zs[i].is_finite())
+        .map(|i| ([xs[i], ys[i]], zs[i]))
+        .unzip();
+    let neighbourhood = Neighbourhood::new(&xy, options)?;
+
+    let (values, variances): (Vec<f32>, Vec<f32>) = node_coordinates(header)
+        .into_par_iter()
+        .map(|(x, y)| {
+            let Some((weights, variance)) = neighbourhood.weights(x, y) else {
+                return (f32::NAN, f32::NAN);
+            };
+            let estimate = match options.kind {
+                KrigingType::Simple { mean } => {
+                    mean + weights.iter().map(|&(i, w)| w * (z[i] - mean)).sum::<f64>()
+                }
+                KrigingType::Ordinary => weights.iter().map(|&(i, w)| w * z[i]).sum(),
+            };
+            (estimate as f32, variance.max(0.0).sqrt() as f32)
+        })
+        .unzip();
+
+    Ok(Kriging {
+        surface: Irap {
+            header: header.clone(),
+            values,
+        },
+        std: options.std.then(|| Irap {
+            header: header.clone(),
+            values: variances,
+        }),
+    })
+}
+
+fn check_options(options: &KrigingOptions) -> Result<()> {
+    let model = &options.model;
+    if !(model.range > 0.0 && model.range.is_finite()) {
+        return Err(format!("Invalid variogram range: {}", model.range).into());
+    }
+    if !(model.nugget >= 0.0 && model.sill >= 0.0 && model.nugget + model.sill > 0.0) {
+        return Err(format!(
+            "Invalid variogram nugget and sill: {}, {}",
+            model.nugget, model.sill
+        )
+        .into());
+    }
+    if let Some(anisotropy) = options.anisotropy
+        && !(anisotropy.minor_range > 0.0 && anisotropy.minor_range.is_finite())
+    {
+        return Err(format!("Invalid minor range: {}", anisotropy.minor_range).into());
+    }
+    if options.radius.is_nan() || options.radius <= 0.0 {
+        return Err(format!("Invalid search radius: {}", options.radius).into());
+    }
+    if options.max_points == 0 {
+        return Err("Maximum number of points must be positive".into());
+    }
+    Ok(())
+}
+
+/// Maps world coordinates to a space where the anisotropy is isotropic,
+/// with the major axis along the first coordinate at its original scale.
+pub(crate) struct Transform {
+    sin: f64,
+    cos: f64,
+    stretch: f64,
+}
+
+impl Transform {
+    pub(crate) fn new(options: &KrigingOptions) -> Self {
+        let (azimuth, stretch) = match options.anisotropy {
+            Some(a) => (a.azimuth, options.model.range / a.minor_range),
+            None => (0.0, 1.0),
+        };
+        let (sin, cos) = azimuth.to_radians().sin_cos();
+        Transform { sin, cos, stretch }
+    }
+
+    pub(crate) fn apply(&self, x: f64, y: f64) -> [f64; 2] {
+        [
+            x * self.sin + y * self.cos,
+            (x * self.cos - y * self.sin) * self.stretch,
+        ]
+    }
+}
+
+/// Search for neighbours and solving of the kriging system around a node.
+pub(crate) struct Neighbourhood<'a> {
+    options: &'a KrigingOptions,
+    transform: Transform,
+    /// Points in the isotropic space.
+    uv: Vec<[f64; 2]>,
+    tree: KdTree,
+}
+
+impl<'a> Neighbourhood<'a> {
+    /// Neighbourhood of the points `xy`, which must be finite.
+    pub(crate) fn new(xy: &[[f64; 2]], options: &'a KrigingOptions) -> Result<Self> {
+        check_options(options)?;
+        let transform = Transform::new(options);
+        let uv: Vec<[f64; 2]> = xy.iter().map(|&[x, y]| transform.apply(x, y)).collect();
+        let tree = KdTree::new(&uv);
+        Ok(Neighbourhood {
+            options,
+            transform,
+            uv,
+            tree,
+        })
+    }
+
+    /// Kriging weights at (x, y) as (point index, weight), with the kriging
+    /// variance, or `None` for ordinary kriging without neighbours or a
+    /// singular system. Simple kriging weights apply to the residuals from
+    /// the mean.
+    pub(crate) fn weights(&self, x: f64, y: f64) -> Option<(Vec<(usize, f64)>, f64)> {
+        let options = self.options;
+        let model = &options.model;
+        let [u, v] = self.transform.apply(x, y);
+        let neighbours = self
+            .tree
+            .k_nearest(u, v, options.max_points, options.radius);
+        let n = neighbours.len();
+        let ordinary = options.kind == KrigingType::Ordinary;
+        if n == 0 && ordinary {
+            return None;
+        }
+
+        let size = if ordinary { n + 1 } else { n };
+        let mut matrix = vec![0.0; size * size];
+        let mut rhs = vec![0.0; size];
+        for (r, &(i, d2)) in neighbours.iter().enumerate() {
+            for (c, &(j, _)) in neighbours.iter().enumerate() {
+                let h = (self.uv[i][0] - self.uv[j][0]).hypot(self.uv[i][1] - self.uv[j][1]);
+                matrix[r * size + c] = model.covariance(h);
+            }
+            rhs[r] = model.covariance(d2.sqrt());
+        }
+        if ordinary {
+            for k in 0..n {
+                matrix[k * size + n] = 1.0;
+                matrix[n * size + k] = 1.0;
+            }
+            rhs[n] = 1.0;
+        }
+        let covariances = rhs.clone();
+        let solution = solve_linear(matrix, rhs)?;
+
+        let explained: f64 = solution.iter().zip(&covariances).map(|(w, c)| w * c).sum();
+        let weights = neighbours
+            .iter()
+            .zip(&solution)
+            .map(|(&(i, _), &w)| (i, w))
+            .collect();
+        Some((weights, model.nugget + model.sill - explained))
+    }
+}
@@ -0,0 +1,297 @@
+use crate::irap::IrapHeader;
+use crate::spatial::KdTree;
+
+/// Over-relaxation factor for the Gauss-Seidel iterations.
+const OMEGA: f64 = 1.4;
+
+/// Weight of the data misfit relative to the curvature.
+const PENALTY: f64 = 1000.0;
+
+/// Coarsest level keeps at least this many cells along each axis.
+const MIN_COARSE_CELLS: usize = 4;
+
+/// Solves (1 - T) * del^4(z) - T * del^2(z) = 0 with the bilinear interpolation
+/// at each datum tied to its value by a heavily weighted misfit, going from a
+/// coarse lattice to the target one so each level starts from a smooth guess.
+pub fn grid(
+    xy: &[[f64; 2]],
+    z: &[f64],
+    header: &IrapHeader,
+    tension: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Vec<f32> {
+    let ncol = header.ncol as usize;
+    let nrow = header.nrow as usize;
+    let aspect = header.yinc / header.xinc;
+
+    let (zmin, zmax) = z
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    let tolerance = tolerance * (zmax - zmin).max(f64::EPSILON);
+
+    let grid_points: Vec<(f64, f64)> = xy
+        .iter()
+        .map(|p| header.world_to_grid(p[0], p[1]))
+        .collect();
+
+    let mut factor = 1;
+    while (ncol - 1) / (2 * factor) >= MIN_COARSE_CELLS
+        && (nrow - 1) / (2 * factor) >= MIN_COARSE_CELLS
+    {
+        factor *= 2;
+    }
+
+    let mut previous: Option<Level> = None;
+    loop {
+        let nc = (ncol - 1).div_ceil(factor) + 1;
+        let nr = (nrow - 1).div_ceil(factor) + 1;
+
+        let mut values = match &previous {
+            None => {
+                let tree = KdTree::new(xy);
+                let mut values = vec![0.0; nc * nr];
+                for ci in 0..nc {
+                    for cj in 0..nr {
+                        let (x, y) = header.node_xy((ci * factor) as f64, (cj * factor) as f64);
+                        if let Some((i, _)) = tree.nearest(x, y) {
+                            values[ci * nr + cj] = z[i];
+                        }
+                    }
+                }
+                values
+            }
+            Some(level) => {
+                let scale = factor as f64 / level.factor as f64;
+                let mut values = vec![0.0; nc * nr];
+                for ci in 0..nc {
+                    for cj in 0..nr {
+                        values[ci * nr + cj] = level.sample(ci as f64 * scale, cj as f64 * scale);
+                    }
+                }
+                values
+            }
+        };
+
+        let constraints: Vec<Constraint> = grid_points
+            .iter()
+            .zip(z)
+            .filter_map(|(&(col, row), &v)| {
+                Constraint::new(col / factor as f64, row / factor as f64, v, nc, nr)
+            })
+            .collect();
+
+        solve(
+            &mut values,
+            &constraints,
+            nc,
+            nr,
+            aspect,
+            tension,
+            max_iterations,
+            tolerance,
+        );
+
+        if factor == 1 {
+            return values.into_iter().map(|v| v as f32).collect();
+        }
+        previous = Some(Level {
+            values,
+            ncol: nc,
+            nrow: nr,
+            factor,
+        });
+        factor /= 2;
+    }
+}
+
+struct Level {
+    values: Vec<f64>,
+    ncol: usize,
+    nrow: usize,
+    factor: usize,
+}
+
+impl Level {
+    fn sample(&self, col: f64, row: f64) -> f64 {
+        let i0 = (col.floor() as usize).min(self.ncol.saturating_sub(2));
+        let j0 = (row.floor() as usize).min(self.nrow.saturating_sub(2));
+        let i1 = (i0 + 1).min(self.ncol - 1);
+        let j1 = (j0 + 1).min(self.nrow - 1);
+        let tx = (col - i0 as f64).clamp(0.0, 1.0);
+        let ty = (row - j0 as f64).clamp(0.0, 1.0);
+        let v = |i: usize, j: usize| self.values[i * self.nrow + j];
+        (v(i0, j0) * (1.0 - tx) + v(i1, j0) * tx) * (1.0 - ty)
+            + (v(i0, j1) * (1.0 - tx) + v(i1, j1) * tx) * ty
+    }
+}
+
+/// Datum inside the lattice as a bilinear combination of the nodes of its
+/// cell, an off-node constraint in the sense of Briggs (1974).
+struct Constraint {
+    corners: Vec<(usize, f64)>,
+    value: f64,
+}
+
+impl Constraint {
+    fn new(col: f64, row: f64, value: f64, ncol: usize, nrow: usize) -> Option<Self> {
+        if !(col >= 0.0 && row >= 0.0 && col <= (ncol - 1) as f64 && row <= (nrow - 1) as f64) {
+            return None;
+        }
+        let i0 = (col.floor() as usize).min(ncol.saturating_sub(2));
+        let j0 = (row.floor() as usize).min(nrow.saturating_sub(2));
+        let i1 = (i0 + 1).min(ncol - 1);
+        let j1 = (j0 + 1).min(nrow - 1);
+        let tx = col - i0 as f64;
+        let ty = row - j0 as f64;
+
+        // Corners coincide on lattices one node wide
+        let mut corners: Vec<(usize, f64)> = Vec::with_capacity(4);
+        for (i, j, w) in [
+            (i0, j0, (1.0 - tx) * (1.0 - ty)),
+            (i1, j0, tx * (1.0 - ty)),
+            (i0, j1, (1.0 - tx) * ty),
+            (i1, j1, tx * ty),
+        ] {
+            let idx = i * nrow + j;
+            match corners.iter_mut().find(|c| c.0 == idx) {
+                Some(corner) => corner.1 += w,
+                None => corners.push((idx, w)),
+            }
+        }
+        Some(Constraint { corners, value })
+    }
+
+    /// Interpolated value minus the datum.
+    fn misfit(&self, values: &[f64]) -> f64 {
+        self.corners
+            .iter()
+            .map(|&(k, w)| w * values[k])
+            .sum::<f64>()
+            - self.value
+    }
+}
+
+struct Stencil {
+    ncol: usize,
+    nrow: usize,
+    ax: f64,
+    ay: f64,
+    c0: f64,
+}
+
+impl Stencil {
+    /// Value at node (i, j), linearly extrapolated outside the lattice. This
+    /// gives zero curvature across the edges, so planar trends continue.
+    fn at(&self, v: &[f64], i: isize, j: isize) -> f64 {
+        let (nc, nr) = (self.ncol as isize, self.nrow as isize);
+        if i < 0 || i >= nc {
+            if nc == 1 {
+                return self.at(v, 0, j);
+            }
+            let (edge, inner, k) = if i < 0 {
+                (0, 1, -i)
+            } else {
+                (nc - 1, nc - 2, i - nc + 1)
+            };
+            let e = self.at(v, edge, j);
+            return e + k as f64 * (e - self.at(v, inner, j));
+        }
+        if j < 0 || j >= nr {
+            if nr == 1 {
+                return self.at(v, i, 0);
+            }
+            let (edge, inner, k) = if j < 0 {
+                (0, 1, -j)
+            } else {
+                (nr - 1, nr - 2, j - nr + 1)
+            };
+            let e = self.at(v, i, edge);
+            return e + k as f64 * (e - self.at(v, i, inner));
+        }
+        v[i as usize * self.nrow + j as usize]
+    }
+
+    fn laplacian(&self, v: &[f64], i: isize, j: isize) -> f64 {
+        self.ax * (self.at(v, i - 1, j) + self.at(v, i + 1, j))
+            + self.ay * (self.at(v, i, j - 1) + self.at(v, i, j + 1))
+            + self.c0 * self.at(v, i, j)
+    }
+
+    fn residual(&self, v: &[f64], i: usize, j: usize, tension: f64) -> f64 {
+        let (i, j) = (i as isize, j as isize);
+        let lap = self.laplacian(v, i, j);
+        let bilap = self.ax * (self.laplacian(v, i - 1, j) + self.laplacian(v, i + 1, j))
+            + self.ay * (self.laplacian(v, i, j - 1) + self.laplacian(v, i, j + 1))
+            + self.c0 * lap;
+        (1.0 - tension) * bilap - tension * lap
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn solve(
+    values: &mut [f64],
+    constraints: &[Constraint],
+    ncol: usize,
+    nrow: usize,
+    aspect: f64,
+    tension: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) {
+    // Node spacing is 1 along columns and `aspect` along rows.
+    let ax = 1.0;
+    let ay = 1.0 / (aspect * aspect);
+    let stencil = Stencil {
+        ncol,
+        nrow,
+        ax,
+        ay,
+        c0: -2.0 * (ax + ay),
+    };
+
+    // The operator is linear, so the weight of a node on its own residual is
+    // the residual of a unit impulse. It differs from the interior value near
+    // the edges because of the extrapolation.
+    let mut impulse = vec![0.0; ncol * nrow];
+    let mut diagonal: Vec<f64> = (0..ncol * nrow)
+        .map(|idx| {
+            impulse[idx] = 1.0;
+            let d = stencil.residual(&impulse, idx / nrow, idx % nrow, tension);
+            impulse[idx] = 0.0;
+            d
+        })
+        .collect();
+
+    // Each datum adds its squared misfit, weighted well above the curvature,
+    // to the equations of the nodes around it.
+    let penalty = PENALTY * diagonal.iter().fold(0.0, |m: f64, &d| m.max(d));
+    let mut held: Vec<Vec<(usize, f64)>> = vec![Vec::new(); ncol * nrow];
+    for (c, constraint) in constraints.iter().enumerate() {
+        for &(k, w) in &constraint.corners {
+            held[k].push((c, w));
+            diagonal[k] += penalty * w * w;
+        }
+    }
+
+    for _ in 0..max_iterations {
+        let mut max_change: f64 = 0.0;
+        for i in 0..ncol {
+            for j in 0..nrow {
+                let idx = i * nrow + j;
+                let mut residual = stencil.residual(values, i, j, tension);
+                for &(c, w) in &held[idx] {
+                    residual += penalty * w * constraints[c].misfit(values);
+                }
+                let delta = -OMEGA * residual / diagonal[idx];
+                values[idx] += delta;
+                max_change = max_change.max(delta.abs());
+            }
+        }
+        if max_change <= tolerance {
+            break;
+        }
+    }
+}
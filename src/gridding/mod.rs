@@ -0,0 +1,116 @@
+mod inverse_distance;
+mod kriging;
+mod minimum_curvature;
+mod nearest;
+mod tie;
+mod triangulation;
+
+pub use kriging::{Anisotropy, Kriging, KrigingOptions, KrigingType, krige};
+pub(crate) use kriging::{Neighbourhood, Transform};
+pub use tie::Tie;
+
+use crate::irap::{Irap, IrapHeader};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Interpolation method used when gridding scattered points onto a lattice.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub enum GriddingMethod {
+    /// Value of the closest point.
+    #[default]
+    Nearest,
+    /// Inverse distance weighting of at most `max_neighbours` points within `radius`.
+    /// `power` must be finite and non-negative, and `radius` positive or infinite.
+    InverseDistance {
+        power: f64,
+        radius: f64,
+        max_neighbours: usize,
+    },
+    /// Linear interpolation on the Delaunay triangulation of the points.
+    /// Nodes outside the convex hull are undefined.
+    Linear,
+    /// Minimum curvature with tension (Briggs 1974, Smith and Wessel 1990).
+    /// `tension` is between 0 (pure minimum curvature) and 1 (harmonic).
+    MinimumCurvature {
+        tension: f64,
+        max_iterations: usize,
+        tolerance: f64,
+    },
+}
+
+/// Grid scattered (x, y, z) points onto the lattice given by `header`.
+///
+/// Points with undefined z are ignored.
+pub fn from_points(
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    header: &IrapHeader,
+    method: &GriddingMethod,
+) -> Result<Irap> {
+    if xs.len() != ys.len() || xs.len() != zs.len() {
+        return Err(format!(
+            "Coordinate arrays differ in length: x={}, y={}, z={}",
+            xs.len(),
+            ys.len(),
+            zs.len()
+        )
+        .into());
+    }
+    if header.ncol == 0 || header.nrow == 0 {
+        return Err(format!(
+            "Invalid dimensions: ncol={}, nrow={}",
+            header.ncol, header.nrow
+        )
+        .into());
+    }
+
+    let (xy, z): (Vec<[f64; 2]>, Vec<f64>) = (0..xs.len())
+        .filter(|&i| xs[i].is_finite() && ys[i].is_finite() && zs[i].is_finite())
+        .map(|i| ([xs[i], ys[i]], zs[i]))
+        .unzip();
+    if xy.is_empty() {
+        return Err("No defined points to grid".into());
+    }
+
+    let values = match method {
+        GriddingMethod::Nearest => nearest::grid(&xy, &z, header),
+        GriddingMethod::InverseDistance {
+            power,
+            radius,
+            max_neighbours,
+        } => {
+            if *power < 0.0 || !power.is_finite() {
+                return Err(format!("Power must be finite and non-negative, got {}", power).into());
+            }
+            if radius.is_nan() || *radius <= 0.0 {
+                return Err(format!("Radius must be positive, got {}", radius).into());
+            }
+            inverse_distance::grid(&xy, &z, header, *power, *radius, *max_neighbours)
+        }
+        GriddingMethod::Linear => triangulation::grid(&xy, &z, header),
+        GriddingMethod::MinimumCurvature {
+            tension,
+            max_iterations,
+            tolerance,
+        } => {
+            if !(0.0..1.0).contains(tension) {
+                return Err(format!("Tension must be in [0, 1), got {}", tension).into());
+            }
+            minimum_curvature::grid(&xy, &z, header, *tension, *max_iterations, *tolerance)
+        }
+    };
+
+    Ok(Irap {
+        header: header.clone(),
+        values,
+    })
+}
+
+/// World coordinates of every node, in value order.
+fn node_coordinates(header: &IrapHeader) -> Vec<(f64, f64)> {
+    let nrow = header.nrow as usize;
+    (0..header.len())
+        .map(|i| header.node_xy((i / nrow) as f64, (i % nrow) as f64))
+        .collect()
+}
@@ -0,0 +1,15 @@
+use super::node_coordinates;
+use crate::irap::IrapHeader;
+use crate::spatial::KdTree;
+use rayon::prelude::*;
+
+pub fn grid(xy: &[[f64; 2]], z: &[f64], header: &IrapHeader) -> Vec<f32> {
+    let tree = KdTree::new(xy);
+    node_coordinates(header)
+        .into_par_iter()
+        .map(|(x, y)| match tree.nearest(x, y) {
+            Some((i, _)) => z[i] as f32,
+            None => f32::NAN,
+        })
+        .collect()
+}
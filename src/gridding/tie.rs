@@ -0,0 +1,97 @@
+use super::{GriddingMethod, Result, from_points};
+use crate::irap::Irap;
+use crate::spatial::KdTree;
+use rayon::prelude::*;
+
+/// Surface adjusted to honour scattered points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tie {
+    pub surface: Irap,
+    /// Point value minus the original surface at each point, NaN where the
+    /// surface is undefined or the point is outside the lattice.
+    pub residuals: Vec<f64>,
+    /// Point value minus the adjusted surface at each point.
+    pub misfits: Vec<f64>,
+}
+
+impl Irap {
+    /// Adjust the surface to pass through the points (x, y, z), such as well
+    /// tops, by gridding the residuals and adding them back.
+    ///
+    /// Residuals are taken against the surface sampled bilinearly at each
+    /// point and gridded with `method`. The correction is tapered with a
+    /// cosine from full strength at a point to zero at `radius` from the
+    /// nearest point with a residual, so the surface is unchanged farther
+    /// away. Undefined nodes stay undefined, and nodes where the gridded
+    /// residual is undefined are not corrected.
+    pub fn tie_to_points(
+        &self,
+        xs: &[f64],
+        ys: &[f64],
+        zs: &[f64],
+        method: &GriddingMethod,
+        radius: f64,
+    ) -> Result<Tie> {
+        if xs.len() != ys.len() || xs.len() != zs.len() {
+            return Err(format!(
+                "Coordinate arrays differ in length: x={}, y={}, z={}",
+                xs.len(),
+                ys.len(),
+                zs.len()
+            )
+            .into());
+        }
+        if !(radius > 0.0 && radius.is_finite()) {
+            return Err(format!("Invalid taper radius: {}", radius).into());
+        }
+
+        let residuals: Vec<f64> = (0..xs.len())
+            .map(|i| zs[i] - self.sample(xs[i], ys[i]))
+            .collect();
+        let tied: Vec<[f64; 2]> = (0..xs.len())
+            .filter(|&i| residuals[i].is_finite())
+            .map(|i| [xs[i], ys[i]])
+            .collect();
+
+        let mut surface = self.clone();
+        if !tied.is_empty() {
+            let correction = from_points(xs, ys, &residuals, &self.header, method)?;
+            let tree = KdTree::new(&tied);
+            let nrow = self.header.nrow as usize;
+            surface
+                .values
+                .par_iter_mut()
+                .zip(&correction.values)
+                .enumerate()
+                .for_each(|(idx, (v, &r))| {
+                    if r.is_nan() {
+                        return;
+                    }
+                    let (x, y) = self
+                        .header
+                        .node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                    if let Some((_, d2)) = tree.nearest(x, y) {
+                        *v += (r as f64 * taper(d2.sqrt() / radius)) as f32;
+                    }
+                });
+        }
+
+        let misfits = (0..xs.len())
+            .map(|i| zs[i] - surface.sample(xs[i], ys[i]))
+            .collect();
+        Ok(Tie {
+            surface,
+            residuals,
+            misfits,
+        })
+    }
+}
+
+/// Cosine taper from 1 at zero to 0 at one.
+fn taper(distance: f64) -> f64 {
+    if distance >= 1.0 {
+        0.0
+    } else {
+        0.5 * (1.0 + (std::f64::consts::PI * distance).cos())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::irap::IrapHeader;
+use delaunator::{Point, triangulate};
+
+pub fn grid(xy: &[[f64; 2]], z: &[f64], header: &IrapHeader) -> Vec<f32> {
+    let ncol = header.ncol as usize;
+    let nrow = header.nrow as usize;
+    let mut values = vec![f32::NAN; header.len()];
+
+    // Triangulate in lattice coordinates, where barycentric weights are the
+    // same as in world coordinates but node positions are just integers.
+    let points: Vec<Point> = xy
+        .iter()
+        .map(|p| {
+            let (col, row) = header.world_to_grid(p[0], p[1]);
+            Point { x: col, y: row }
+        })
+        .collect();
+    let triangulation = triangulate(&points);
+
+    for t in triangulation.triangles.chunks_exact(3) {
+        let (a, b, c) = (&points[t[0]], &points[t[1]], &points[t[2]]);
+        let det = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
+        if det == 0.0 {
+            continue;
+        }
+
+        let min_col = a.x.min(b.x).min(c.x).ceil().max(0.0);
+        let max_col = a.x.max(b.x).max(c.x).floor().min((ncol - 1) as f64);
+        let min_row = a.y.min(b.y).min(c.y).ceil().max(0.0);
+        let max_row = a.y.max(b.y).max(c.y).floor().min((nrow - 1) as f64);
+        if min_col > max_col || min_row > max_row {
+            continue;
+        }
+
+        for col in min_col as usize..=max_col as usize {
+            for row in min_row as usize..=max_row as usize {
+                let (px, py) = (col as f64, row as f64);
+                let wa = ((b.y - c.y) * (px - c.x) + (c.x - b.x) * (py - c.y)) / det;
+                let wb = ((c.y - a.y) * (px - c.x) + (a.x - c.x) * (py - c.y)) / det;
+                let wc = 1.0 - wa - wb;
+                const EPS: f64 = -1e-12;
+                if wa >= EPS && wb >= EPS && wc >= EPS {
+                    let v = wa * z[t[0]] + wb * z[t[1]] + wc * z[t[2]];
+                    values[header.index(col, row)] = v as f32;
+                }
+            }
+        }
+    }
+
+    values
+}
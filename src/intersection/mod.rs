@@ -0,0 +1,37 @@
+use crate::contours::trace;
+use crate::irap::{Irap, Polygons};
+
+/// Lines where surface `b` cuts surface `a`, such as a fluid contact or a
+/// fault plane cutting a horizon.
+///
+/// The lines are the zero contour of `a - b` on the lattice of `a`, with `b`
+/// resampled bilinearly onto it when the lattices differ. Each vertex has the
+/// depth of `a` there as z value, and closed lines repeat the first vertex at
+/// the end. Lines are broken where either surface is undefined.
+pub fn intersection(a: &Irap, b: &Irap) -> Polygons {
+    let resampled;
+    let b = if b.header.same_lattice(&a.header) {
+        b
+    } else {
+        resampled = b.resample(&a.header);
+        &resampled
+    };
+
+    let difference: Vec<f32> = a.values.iter().zip(&b.values).map(|(a, b)| a - b).collect();
+    let ncol = a.header.ncol as usize;
+    let nrow = a.header.nrow as usize;
+    Polygons {
+        polygons: trace(&difference, ncol, nrow, 0.0)
+            .into_iter()
+            .map(|line| {
+                line.points
+                    .iter()
+                    .map(|&[col, row]| {
+                        let (x, y) = a.header.node_xy(col, row);
+                        [x, y, a.sample_grid(col, row)]
+                    })
+                    .collect()
+            })
+            .collect(),
+    }
+}
@@ -1,30 +1,10 @@
 use crate::irap::{self, Irap, IrapHeader};
-use crate::utils;
+use crate::utils::{self, is_ascii_whitespace, read_value_float, skip_whitespace};
 use memmap::Mmap;
 use std::fs::File;
 
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-fn is_ascii_whitespace(byte: u8) -> bool {
-    matches!(byte, 0x20 | 0x09 | 0x0A | 0x0D | 0x0C | 0x0B)
-}
-
-fn skip_whitespace(buffer: &[u8]) -> usize {
-    buffer
-        .iter()
-        .position(|&c| !is_ascii_whitespace(c))
-        .unwrap_or(buffer.len())
-}
-
-fn read_value_float(buffer: &[u8]) -> Result<(f64, usize)> {
-    let pos = skip_whitespace(buffer);
-    if pos == buffer.len() {
-        return Err("Unexpected end of file".into());
-    }
-    let (num, len) = fast_float::parse_partial::<f64, _>(&buffer[pos..])?;
-    Ok((num, len + pos))
-}
-
 fn read_value_int(buffer: &[u8]) -> Result<(i32, usize)> {
     let pos = skip_whitespace(buffer);
     if pos == buffer.len() {
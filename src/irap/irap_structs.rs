@@ -3,6 +3,7 @@ use pyo3::prelude::*;
 pub const IRAP_HEADER_ID: i32 = -996;
 pub const UNDEF_MAP_IRAP_ASCII: f32 = 9999900.0;
 pub const UNDEF_MAP_IRAP_BINARY: f32 = 1e30;
+pub const POLYGON_SEPARATOR: f64 = 999.0;
 
 #[pymethods]
 impl IrapHeader {
@@ -11,6 +12,7 @@ impl IrapHeader {
         ncol, nrow, xori = 0.0, yori = 0.0, xmax = 0.0, ymax = 0.0,
         xinc = 1.0, yinc = 1.0, rot = 0.0, xrot = 0.0, yrot = 0.0
     ))]
+    #[allow(clippy::too_many_arguments)]
     fn py_new(
         ncol: u32,
         nrow: u32,
@@ -72,6 +74,95 @@ impl IrapHeader {
 
 impl IrapHeader {
     pub const ID: i32 = IRAP_HEADER_ID;
+
+    /// Number of nodes in the lattice.
+    pub fn len(&self) -> usize {
+        self.ncol as usize * self.nrow as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Index into the value vector of node (col, row).
+    pub fn index(&self, col: usize, row: usize) -> usize {
+        col * self.nrow as usize + row
+    }
+
+    /// World coordinates of a (possibly fractional) node position.
+    ///
+    /// The lattice is rotated `rot` degrees counter-clockwise around (xori, yori).
+    pub fn node_xy(&self, col: f64, row: f64) -> (f64, f64) {
+        let (sin, cos) = self.rot.to_radians().sin_cos();
+        let u = col * self.xinc;
+        let v = row * self.yinc;
+        (self.xori + u * cos - v * sin, self.yori + u * sin + v * cos)
+    }
+
+    /// Fractional (col, row) position of a world coordinate, the inverse of `node_xy`.
+    pub fn world_to_grid(&self, x: f64, y: f64) -> (f64, f64) {
+        let (sin, cos) = self.rot.to_radians().sin_cos();
+        let dx = x - self.xori;
+        let dy = y - self.yori;
+        (
+            (dx * cos + dy * sin) / self.xinc,
+            (-dx * sin + dy * cos) / self.yinc,
+        )
+    }
+
+    /// Whether both headers describe the same nodes, ignoring the derived
+    /// xmax/ymax and the unused xrot/yrot.
+    pub fn same_lattice(&self, other: &IrapHeader) -> bool {
+        self.ncol == other.ncol
+            && self.nrow == other.nrow
+            && self.xori == other.xori
+            && self.yori == other.yori
+            && self.xinc == other.xinc
+            && self.yinc == other.yinc
+            && self.rot == other.rot
+    }
+}
+
+impl Irap {
+    /// Value at a fractional node position using bilinear interpolation.
+    ///
+    /// Returns NaN outside the lattice or when any of the surrounding nodes is undefined.
+    pub fn sample_grid(&self, col: f64, row: f64) -> f64 {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        if !(col >= 0.0 && row >= 0.0 && col <= (ncol - 1) as f64 && row <= (nrow - 1) as f64) {
+            return f64::NAN;
+        }
+        let i0 = (col.floor() as usize).min(ncol.saturating_sub(2));
+        let j0 = (row.floor() as usize).min(nrow.saturating_sub(2));
+        let i1 = (i0 + 1).min(ncol - 1);
+        let j1 = (j0 + 1).min(nrow - 1);
+        let tx = col - i0 as f64;
+        let ty = row - j0 as f64;
+
+        let corners = [
+            (i0, j0, (1.0 - tx) * (1.0 - ty)),
+            (i1, j0, tx * (1.0 - ty)),
+            (i0, j1, (1.0 - tx) * ty),
+            (i1, j1, tx * ty),
+        ];
+
+        // Nodes with zero weight do not contribute, so sampling exactly on a
+        // defined node or edge next to an undefined node still gives a value.
+        let mut value = 0.0;
+        for (i, j, w) in corners {
+            if w > 0.0 {
+                value += w * self.values[self.header.index(i, j)] as f64;
+            }
+        }
+        value
+    }
+
+    /// Value at a world coordinate using bilinear interpolation, see `sample_grid`.
+    pub fn sample(&self, x: f64, y: f64) -> f64 {
+        let (col, row) = self.header.world_to_grid(x, y);
+        self.sample_grid(col, row)
+    }
 }
 
 #[pyclass(from_py_object)]
@@ -107,3 +198,13 @@ pub struct Irap {
     pub header: IrapHeader,
     pub values: Vec<f32>,
 }
+
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct Points {
+    pub values: Vec<[f64; 3]>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct Polygons {
+    pub polygons: Vec<Vec<[f64; 3]>>,
+}
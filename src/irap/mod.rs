@@ -1,6 +1,10 @@
 pub mod ascii;
 pub mod binary;
 mod irap_structs;
+pub mod points;
+pub mod polygons;
 
-pub use irap_structs::{Irap, IrapHeader, UNDEF_MAP_IRAP_ASCII, UNDEF_MAP_IRAP_BINARY};
-
+pub use irap_structs::{
+    Irap, IrapHeader, POLYGON_SEPARATOR, Points, Polygons, UNDEF_MAP_IRAP_ASCII,
+    UNDEF_MAP_IRAP_BINARY,
+};
@@ -0,0 +1,30 @@
+use crate::irap::Points;
+use crate::utils::write_xyz;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn write_values<W: Write>(values: &[[f64; 3]], out: &mut W) -> std::io::Result<()> {
+    for &xyz in values {
+        write_xyz(out, xyz)?;
+    }
+    Ok(())
+}
+
+pub fn to_file(path: String, data: &Points) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    write_values(&data.values, &mut writer)?;
+
+    Ok(())
+}
+
+pub fn to_string(data: &Points) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_values(&data.values, &mut buffer)?;
+
+    Ok(String::from_utf8(buffer)?)
+}
@@ -0,0 +1,48 @@
+use crate::irap::{POLYGON_SEPARATOR, Points};
+use crate::utils::{read_value_float, skip_whitespace, undefined_z};
+use memmap::Mmap;
+use std::fs::File;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn read_values(buffer: &[u8]) -> Result<Vec<[f64; 3]>> {
+    let mut values = Vec::new();
+    let mut index = 0;
+
+    loop {
+        index += skip_whitespace(&buffer[index..]);
+        if index == buffer.len() {
+            break;
+        }
+
+        let mut xyz = [0.0; 3];
+        for v in xyz.iter_mut() {
+            let (val, len) = read_value_float(&buffer[index..])?;
+            *v = val;
+            index += len;
+        }
+
+        // Some applications terminate point sets like polygons, skip the marker
+        if xyz == [POLYGON_SEPARATOR; 3] {
+            continue;
+        }
+        values.push(undefined_z(xyz));
+    }
+
+    Ok(values)
+}
+
+pub fn from_file(path: String) -> Result<Points> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+
+    let values = read_values(&mmap[..])?;
+
+    Ok(Points { values })
+}
+
+pub fn from_string(data: &str) -> Result<Points> {
+    let values = read_values(data.as_bytes())?;
+
+    Ok(Points { values })
+}
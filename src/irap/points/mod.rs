@@ -0,0 +1,5 @@
+mod export_points;
+mod import_points;
+
+pub use export_points::{to_file, to_string};
+pub use import_points::{from_file, from_string};
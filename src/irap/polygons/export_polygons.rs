@@ -0,0 +1,35 @@
+use crate::irap::Polygons;
+use crate::utils::write_xyz;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const POLYGON_SEPARATOR_STRING: &str = "999.000000 999.000000 999.000000";
+
+fn write_polygons<W: Write>(polygons: &[Vec<[f64; 3]>], out: &mut W) -> std::io::Result<()> {
+    for polygon in polygons {
+        for &xyz in polygon {
+            write_xyz(out, xyz)?;
+        }
+        writeln!(out, "{}", POLYGON_SEPARATOR_STRING)?;
+    }
+    Ok(())
+}
+
+pub fn to_file(path: String, data: &Polygons) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    write_polygons(&data.polygons, &mut writer)?;
+
+    Ok(())
+}
+
+pub fn to_string(data: &Polygons) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_polygons(&data.polygons, &mut buffer)?;
+
+    Ok(String::from_utf8(buffer)?)
+}
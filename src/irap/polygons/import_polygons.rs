@@ -0,0 +1,56 @@
+use crate::irap::{POLYGON_SEPARATOR, Polygons};
+use crate::utils::{read_value_float, skip_whitespace, undefined_z};
+use memmap::Mmap;
+use std::fs::File;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn read_polygons(buffer: &[u8]) -> Result<Vec<Vec<[f64; 3]>>> {
+    let mut polygons = Vec::new();
+    let mut current = Vec::new();
+    let mut index = 0;
+
+    loop {
+        index += skip_whitespace(&buffer[index..]);
+        if index == buffer.len() {
+            break;
+        }
+
+        let mut xyz = [0.0; 3];
+        for v in xyz.iter_mut() {
+            let (val, len) = read_value_float(&buffer[index..])?;
+            *v = val;
+            index += len;
+        }
+
+        if xyz == [POLYGON_SEPARATOR; 3] {
+            if !current.is_empty() {
+                polygons.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(undefined_z(xyz));
+        }
+    }
+
+    // Tolerate a missing separator after the last polygon
+    if !current.is_empty() {
+        polygons.push(current);
+    }
+
+    Ok(polygons)
+}
+
+pub fn from_file(path: String) -> Result<Polygons> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+
+    let polygons = read_polygons(&mmap[..])?;
+
+    Ok(Polygons { polygons })
+}
+
+pub fn from_string(data: &str) -> Result<Polygons> {
+    let polygons = read_polygons(data.as_bytes())?;
+
+    Ok(Polygons { polygons })
+}
@@ -0,0 +1,5 @@
+mod export_polygons;
+mod import_polygons;
+
+pub use export_polygons::{to_file, to_string};
+pub use import_polygons::{from_file, from_string};
@@ -1,12 +1,35 @@
 use pyo3::prelude::*;
 use pyo3::types::PyBytes;
 
+pub mod closures;
+pub mod contours;
+pub mod depth_conversion;
+pub mod derivatives;
+pub mod extrema;
+pub mod fill;
+pub mod filters;
+pub mod flow;
+pub mod gridding;
+pub mod intersection;
 pub mod irap;
+pub mod merge;
+pub mod profile;
+pub mod resample;
+pub mod simulation;
+mod spatial;
+pub mod stratigraphy;
+pub mod trend;
 mod utils;
+pub mod variogram;
+pub mod volumetrics;
+pub mod wells;
+pub mod zonal;
 
-pub use irap::{Irap, IrapHeader};
+pub use irap::{Irap, IrapHeader, Points, Polygons};
 use numpy::ndarray::Array2;
-use numpy::{IntoPyArray, PyArray2, PyArrayMethods, PyUntypedArrayMethods};
+use numpy::{
+    IntoPyArray, PyArray1, PyArray2, PyArrayMethods, PyReadonlyArray1, PyUntypedArrayMethods,
+};
 
 #[pyclass(from_py_object, name = "IrapSurface")]
 #[derive(Debug)]
@@ -110,22 +133,1007 @@ impl IrapSurface {
         irap_to_surface(py, &irap)
     }
 
+    #[staticmethod]
+    #[pyo3(signature = (
+        xs, ys, zs, header, method = "nearest", power = 2.0, radius = None,
+        max_neighbours = 12, tension = 0.25, max_iterations = 2000, tolerance = 1e-4
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_points(
+        py: Python,
+        xs: PyReadonlyArray1<f64>,
+        ys: PyReadonlyArray1<f64>,
+        zs: PyReadonlyArray1<f64>,
+        header: IrapHeader,
+        method: &str,
+        power: f64,
+        radius: Option<f64>,
+        max_neighbours: usize,
+        tension: f64,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> PyResult<IrapSurface> {
+        let method = parse_gridding_method(
+            method,
+            power,
+            radius.unwrap_or(f64::INFINITY),
+            max_neighbours,
+            tension,
+            max_iterations,
+            tolerance,
+        )?;
+        let mut header = header;
+        utils::fill_header(&mut header);
+        let irap = gridding::from_points(
+            xs.as_slice()?,
+            ys.as_slice()?,
+            zs.as_slice()?,
+            &header,
+            &method,
+        )
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &irap)
+    }
+
+    /// Contour lines as a list of (n, 3) arrays of x, y and level.
+    fn contours(&self, py: Python, levels: Vec<f64>) -> PyResult<Vec<Py<PyArray2<f64>>>> {
+        let irap = surface_to_irap(py, self)?;
+        let polygons = contours::to_polygons(&irap.contours(&levels));
+        Ok(polygons
+            .polygons
+            .iter()
+            .map(|p| xyz_to_pyarray(py, p))
+            .collect())
+    }
+
+    fn contours_to_geojson(&self, py: Python, levels: Vec<f64>) -> PyResult<String> {
+        let irap = surface_to_irap(py, self)?;
+        contours::to_geojson_string(&irap.contours(&levels))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Filled bands between consecutive levels, with area per band.
+    fn isobands(&self, py: Python, levels: Vec<f64>) -> PyResult<Vec<PyIsoband>> {
+        let irap = surface_to_irap(py, self)?;
+        Ok(irap
+            .isobands(&levels)
+            .iter()
+            .map(|band| isoband_to_py(py, band))
+            .collect())
+    }
+
+    fn isobands_to_geojson(&self, py: Python, levels: Vec<f64>) -> PyResult<String> {
+        let irap = surface_to_irap(py, self)?;
+        contours::isobands_to_geojson_string(&irap.isobands(&levels))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Dip magnitude in degrees.
+    fn slope(&self, py: Python) -> PyResult<IrapSurface> {
+        let irap = surface_to_irap(py, self)?;
+        irap_to_surface(py, &derivatives::slope(&irap))
+    }
+
+    /// Dip azimuth in degrees clockwise from north.
+    fn aspect(&self, py: Python) -> PyResult<IrapSurface> {
+        let irap = surface_to_irap(py, self)?;
+        irap_to_surface(py, &derivatives::aspect(&irap))
+    }
+
+    #[pyo3(signature = (kind = "mean"))]
+    fn curvature(&self, py: Python, kind: &str) -> PyResult<IrapSurface> {
+        let kind = match kind {
+            "mean" => derivatives::Curvature::Mean,
+            "gaussian" => derivatives::Curvature::Gaussian,
+            "most_positive" => derivatives::Curvature::MostPositive,
+            "most_negative" => derivatives::Curvature::MostNegative,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown curvature kind: {}",
+                    kind
+                )));
+            }
+        };
+        let irap = surface_to_irap(py, self)?;
+        irap_to_surface(py, &derivatives::curvature(&irap, kind))
+    }
+
+    #[pyo3(signature = (radius, units = "nodes"))]
+    fn mean_filter(&self, py: Python, radius: f64, units: &str) -> PyResult<IrapSurface> {
+        let radius = parse_radius(radius, units)?;
+        let irap = surface_to_irap(py, self)?;
+        let filtered = irap
+            .mean_filter(radius)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &filtered)
+    }
+
+    #[pyo3(signature = (radius, units = "nodes"))]
+    fn median_filter(&self, py: Python, radius: f64, units: &str) -> PyResult<IrapSurface> {
+        let radius = parse_radius(radius, units)?;
+        let irap = surface_to_irap(py, self)?;
+        let filtered = irap
+            .median_filter(radius)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &filtered)
+    }
+
+    #[pyo3(signature = (sigma, units = "nodes"))]
+    fn gaussian_filter(&self, py: Python, sigma: f64, units: &str) -> PyResult<IrapSurface> {
+        let sigma = parse_radius(sigma, units)?;
+        let irap = surface_to_irap(py, self)?;
+        let filtered = irap
+            .gaussian_filter(sigma)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &filtered)
+    }
+
+    #[pyo3(signature = (radius, threshold = 3.0, units = "nodes"))]
+    fn despike(
+        &self,
+        py: Python,
+        radius: f64,
+        threshold: f64,
+        units: &str,
+    ) -> PyResult<IrapSurface> {
+        let radius = parse_radius(radius, units)?;
+        let irap = surface_to_irap(py, self)?;
+        let despiked = irap
+            .despike(radius, threshold)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &despiked)
+    }
+
+    /// Fill undefined nodes, returning the filled surface and a boolean mask
+    /// of the nodes that were filled.
+    #[pyo3(signature = (
+        method = "nearest", max_distance = None, extrapolate = false, power = 2.0,
+        max_neighbours = 8
+    ))]
+    fn fill_undefined<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        max_distance: Option<f64>,
+        extrapolate: bool,
+        power: f64,
+        max_neighbours: usize,
+    ) -> PyResult<(IrapSurface, Bound<'py, PyArray2<bool>>)> {
+        let method = match method {
+            "nearest" => fill::FillMethod::Nearest,
+            "inverse_distance" => fill::FillMethod::InverseDistance {
+                power,
+                max_neighbours,
+            },
+            "laplace" => fill::FillMethod::Laplace,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown fill method: {}",
+                    method
+                )));
+            }
+        };
+        let extent = if extrapolate {
+            fill::FillExtent::Extrapolate
+        } else {
+            fill::FillExtent::Enclosed
+        };
+        let irap = surface_to_irap(py, self)?;
+        let (filled, mask) =
+            irap.fill_undefined(&method, max_distance.unwrap_or(f64::INFINITY), extent);
+        let shape = (filled.header.ncol as usize, filled.header.nrow as usize);
+        let mask = Array2::from_shape_vec(shape, mask).expect("Error reshaping array");
+        Ok((irap_to_surface(py, &filled)?, mask.into_pyarray(py)))
+    }
+
+    /// Gross rock volume between this surface as the top and `base`.
+    ///
+    /// `contact` is a constant depth or a surface, and `polygon` an (n, 2) or
+    /// (n, 3) array of the ring to restrict the volume to.
+    #[pyo3(signature = (
+        base, contact = None, polygon = None, slice_interval = None, subdivisions = 4
+    ))]
+    fn volumetrics(
+        &self,
+        py: Python,
+        base: &IrapSurface,
+        contact: Option<&Bound<'_, PyAny>>,
+        polygon: Option<Py<PyArray2<f64>>>,
+        slice_interval: Option<f64>,
+        subdivisions: usize,
+    ) -> PyResult<PyVolumetrics> {
+        let top = surface_to_irap(py, self)?;
+        let base = surface_to_irap(py, base)?;
+        let contact_surface = match contact {
+            Some(c) if c.is_instance_of::<IrapSurface>() => {
+                Some(surface_to_irap(py, &c.extract::<IrapSurface>()?)?)
+            }
+            _ => None,
+        };
+        let contact = match (contact, &contact_surface) {
+            (_, Some(surface)) => Some(volumetrics::Contact::Surface(surface)),
+            (Some(c), None) => Some(volumetrics::Contact::Level(c.extract::<f64>()?)),
+            (None, None) => None,
+        };
+        let polygon = polygon.map(|p| pyarray_to_xy(py, &p)).transpose()?;
+        let options = volumetrics::VolumeOptions {
+            contact,
+            polygon: polygon.as_deref(),
+            slice_interval,
+            subdivisions,
+        };
+        let result = volumetrics::volumetrics(&top, &base, &options)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(volumetrics_to_py(py, &result))
+    }
+
+    /// Resolve crossings in a stack of surfaces ordered from youngest to
+    /// oldest. `rule` is "erosion", "onlap" or "minimum_thickness".
+    #[staticmethod]
+    #[pyo3(signature = (surfaces, rule = "erosion", thickness = 0.0))]
+    fn apply_stack_rule(
+        py: Python,
+        surfaces: Vec<IrapSurface>,
+        rule: &str,
+        thickness: f64,
+    ) -> PyResult<PyStack> {
+        let rule = match rule {
+            "erosion" => stratigraphy::StackRule::Erosion,
+            "onlap" => stratigraphy::StackRule::Onlap,
+            "minimum_thickness" => stratigraphy::StackRule::MinimumThickness(thickness),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown stack rule: {}",
+                    rule
+                )));
+            }
+        };
+        let surfaces: Vec<Irap> = surfaces
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        let stack = stratigraphy::apply_rule(&surfaces, rule)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        stack_to_py(py, &stack)
+    }
+
+    /// Thickness between each pair of consecutive surfaces.
+    #[staticmethod]
+    fn isochores(py: Python, surfaces: Vec<IrapSurface>) -> PyResult<Vec<IrapSurface>> {
+        let surfaces: Vec<Irap> = surfaces
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        stratigraphy::isochores(&surfaces)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+            .iter()
+            .map(|irap| irap_to_surface(py, irap))
+            .collect()
+    }
+
+    /// Resample onto the smallest lattice with rotation `rot` covering this
+    /// one, keeping the increments unless given.
+    #[pyo3(signature = (rot, xinc = None, yinc = None))]
+    fn rotate_to(
+        &self,
+        py: Python,
+        rot: f64,
+        xinc: Option<f64>,
+        yinc: Option<f64>,
+    ) -> PyResult<IrapSurface> {
+        let irap = surface_to_irap(py, self)?;
+        let xinc = xinc.unwrap_or(irap.header.xinc);
+        let yinc = yinc.unwrap_or(irap.header.yinc);
+        let rotated = irap
+            .rotate_to(rot, xinc, yinc)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &rotated)
+    }
+
+    /// Keep every `fx`-th column and `fy`-th row, aggregating blocks of
+    /// nodes with "mean", "min", "max" or "median".
+    #[pyo3(signature = (fx, fy = None, agg = "mean", min_defined_fraction = 0.5))]
+    fn coarsen(
+        &self,
+        py: Python,
+        fx: usize,
+        fy: Option<usize>,
+        agg: &str,
+        min_defined_fraction: f64,
+    ) -> PyResult<IrapSurface> {
+        let fy = fy.unwrap_or(fx);
+        let agg = match agg {
+            "mean" => resample::Aggregation::Mean,
+            "min" => resample::Aggregation::Min,
+            "max" => resample::Aggregation::Max,
+            "median" => resample::Aggregation::Median,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown aggregation: {}",
+                    agg
+                )));
+            }
+        };
+        let irap = surface_to_irap(py, self)?;
+        let coarse = irap
+            .coarsen(fx, fy, agg, min_defined_fraction)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &coarse)
+    }
+
+    /// Insert nodes between the existing ones, interpolating with
+    /// "bilinear" or "bicubic".
+    #[pyo3(signature = (fx, fy = None, interp = "bilinear"))]
+    fn refine(
+        &self,
+        py: Python,
+        fx: usize,
+        fy: Option<usize>,
+        interp: &str,
+    ) -> PyResult<IrapSurface> {
+        let fy = fy.unwrap_or(fx);
+        let interp = match interp {
+            "bilinear" => resample::Interpolation::Bilinear,
+            "bicubic" => resample::Interpolation::Bicubic,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown interpolation: {}",
+                    interp
+                )));
+            }
+        };
+        let irap = surface_to_irap(py, self)?;
+        let fine = irap
+            .refine(fx, fy, interp)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &fine)
+    }
+
+    /// Distance, x, y and sampled value along a polyline given as an (n, 2)
+    /// or (n, 3) array, every `spacing` world units and at each vertex.
+    #[allow(clippy::type_complexity)]
+    fn profile<'py>(
+        &self,
+        py: Python<'py>,
+        polyline: Py<PyArray2<f64>>,
+        spacing: f64,
+    ) -> PyResult<(
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+    )> {
+        let polyline = pyarray_to_xy(py, &polyline)?;
+        let irap = surface_to_irap(py, self)?;
+        let profile = irap
+            .profile(&polyline, spacing)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok((
+            profile.distance.into_pyarray(py),
+            profile.x.into_pyarray(py),
+            profile.y.into_pyarray(py),
+            profile.values.into_pyarray(py),
+        ))
+    }
+
+    /// Like `profile` for several surfaces, with the values as an (n, m)
+    /// array with one column per surface.
+    #[staticmethod]
+    #[allow(clippy::type_complexity)]
+    fn profiles<'py>(
+        py: Python<'py>,
+        surfaces: Vec<IrapSurface>,
+        polyline: Py<PyArray2<f64>>,
+        spacing: f64,
+    ) -> PyResult<(
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray2<f64>>,
+    )> {
+        let polyline = pyarray_to_xy(py, &polyline)?;
+        let surfaces: Vec<Irap> = surfaces
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        let profiles = profile::profiles(&surfaces, &polyline, spacing)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let n = profiles.distance.len();
+        let values = Array2::from_shape_fn((n, surfaces.len()), |(i, k)| profiles.values[k][i]);
+        Ok((
+            profiles.distance.into_pyarray(py),
+            profiles.x.into_pyarray(py),
+            profiles.y.into_pyarray(py),
+            values.into_pyarray(py),
+        ))
+    }
+
+    /// Statistics per zone, where `zones` is a `Polygons`, a list of (n, 2)
+    /// or (n, 3) arrays, or a surface of integer zone numbers.
+    fn zonal_stats(&self, py: Python, zones: &Bound<'_, PyAny>) -> PyResult<PyZonalStats> {
+        let irap = surface_to_irap(py, self)?;
+        let stats = if let Ok(surface) = zones.extract::<IrapSurface>() {
+            let zone_surface = surface_to_irap(py, &surface)?;
+            zonal::zonal_stats(&irap, &zonal::Zones::Surface(&zone_surface))
+        } else {
+            let rings: Vec<Py<PyArray2<f64>>> = match zones.cast::<PyPolygons>() {
+                Ok(polygons) => polygons
+                    .borrow()
+                    .values
+                    .iter()
+                    .map(|v| v.clone_ref(py))
+                    .collect(),
+                Err(_) => zones.extract()?,
+            };
+            let polygons = Polygons {
+                polygons: rings
+                    .iter()
+                    .map(|r| {
+                        Ok(pyarray_to_xy(py, r)?
+                            .into_iter()
+                            .map(|[x, y]| [x, y, 0.0])
+                            .collect())
+                    })
+                    .collect::<PyResult<_>>()?,
+            };
+            zonal::zonal_stats(&irap, &zonal::Zones::Polygons(&polygons))
+        }
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(zonal_stats_to_py(py, &stats))
+    }
+
+    /// Merge overlapping surfaces with mode "first", "mean" or "feather",
+    /// onto `header` or the union of their extents.
+    #[staticmethod]
+    #[pyo3(signature = (surfaces, header = None, mode = "first", width = None))]
+    fn merge(
+        py: Python,
+        surfaces: Vec<IrapSurface>,
+        header: Option<IrapHeader>,
+        mode: &str,
+        width: Option<f64>,
+    ) -> PyResult<IrapSurface> {
+        let mode = match (mode, width) {
+            ("first", _) => merge::MergeMode::First,
+            ("mean", _) => merge::MergeMode::Mean,
+            ("feather", Some(width)) => merge::MergeMode::Feather { width },
+            ("feather", None) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Feathering requires a width",
+                ));
+            }
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown merge mode: {}",
+                    mode
+                )));
+            }
+        };
+        let header = header.map(|mut header| {
+            utils::fill_header(&mut header);
+            header
+        });
+        let surfaces: Vec<Irap> = surfaces
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        let merged = merge::merge(&surfaces, header.as_ref(), mode)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        irap_to_surface(py, &merged)
+    }
+
+    /// Structural closures from a fill-and-spill analysis. Undefined nodes
+    /// are sealing boundaries, or sinks when `undefined` is "sink".
+    #[pyo3(signature = (undefined = "boundary"))]
+    fn closures(&self, py: Python, undefined: &str) -> PyResult<Vec<PyClosure>> {
+        let undefined = parse_undefined_nodes(undefined)?;
+        let irap = surface_to_irap(py, self)?;
+        Ok(irap
+            .closures(undefined)
+            .iter()
+            .map(|c| closure_to_py(py, c))
+            .collect())
+    }
+
+    /// Closure outlines as polygons with the spill depth as z value.
+    #[pyo3(signature = (undefined = "boundary"))]
+    fn closure_polygons(&self, py: Python, undefined: &str) -> PyResult<PyPolygons> {
+        let undefined = parse_undefined_nodes(undefined)?;
+        let irap = surface_to_irap(py, self)?;
+        Ok(polygons_to_py(
+            py,
+            &closures::to_polygons(&irap.closures(undefined)),
+        ))
+    }
+
+    /// Azimuth of steepest ascent, with method "d8" or "dinf".
+    #[pyo3(signature = (method = "d8"))]
+    fn flow_direction(&self, py: Python, method: &str) -> PyResult<IrapSurface> {
+        let method = parse_flow_method(method)?;
+        let irap = surface_to_irap(py, self)?;
+        irap_to_surface(py, &irap.flow_direction(method))
+    }
+
+    /// Area whose steepest ascent paths pass through each node.
+    #[pyo3(signature = (method = "d8"))]
+    fn flow_accumulation(&self, py: Python, method: &str) -> PyResult<IrapSurface> {
+        let method = parse_flow_method(method)?;
+        let irap = surface_to_irap(py, self)?;
+        irap_to_surface(py, &irap.flow_accumulation(method))
+    }
+
+    /// Steepest ascent paths from the (n, 2) or (n, 3) array of start
+    /// points, as a list of (m, 2) arrays.
+    #[pyo3(signature = (starts, method = "d8"))]
+    fn flow_paths(
+        &self,
+        py: Python,
+        starts: Py<PyArray2<f64>>,
+        method: &str,
+    ) -> PyResult<Vec<Py<PyArray2<f64>>>> {
+        let method = parse_flow_method(method)?;
+        let starts = pyarray_to_xy(py, &starts)?;
+        let irap = surface_to_irap(py, self)?;
+        Ok(irap
+            .flow_paths(&starts, method)
+            .iter()
+            .map(|path| {
+                let flat = path.iter().flatten().copied().collect();
+                Array2::from_shape_vec((path.len(), 2), flat)
+                    .expect("Error reshaping array")
+                    .into_pyarray(py)
+                    .into()
+            })
+            .collect())
+    }
+
+    /// Closures, the index of the closure each node drains into, and the
+    /// drainage area of each closure.
+    #[pyo3(signature = (undefined = "boundary"))]
+    #[allow(clippy::type_complexity)]
+    fn drainage<'py>(
+        &self,
+        py: Python<'py>,
+        undefined: &str,
+    ) -> PyResult<(Vec<PyClosure>, IrapSurface, Bound<'py, PyArray1<f64>>)> {
+        let undefined = parse_undefined_nodes(undefined)?;
+        let irap = surface_to_irap(py, self)?;
+        let drainage = irap.drainage(undefined);
+        Ok((
+            drainage
+                .closures
+                .iter()
+                .map(|c| closure_to_py(py, c))
+                .collect(),
+            irap_to_surface(py, &drainage.labels)?,
+            drainage.areas.into_pyarray(py),
+        ))
+    }
+
+    /// Local extrema of kind "maximum", "minimum" or "saddle".
+    #[pyo3(signature = (kind = "maximum", min_prominence = 0.0, window = 1))]
+    fn extrema(
+        &self,
+        py: Python,
+        kind: &str,
+        min_prominence: f64,
+        window: usize,
+    ) -> PyResult<PyExtrema> {
+        let kind = match kind {
+            "maximum" => extrema::ExtremumKind::Maximum,
+            "minimum" => extrema::ExtremumKind::Minimum,
+            "saddle" => extrema::ExtremumKind::Saddle,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown extremum kind: {}",
+                    kind
+                )));
+            }
+        };
+        let irap = surface_to_irap(py, self)?;
+        Ok(extrema_to_py(
+            py,
+            &irap.extrema(kind, min_prominence, window),
+        ))
+    }
+
+    /// Lines where `other` cuts this surface, with the depth of this surface
+    /// as z value. `other` is resampled onto this lattice when they differ.
+    fn intersection(&self, py: Python, other: &IrapSurface) -> PyResult<PyPolygons> {
+        let a = surface_to_irap(py, self)?;
+        let b = surface_to_irap(py, other)?;
+        Ok(polygons_to_py(py, &intersection::intersection(&a, &b)))
+    }
+
+    /// Depth surfaces from time surfaces in a layer-cake model, one layer per
+    /// surface from the top down. Each velocity is a constant interval
+    /// velocity, an interval velocity surface, or a tuple (v0, k) for the
+    /// linear velocity v0 + k * z. `unit` is "ms" or "s".
+    #[staticmethod]
+    #[pyo3(signature = (times, velocities, unit = "ms", two_way = true))]
+    fn time_to_depth(
+        py: Python,
+        times: Vec<IrapSurface>,
+        velocities: Vec<Bound<'_, PyAny>>,
+        unit: &str,
+        two_way: bool,
+    ) -> PyResult<Vec<IrapSurface>> {
+        let convention = parse_time_convention(unit, two_way)?;
+        let times: Vec<Irap> = times
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        let surfaces = velocity_surfaces(py, &velocities)?;
+        let velocities = parse_velocities(&velocities, &surfaces)?;
+        depth_conversion::time_to_depth(&times, &velocities, convention)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+            .iter()
+            .map(|irap| irap_to_surface(py, irap))
+            .collect()
+    }
+
+    /// Time surfaces from depth surfaces, the inverse of `time_to_depth`.
+    #[staticmethod]
+    #[pyo3(signature = (depths, velocities, unit = "ms", two_way = true))]
+    fn depth_to_time(
+        py: Python,
+        depths: Vec<IrapSurface>,
+        velocities: Vec<Bound<'_, PyAny>>,
+        unit: &str,
+        two_way: bool,
+    ) -> PyResult<Vec<IrapSurface>> {
+        let convention = parse_time_convention(unit, two_way)?;
+        let depths: Vec<Irap> = depths
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        let surfaces = velocity_surfaces(py, &velocities)?;
+        let velocities = parse_velocities(&velocities, &surfaces)?;
+        depth_conversion::depth_to_time(&depths, &velocities, convention)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+            .iter()
+            .map(|irap| irap_to_surface(py, irap))
+            .collect()
+    }
+
+    /// Points where a well trajectory, an (n, 4) array of x, y, z and md,
+    /// crosses this surface, as an (m, 5) array of md, x, y, z and the
+    /// surface value.
+    fn intersect_well(
+        &self,
+        py: Python,
+        trajectory: Py<PyArray2<f64>>,
+    ) -> PyResult<Py<PyArray2<f64>>> {
+        let trajectory = pyarray_to_trajectory(py, &trajectory)?;
+        let irap = surface_to_irap(py, self)?;
+        let crossings = wells::intersect_well(&irap, &trajectory)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(crossings_to_pyarray(py, &crossings))
+    }
+
+    /// Like `intersect_well` for several surfaces, with one array per surface.
+    #[staticmethod]
+    fn intersect_well_batch(
+        py: Python,
+        surfaces: Vec<IrapSurface>,
+        trajectory: Py<PyArray2<f64>>,
+    ) -> PyResult<Vec<Py<PyArray2<f64>>>> {
+        let trajectory = pyarray_to_trajectory(py, &trajectory)?;
+        let surfaces: Vec<Irap> = surfaces
+            .iter()
+            .map(|s| surface_to_irap(py, s))
+            .collect::<PyResult<_>>()?;
+        let crossings = wells::intersect_well_batch(&surfaces, &trajectory)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(crossings
+            .iter()
+            .map(|c| crossings_to_pyarray(py, c))
+            .collect())
+    }
+
+    /// Adjust the surface to pass through the points by gridding the
+    /// residuals with `method`, tapered to zero at `radius`. Returns the
+    /// adjusted surface and, per point, the residual before and the misfit
+    /// after the adjustment.
+    #[pyo3(signature = (
+        xs, ys, zs, radius, method = "minimum_curvature", power = 2.0,
+        max_neighbours = 12, tension = 0.25, max_iterations = 2000, tolerance = 1e-4
+    ))]
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn tie_to_points<'py>(
+        &self,
+        py: Python<'py>,
+        xs: PyReadonlyArray1<f64>,
+        ys: PyReadonlyArray1<f64>,
+        zs: PyReadonlyArray1<f64>,
+        radius: f64,
+        method: &str,
+        power: f64,
+        max_neighbours: usize,
+        tension: f64,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> PyResult<(
+        IrapSurface,
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+    )> {
+        let method = parse_gridding_method(
+            method,
+            power,
+            radius,
+            max_neighbours,
+            tension,
+            max_iterations,
+            tolerance,
+        )?;
+        let irap = surface_to_irap(py, self)?;
+        let tie = irap
+            .tie_to_points(
+                xs.as_slice()?,
+                ys.as_slice()?,
+                zs.as_slice()?,
+                &method,
+                radius,
+            )
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok((
+            irap_to_surface(py, &tie.surface)?,
+            tie.residuals.into_pyarray(py),
+            tie.misfits.into_pyarray(py),
+        ))
+    }
+
+    /// Least-squares polynomial trend of order 1 to 3 fitted to the defined
+    /// nodes, returned with the trend and residual surfaces.
+    #[pyo3(signature = (order = 1))]
+    fn fit_trend(&self, py: Python, order: usize) -> PyResult<(PyTrend, IrapSurface, IrapSurface)> {
+        let irap = surface_to_irap(py, self)?;
+        let fit = irap
+            .fit_trend(order)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok((
+            trend_to_py(&fit.trend),
+            irap_to_surface(py, &fit.surface)?,
+            irap_to_surface(py, &fit.residual)?,
+        ))
+    }
+
+    /// Least-squares polynomial trend of order 1 to 3 fitted to scattered points.
+    #[staticmethod]
+    #[pyo3(signature = (xs, ys, zs, order = 1))]
+    fn fit_trend_points(
+        xs: PyReadonlyArray1<f64>,
+        ys: PyReadonlyArray1<f64>,
+        zs: PyReadonlyArray1<f64>,
+        order: usize,
+    ) -> PyResult<PyTrend> {
+        let trend = trend::Trend::fit(xs.as_slice()?, ys.as_slice()?, zs.as_slice()?, order)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(trend_to_py(&trend))
+    }
+
+    /// Experimental variogram of the defined nodes, in `nlags` bins of width
+    /// `lag`. With an `azimuth` in degrees clockwise from north it is
+    /// directional, using pairs within `tolerance` degrees and `bandwidth`
+    /// across it. `max_points` limits the nodes used to a random subset.
+    #[pyo3(signature = (
+        lag, nlags, azimuth = None, tolerance = 22.5, bandwidth = None, max_points = None, seed = 0
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn variogram(
+        &self,
+        py: Python,
+        lag: f64,
+        nlags: usize,
+        azimuth: Option<f64>,
+        tolerance: f64,
+        bandwidth: Option<f64>,
+        max_points: Option<usize>,
+        seed: u64,
+    ) -> PyResult<PyVariogram> {
+        let options =
+            variogram_options(lag, nlags, azimuth, tolerance, bandwidth, max_points, seed);
+        let irap = surface_to_irap(py, self)?;
+        let variogram = irap
+            .variogram(&options)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(variogram_to_py(py, variogram))
+    }
+
+    /// Experimental variogram of scattered points, see `variogram`.
+    #[staticmethod]
+    #[pyo3(signature = (
+        xs, ys, zs, lag, nlags, azimuth = None, tolerance = 22.5, bandwidth = None,
+        max_points = None, seed = 0
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn variogram_points(
+        py: Python,
+        xs: PyReadonlyArray1<f64>,
+        ys: PyReadonlyArray1<f64>,
+        zs: PyReadonlyArray1<f64>,
+        lag: f64,
+        nlags: usize,
+        azimuth: Option<f64>,
+        tolerance: f64,
+        bandwidth: Option<f64>,
+        max_points: Option<usize>,
+        seed: u64,
+    ) -> PyResult<PyVariogram> {
+        let options =
+            variogram_options(lag, nlags, azimuth, tolerance, bandwidth, max_points, seed);
+        let variogram =
+            variogram::from_points(xs.as_slice()?, ys.as_slice()?, zs.as_slice()?, &options)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(variogram_to_py(py, variogram))
+    }
+
+    /// Krige scattered points onto the lattice given by `header`, with
+    /// `kind` "ordinary" or "simple" around `mean`. The model range is along
+    /// `azimuth`, in degrees clockwise from north, and `minor_range` across
+    /// it. Returns the surface and, with `return_std`, the kriging standard
+    /// deviation.
+    #[staticmethod]
+    #[pyo3(signature = (
+        xs, ys, zs, header, model, kind = "ordinary", mean = 0.0, azimuth = 0.0,
+        minor_range = None, radius = None, max_points = 16, return_std = false
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn krige(
+        py: Python,
+        xs: PyReadonlyArray1<f64>,
+        ys: PyReadonlyArray1<f64>,
+        zs: PyReadonlyArray1<f64>,
+        header: IrapHeader,
+        model: PyVariogramModel,
+        kind: &str,
+        mean: f64,
+        azimuth: f64,
+        minor_range: Option<f64>,
+        radius: Option<f64>,
+        max_points: usize,
+        return_std: bool,
+    ) -> PyResult<(IrapSurface, Option<IrapSurface>)> {
+        let kind = match kind {
+            "simple" => gridding::KrigingType::Simple { mean },
+            "ordinary" => gridding::KrigingType::Ordinary,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown kriging type: {}, expected 'simple' or 'ordinary'",
+                    kind
+                )));
+            }
+        };
+        let options = gridding::KrigingOptions {
+            kind,
+            model: model.to_model()?,
+            anisotropy: minor_range.map(|minor_range| gridding::Anisotropy {
+                azimuth,
+                minor_range,
+            }),
+            radius: radius.unwrap_or(f64::INFINITY),
+            max_points,
+            std: return_std,
+        };
+        let mut header = header;
+        utils::fill_header(&mut header);
+        let kriging = gridding::krige(
+            xs.as_slice()?,
+            ys.as_slice()?,
+            zs.as_slice()?,
+            &header,
+            &options,
+        )
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok((
+            irap_to_surface(py, &kriging.surface)?,
+            kriging
+                .std
+                .map(|std| irap_to_surface(py, &std))
+                .transpose()?,
+        ))
+    }
+
+    /// Gaussian random field realizations on the lattice given by `header`,
+    /// with the covariance of `model` around `mean`. The model range is along
+    /// `azimuth`, in degrees clockwise from north, and `minor_range` across
+    /// it. Given `xs`, `ys` and `zs`, the realizations are conditioned on the
+    /// points by simple kriging. Realization `k` only depends on `seed` and `k`.
+    #[staticmethod]
+    #[pyo3(signature = (
+        header, model, count = 1, mean = 0.0, azimuth = 0.0, minor_range = None,
+        seed = 0, xs = None, ys = None, zs = None, radius = None, max_points = 16
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn simulate(
+        py: Python,
+        header: IrapHeader,
+        model: PyVariogramModel,
+        count: usize,
+        mean: f64,
+        azimuth: f64,
+        minor_range: Option<f64>,
+        seed: u64,
+        xs: Option<PyReadonlyArray1<f64>>,
+        ys: Option<PyReadonlyArray1<f64>>,
+        zs: Option<PyReadonlyArray1<f64>>,
+        radius: Option<f64>,
+        max_points: usize,
+    ) -> PyResult<Vec<IrapSurface>> {
+        let data = conditioning_data(&xs, &ys, &zs)?;
+        let options = simulation_options(
+            &model,
+            mean,
+            azimuth,
+            minor_range,
+            seed,
+            data,
+            radius,
+            max_points,
+        )?;
+        let mut header = header;
+        utils::fill_header(&mut header);
+        simulation::simulate(&header, &options, count)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+            .iter()
+            .map(|irap| irap_to_surface(py, irap))
+            .collect()
+    }
+
+    /// Write `count` realizations, see `simulate`, as IRAP binary files
+    /// without keeping them all in memory. `pattern` must contain `{}`,
+    /// which is replaced by the realization number. Returns the paths.
+    #[staticmethod]
+    #[pyo3(signature = (
+        pattern, header, model, count, mean = 0.0, azimuth = 0.0, minor_range = None,
+        seed = 0, xs = None, ys = None, zs = None, radius = None, max_points = 16
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn simulate_to_files(
+        pattern: &str,
+        header: IrapHeader,
+        model: PyVariogramModel,
+        count: usize,
+        mean: f64,
+        azimuth: f64,
+        minor_range: Option<f64>,
+        seed: u64,
+        xs: Option<PyReadonlyArray1<f64>>,
+        ys: Option<PyReadonlyArray1<f64>>,
+        zs: Option<PyReadonlyArray1<f64>>,
+        radius: Option<f64>,
+        max_points: usize,
+    ) -> PyResult<Vec<String>> {
+        let data = conditioning_data(&xs, &ys, &zs)?;
+        let options = simulation_options(
+            &model,
+            mean,
+            azimuth,
+            minor_range,
+            seed,
+            data,
+            radius,
+            max_points,
+        )?;
+        let mut header = header;
+        utils::fill_header(&mut header);
+        simulation::simulate_to_files(&header, &options, count, pattern)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
     fn to_ascii_string(&self, py: Python) -> PyResult<String> {
         let arr = self.values.as_ref();
-        let arr = arr.cast_bound::<PyArray2<f32>>(py).unwrap();
+        let arr = arr.cast_bound::<PyArray2<f32>>(py)?;
         let f_ordered = arr.is_fortran_contiguous();
 
         if f_ordered {
             let slice: &[f32] = unsafe { std::slice::from_raw_parts(arr.data(), arr.len()) };
-            let mut header: IrapHeader = self
-                .header
-                .extract(py)
-                .expect("Unable to extract Irap header");
-            utils::fill_header(&mut header);
+            let header = surface_header(py, self)?;
             irap::ascii::to_string_fortran(&header, slice)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
         } else {
-            let data = surface_to_irap(py, self);
+            let data = surface_to_irap(py, self)?;
             irap::ascii::to_string(&data)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
         }
@@ -133,20 +1141,16 @@ impl IrapSurface {
 
     fn to_ascii_file(&self, py: Python, path: String) -> PyResult<()> {
         let arr = self.values.as_ref();
-        let arr = arr.cast_bound::<PyArray2<f32>>(py).unwrap();
+        let arr = arr.cast_bound::<PyArray2<f32>>(py)?;
         let f_ordered = arr.is_fortran_contiguous();
 
         if f_ordered {
             let slice: &[f32] = unsafe { std::slice::from_raw_parts(arr.data(), arr.len()) };
-            let mut header: IrapHeader = self
-                .header
-                .extract(py)
-                .expect("Unable to extract Irap header");
-            utils::fill_header(&mut header);
+            let header = surface_header(py, self)?;
             irap::ascii::to_file_fortran(path, &header, slice)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
         } else {
-            let data = surface_to_irap(py, self);
+            let data = surface_to_irap(py, self)?;
             irap::ascii::to_file(path, &data)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
         }
@@ -154,20 +1158,16 @@ impl IrapSurface {
 
     fn to_binary_buffer<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         let arr = self.values.as_ref();
-        let arr = arr.cast_bound::<PyArray2<f32>>(py).unwrap();
+        let arr = arr.cast_bound::<PyArray2<f32>>(py)?;
         let f_ordered = arr.is_fortran_contiguous();
 
         let bytes = if f_ordered {
             let slice: &[f32] = unsafe { std::slice::from_raw_parts(arr.data(), arr.len()) };
-            let mut header: IrapHeader = self
-                .header
-                .extract(py)
-                .expect("Unable to extract Irap header");
-            utils::fill_header(&mut header);
+            let header = surface_header(py, self)?;
             irap::binary::to_buffer_fortran(&header, slice)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
         } else {
-            let data = surface_to_irap(py, self);
+            let data = surface_to_irap(py, self)?;
             irap::binary::to_buffer(&data)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
         };
@@ -177,26 +1177,62 @@ impl IrapSurface {
 
     fn to_binary_file(&self, py: Python, path: String) -> PyResult<()> {
         let arr = self.values.as_ref();
-        let arr = arr.cast_bound::<PyArray2<f32>>(py).unwrap();
+        let arr = arr.cast_bound::<PyArray2<f32>>(py)?;
         let f_ordered = arr.is_fortran_contiguous();
 
         if f_ordered {
             let slice: &[f32] = unsafe { std::slice::from_raw_parts(arr.data(), arr.len()) };
-            let mut header: IrapHeader = self
-                .header
-                .extract(py)
-                .expect("Unable to extract Irap header");
-            utils::fill_header(&mut header);
+            let header = surface_header(py, self)?;
             irap::binary::to_file_fortran(path, &header, slice)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
         } else {
-            let data = surface_to_irap(py, self);
+            let data = surface_to_irap(py, self)?;
             irap::binary::to_file(path, &data)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
         }
     }
 }
 
+fn parse_gridding_method(
+    method: &str,
+    power: f64,
+    radius: f64,
+    max_neighbours: usize,
+    tension: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> PyResult<gridding::GriddingMethod> {
+    match method {
+        "nearest" => Ok(gridding::GriddingMethod::Nearest),
+        "inverse_distance" => Ok(gridding::GriddingMethod::InverseDistance {
+            power,
+            radius,
+            max_neighbours,
+        }),
+        "linear" => Ok(gridding::GriddingMethod::Linear),
+        "minimum_curvature" => Ok(gridding::GriddingMethod::MinimumCurvature {
+            tension,
+            max_iterations,
+            tolerance,
+        }),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown gridding method: {}",
+            method
+        ))),
+    }
+}
+
+fn parse_radius(radius: f64, units: &str) -> PyResult<filters::Radius> {
+    match units {
+        "nodes" => Ok(filters::Radius::Nodes(radius)),
+        "world" => Ok(filters::Radius::World(radius)),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown units: {}, expected 'nodes' or 'world'",
+            units
+        ))),
+    }
+}
+
 pub fn irap_to_surface<'py>(py: Python<'py>, irap: &Irap) -> PyResult<IrapSurface> {
     let h = irap.header.clone();
     let shape = (h.ncol as usize, h.nrow as usize);
@@ -208,22 +1244,816 @@ pub fn irap_to_surface<'py>(py: Python<'py>, irap: &Irap) -> PyResult<IrapSurfac
     })
 }
 
-fn surface_to_irap(py: Python, surface: &IrapSurface) -> Irap {
-    let mut header: IrapHeader = surface
-        .header
-        .extract(py)
-        .expect("Unable to extract Irap header");
+/// Header of `surface` with its derived fields filled, checked against the
+/// shape of the values.
+fn surface_header(py: Python, surface: &IrapSurface) -> PyResult<IrapHeader> {
+    let mut header: IrapHeader = surface.header.extract(py)?;
     utils::fill_header(&mut header);
-    let arr = surface.values.as_ref();
-    let arr = arr.cast_bound::<PyArray2<f32>>(py).unwrap();
+    let shape = surface.values.bind(py).shape().to_vec();
+    if shape != [header.ncol as usize, header.nrow as usize] {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Values of shape {:?} do not match ncol={}, nrow={}",
+            shape, header.ncol, header.nrow
+        )));
+    }
+    Ok(header)
+}
+
+fn surface_to_irap(py: Python, surface: &IrapSurface) -> PyResult<Irap> {
+    let header = surface_header(py, surface)?;
+    let arr = surface.values.bind(py).readonly();
+    let values = arr.as_array().iter().copied().collect();
+    Ok(Irap { header, values })
+}
+
+#[pyclass(name = "Points")]
+#[derive(Debug)]
+pub struct PyPoints {
+    #[pyo3(get, set)]
+    pub values: Py<PyArray2<f64>>,
+}
+
+#[pymethods]
+impl PyPoints {
+    #[new]
+    fn py_new(values: Py<PyArray2<f64>>) -> Self {
+        PyPoints { values }
+    }
+
+    fn __repr__(&self, py: Python) -> String {
+        format!("<Points(n={})>", self.values.bind(py).shape()[0])
+    }
+
+    fn __str__(&self, py: Python) -> String {
+        self.__repr__(py)
+    }
+
+    fn __len__(&self, py: Python) -> usize {
+        self.values.bind(py).shape()[0]
+    }
+
+    #[staticmethod]
+    fn from_file(py: Python, path: String) -> PyResult<PyPoints> {
+        let points = irap::points::from_file(path)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+        Ok(points_to_py(py, &points))
+    }
+
+    #[staticmethod]
+    fn from_string(py: Python, data: String) -> PyResult<PyPoints> {
+        let points = irap::points::from_string(&data)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(points_to_py(py, &points))
+    }
+
+    fn to_string(&self, py: Python) -> PyResult<String> {
+        let points = py_to_points(py, &self.values)?;
+        irap::points::to_string(&points)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    fn to_file(&self, py: Python, path: String) -> PyResult<()> {
+        let points = py_to_points(py, &self.values)?;
+        irap::points::to_file(path, &points)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
+    }
+}
+
+#[pyclass(name = "Polygons")]
+#[derive(Debug)]
+pub struct PyPolygons {
+    #[pyo3(get, set)]
+    pub values: Vec<Py<PyArray2<f64>>>,
+}
+
+#[pymethods]
+impl PyPolygons {
+    #[new]
+    fn py_new(values: Vec<Py<PyArray2<f64>>>) -> Self {
+        PyPolygons { values }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Polygons(n={})>", self.values.len())
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn __len__(&self) -> usize {
+        self.values.len()
+    }
+
+    #[staticmethod]
+    fn from_file(py: Python, path: String) -> PyResult<PyPolygons> {
+        let polygons = irap::polygons::from_file(path)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+        Ok(polygons_to_py(py, &polygons))
+    }
+
+    #[staticmethod]
+    fn from_string(py: Python, data: String) -> PyResult<PyPolygons> {
+        let polygons = irap::polygons::from_string(&data)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(polygons_to_py(py, &polygons))
+    }
+
+    fn to_string(&self, py: Python) -> PyResult<String> {
+        let polygons = py_to_polygons(py, &self.values)?;
+        irap::polygons::to_string(&polygons)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    fn to_file(&self, py: Python, path: String) -> PyResult<()> {
+        let polygons = py_to_polygons(py, &self.values)?;
+        irap::polygons::to_file(path, &polygons)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
+    }
+}
+
+/// Filled region between two levels. Each polygon is a list of (n, 2) rings,
+/// the exterior followed by any holes.
+#[pyclass(name = "Isoband", get_all)]
+#[derive(Debug)]
+pub struct PyIsoband {
+    pub lower: f64,
+    pub upper: f64,
+    pub area: f64,
+    pub polygons: Vec<Vec<Py<PyArray2<f64>>>>,
+}
+
+#[pymethods]
+impl PyIsoband {
+    fn __repr__(&self) -> String {
+        format!(
+            "<Isoband(lower={}, upper={}, area={}, polygons={})>",
+            self.lower,
+            self.upper,
+            self.area,
+            self.polygons.len()
+        )
+    }
+}
+
+fn isoband_to_py(py: Python, band: &contours::Isoband) -> PyIsoband {
+    let ring = |r: &[[f64; 2]]| -> Py<PyArray2<f64>> {
+        let flat = r.iter().flatten().copied().collect();
+        let np_arr = Array2::from_shape_vec((r.len(), 2), flat).expect("Error reshaping array");
+        np_arr.into_pyarray(py).into()
+    };
+    PyIsoband {
+        lower: band.lower,
+        upper: band.upper,
+        area: band.area,
+        polygons: band
+            .polygons
+            .iter()
+            .map(|p| {
+                std::iter::once(&p.exterior)
+                    .chain(&p.holes)
+                    .map(|r| ring(r))
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+/// Surfaces after applying a stack rule, with the isochores between them and,
+/// per surface, the change made and the number of adjusted nodes.
+#[pyclass(name = "Stack", get_all)]
+#[derive(Debug)]
+pub struct PyStack {
+    pub surfaces: Vec<IrapSurface>,
+    pub isochores: Vec<IrapSurface>,
+    pub changes: Vec<IrapSurface>,
+    pub adjusted_nodes: Vec<usize>,
+    pub max_changes: Vec<f64>,
+}
+
+#[pymethods]
+impl PyStack {
+    fn __repr__(&self) -> String {
+        format!(
+            "<Stack(surfaces={}, adjusted_nodes={:?})>",
+            self.surfaces.len(),
+            self.adjusted_nodes
+        )
+    }
+}
+
+fn stack_to_py(py: Python, stack: &stratigraphy::Stack) -> PyResult<PyStack> {
+    let convert = |iraps: &mut dyn Iterator<Item = &Irap>| -> PyResult<Vec<IrapSurface>> {
+        iraps.map(|irap| irap_to_surface(py, irap)).collect()
+    };
+    Ok(PyStack {
+        surfaces: convert(&mut stack.surfaces.iter())?,
+        isochores: convert(&mut stack.isochores.iter())?,
+        changes: convert(&mut stack.adjustments.iter().map(|a| &a.change))?,
+        adjusted_nodes: stack.adjustments.iter().map(|a| a.nodes).collect(),
+        max_changes: stack.adjustments.iter().map(|a| a.max_change).collect(),
+    })
+}
+
+/// Structural closure with its crest and spill nodes as (col, row), and the
+/// outline as an (n, 2) array.
+#[pyclass(name = "Closure", get_all)]
+#[derive(Debug)]
+pub struct PyClosure {
+    pub crest: (usize, usize),
+    pub crest_depth: f64,
+    pub spill: (usize, usize),
+    pub spill_depth: f64,
+    pub area: f64,
+    pub outline: Py<PyArray2<f64>>,
+    pub parent: Option<usize>,
+}
+
+#[pymethods]
+impl PyClosure {
+    fn __repr__(&self) -> String {
+        format!(
+            "<Closure(crest_depth={}, spill_depth={}, area={})>",
+            self.crest_depth, self.spill_depth, self.area
+        )
+    }
+}
+
+fn closure_to_py(py: Python, closure: &closures::Closure) -> PyClosure {
+    let flat = closure.outline.iter().flatten().copied().collect();
+    let outline = Array2::from_shape_vec((closure.outline.len(), 2), flat)
+        .expect("Error reshaping array")
+        .into_pyarray(py)
+        .into();
+    PyClosure {
+        crest: closure.crest,
+        crest_depth: closure.crest_depth,
+        spill: closure.spill,
+        spill_depth: closure.spill_depth,
+        area: closure.area,
+        outline,
+        parent: closure.parent,
+    }
+}
+
+fn parse_undefined_nodes(undefined: &str) -> PyResult<closures::UndefinedNodes> {
+    match undefined {
+        "boundary" => Ok(closures::UndefinedNodes::Boundary),
+        "sink" => Ok(closures::UndefinedNodes::Sink),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown handling of undefined nodes: {}, expected 'boundary' or 'sink'",
+            undefined
+        ))),
+    }
+}
+
+fn parse_flow_method(method: &str) -> PyResult<flow::FlowMethod> {
+    match method {
+        "d8" => Ok(flow::FlowMethod::D8),
+        "dinf" => Ok(flow::FlowMethod::DInfinity),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown flow method: {}, expected 'd8' or 'dinf'",
+            method
+        ))),
+    }
+}
+
+fn parse_time_convention(unit: &str, two_way: bool) -> PyResult<depth_conversion::TimeConvention> {
+    let unit = match unit {
+        "ms" => depth_conversion::TimeUnit::Milliseconds,
+        "s" => depth_conversion::TimeUnit::Seconds,
+        _ => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown time unit: {}, expected 'ms' or 's'",
+                unit
+            )));
+        }
+    };
+    Ok(depth_conversion::TimeConvention { unit, two_way })
+}
+
+/// Velocity surfaces among `velocities`, converted ahead of
+/// `parse_velocities` so that they can be borrowed.
+fn velocity_surfaces(py: Python, velocities: &[Bound<'_, PyAny>]) -> PyResult<Vec<Option<Irap>>> {
+    velocities
+        .iter()
+        .map(|v| {
+            if v.is_instance_of::<IrapSurface>() {
+                surface_to_irap(py, &v.extract::<IrapSurface>()?).map(Some)
+            } else {
+                Ok(None)
+            }
+        })
+        .collect()
+}
+
+fn parse_velocities<'a>(
+    velocities: &[Bound<'_, PyAny>],
+    surfaces: &'a [Option<Irap>],
+) -> PyResult<Vec<depth_conversion::Velocity<'a>>> {
+    velocities
+        .iter()
+        .zip(surfaces)
+        .map(|(v, surface)| match surface {
+            Some(surface) => Ok(depth_conversion::Velocity::Surface(surface)),
+            None => match v.extract::<(f64, f64)>() {
+                Ok((v0, k)) => Ok(depth_conversion::Velocity::Linear { v0, k }),
+                Err(_) => Ok(depth_conversion::Velocity::Interval(v.extract::<f64>()?)),
+            },
+        })
+        .collect()
+}
+
+/// Local extrema, as arrays with one entry per extremum.
+#[pyclass(name = "Extrema", get_all)]
+#[derive(Debug)]
+pub struct PyExtrema {
+    pub col: Py<PyArray1<usize>>,
+    pub row: Py<PyArray1<usize>>,
+    pub x: Py<PyArray1<f64>>,
+    pub y: Py<PyArray1<f64>>,
+    pub value: Py<PyArray1<f64>>,
+    pub prominence: Py<PyArray1<f64>>,
+}
+
+#[pymethods]
+impl PyExtrema {
+    fn __repr__(&self, py: Python) -> String {
+        format!("<Extrema(n={})>", self.col.bind(py).len())
+    }
+
+    fn __len__(&self, py: Python) -> usize {
+        self.col.bind(py).len()
+    }
+}
+
+fn extrema_to_py(py: Python, extrema: &[extrema::Extremum]) -> PyExtrema {
+    let column = |f: fn(&extrema::Extremum) -> f64| -> Py<PyArray1<f64>> {
+        extrema
+            .iter()
+            .map(f)
+            .collect::<Vec<_>>()
+            .into_pyarray(py)
+            .into()
+    };
+    let index = |f: fn(&extrema::Extremum) -> usize| -> Py<PyArray1<usize>> {
+        extrema
+            .iter()
+            .map(f)
+            .collect::<Vec<_>>()
+            .into_pyarray(py)
+            .into()
+    };
+    PyExtrema {
+        col: index(|e| e.col),
+        row: index(|e| e.row),
+        x: column(|e| e.x),
+        y: column(|e| e.y),
+        value: column(|e| e.value),
+        prominence: column(|e| e.prominence),
+    }
+}
+
+/// Polynomial trend in world coordinates relative to `center`, with the
+/// coefficients of the terms 1, x, y, x², xy, y², x³, x²y, xy², y³ up to the
+/// order.
+#[pyclass(name = "Trend", get_all)]
+#[derive(Debug)]
+pub struct PyTrend {
+    pub order: usize,
+    pub center: (f64, f64),
+    pub coefficients: Vec<f64>,
+    pub r_squared: f64,
+}
+
+#[pymethods]
+impl PyTrend {
+    fn __repr__(&self) -> String {
+        format!(
+            "<Trend(order={}, r_squared={})>",
+            self.order, self.r_squared
+        )
+    }
+
+    /// Trend values at world coordinates.
+    fn evaluate<'py>(
+        &self,
+        py: Python<'py>,
+        xs: PyReadonlyArray1<f64>,
+        ys: PyReadonlyArray1<f64>,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let (xs, ys) = (xs.as_slice()?, ys.as_slice()?);
+        if xs.len() != ys.len() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Coordinate arrays differ in length: x={}, y={}",
+                xs.len(),
+                ys.len()
+            )));
+        }
+        let trend = self.to_trend();
+        Ok(xs
+            .iter()
+            .zip(ys)
+            .map(|(&x, &y)| trend.evaluate(x, y))
+            .collect::<Vec<_>>()
+            .into_pyarray(py))
+    }
+
+    /// Trend evaluated at every node of the lattice given by `header`.
+    fn to_surface(&self, py: Python, header: IrapHeader) -> PyResult<IrapSurface> {
+        irap_to_surface(py, &self.to_trend().to_surface(&header))
+    }
+}
+
+impl PyTrend {
+    fn to_trend(&self) -> trend::Trend {
+        trend::Trend {
+            order: self.order,
+            center: [self.center.0, self.center.1],
+            coefficients: self.coefficients.clone(),
+            r_squared: self.r_squared,
+        }
+    }
+}
+
+fn trend_to_py(trend: &trend::Trend) -> PyTrend {
+    PyTrend {
+        order: trend.order,
+        center: (trend.center[0], trend.center[1]),
+        coefficients: trend.coefficients.clone(),
+        r_squared: trend.r_squared,
+    }
+}
+
+/// Experimental semivariogram, as arrays with one entry per lag bin.
+#[pyclass(name = "Variogram", get_all)]
+#[derive(Debug)]
+pub struct PyVariogram {
+    pub lags: Py<PyArray1<f64>>,
+    pub gamma: Py<PyArray1<f64>>,
+    pub pairs: Py<PyArray1<usize>>,
+}
+
+#[pymethods]
+impl PyVariogram {
+    fn __repr__(&self, py: Python) -> String {
+        format!("<Variogram(nlags={})>", self.lags.bind(py).len())
+    }
+
+    /// Fit a "spherical", "exponential" or "gaussian" model by weighted
+    /// least squares.
+    #[pyo3(signature = (model = "spherical"))]
+    fn fit(&self, py: Python, model: &str) -> PyResult<PyVariogramModel> {
+        let kind = parse_model_kind(model)?;
+        let variogram = variogram::Variogram {
+            lags: self.lags.bind(py).readonly().as_slice()?.to_vec(),
+            gamma: self.gamma.bind(py).readonly().as_slice()?.to_vec(),
+            pairs: self.pairs.bind(py).readonly().as_slice()?.to_vec(),
+        };
+        let fitted = variogram
+            .fit(kind)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(PyVariogramModel {
+            model: model.to_string(),
+            nugget: fitted.nugget,
+            sill: fitted.sill,
+            range: fitted.range,
+        })
+    }
+}
+
+fn variogram_to_py(py: Python, variogram: variogram::Variogram) -> PyVariogram {
+    PyVariogram {
+        lags: variogram.lags.into_pyarray(py).into(),
+        gamma: variogram.gamma.into_pyarray(py).into(),
+        pairs: variogram.pairs.into_pyarray(py).into(),
+    }
+}
+
+fn variogram_options(
+    lag: f64,
+    nlags: usize,
+    azimuth: Option<f64>,
+    tolerance: f64,
+    bandwidth: Option<f64>,
+    max_points: Option<usize>,
+    seed: u64,
+) -> variogram::VariogramOptions {
+    variogram::VariogramOptions {
+        lag,
+        nlags,
+        direction: azimuth.map(|azimuth| variogram::Direction {
+            azimuth,
+            tolerance,
+            bandwidth,
+        }),
+        max_points,
+        seed,
+    }
+}
+
+/// Variogram model "spherical", "exponential" or "gaussian", with a nugget,
+/// partial sill and practical range.
+#[pyclass(name = "VariogramModel", get_all, set_all, from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyVariogramModel {
+    pub model: String,
+    pub nugget: f64,
+    pub sill: f64,
+    pub range: f64,
+}
+
+#[pymethods]
+impl PyVariogramModel {
+    #[new]
+    #[pyo3(signature = (model = "spherical".to_string(), nugget = 0.0, sill = 1.0, range = 1.0))]
+    fn py_new(model: String, nugget: f64, sill: f64, range: f64) -> PyResult<Self> {
+        parse_model_kind(&model)?;
+        Ok(PyVariogramModel {
+            model,
+            nugget,
+            sill,
+            range,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<VariogramModel(model={}, nugget={}, sill={}, range={})>",
+            self.model, self.nugget, self.sill, self.range
+        )
+    }
+
+    /// Semivariance at the separations `h`.
+    fn gamma<'py>(
+        &self,
+        py: Python<'py>,
+        h: PyReadonlyArray1<f64>,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let model = self.to_model()?;
+        Ok(h.as_slice()?
+            .iter()
+            .map(|&h| model.gamma(h))
+            .collect::<Vec<_>>()
+            .into_pyarray(py))
+    }
+}
+
+impl PyVariogramModel {
+    fn to_model(&self) -> PyResult<variogram::VariogramModel> {
+        Ok(variogram::VariogramModel {
+            kind: parse_model_kind(&self.model)?,
+            nugget: self.nugget,
+            sill: self.sill,
+            range: self.range,
+        })
+    }
+}
+
+fn parse_model_kind(model: &str) -> PyResult<variogram::ModelKind> {
+    match model {
+        "spherical" => Ok(variogram::ModelKind::Spherical),
+        "exponential" => Ok(variogram::ModelKind::Exponential),
+        "gaussian" => Ok(variogram::ModelKind::Gaussian),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown variogram model: {}, expected 'spherical', 'exponential' or 'gaussian'",
+            model
+        ))),
+    }
+}
+
+/// Conditioning points of a simulation, all three coordinates or none.
+#[allow(clippy::type_complexity)]
+fn conditioning_data<'a>(
+    xs: &'a Option<PyReadonlyArray1<f64>>,
+    ys: &'a Option<PyReadonlyArray1<f64>>,
+    zs: &'a Option<PyReadonlyArray1<f64>>,
+) -> PyResult<Option<(&'a [f64], &'a [f64], &'a [f64])>> {
+    match (xs, ys, zs) {
+        (Some(xs), Some(ys), Some(zs)) => {
+            Ok(Some((xs.as_slice()?, ys.as_slice()?, zs.as_slice()?)))
+        }
+        (None, None, None) => Ok(None),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Conditioning needs all of xs, ys and zs",
+        )),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn simulation_options<'a>(
+    model: &PyVariogramModel,
+    mean: f64,
+    azimuth: f64,
+    minor_range: Option<f64>,
+    seed: u64,
+    data: Option<(&'a [f64], &'a [f64], &'a [f64])>,
+    radius: Option<f64>,
+    max_points: usize,
+) -> PyResult<simulation::SimulationOptions<'a>> {
+    Ok(simulation::SimulationOptions {
+        model: model.to_model()?,
+        anisotropy: minor_range.map(|minor_range| gridding::Anisotropy {
+            azimuth,
+            minor_range,
+        }),
+        mean,
+        seed,
+        conditioning: data.map(|(xs, ys, zs)| simulation::Conditioning {
+            xs,
+            ys,
+            zs,
+            radius: radius.unwrap_or(f64::INFINITY),
+            max_points,
+        }),
+    })
+}
+
+/// Statistics per zone, as arrays with one entry per zone.
+#[pyclass(name = "ZonalStats", get_all)]
+#[derive(Debug)]
+pub struct PyZonalStats {
+    pub zone: Py<PyArray1<i64>>,
+    pub count: Py<PyArray1<usize>>,
+    pub area: Py<PyArray1<f64>>,
+    pub min: Py<PyArray1<f64>>,
+    pub max: Py<PyArray1<f64>>,
+    pub mean: Py<PyArray1<f64>>,
+    pub std: Py<PyArray1<f64>>,
+    pub sum: Py<PyArray1<f64>>,
+}
+
+#[pymethods]
+impl PyZonalStats {
+    fn __repr__(&self, py: Python) -> String {
+        format!("<ZonalStats(zones={})>", self.zone.bind(py).len())
+    }
+}
+
+fn zonal_stats_to_py(py: Python, stats: &[zonal::ZoneStats]) -> PyZonalStats {
+    let column = |f: fn(&zonal::ZoneStats) -> f64| -> Py<PyArray1<f64>> {
+        stats
+            .iter()
+            .map(f)
+            .collect::<Vec<_>>()
+            .into_pyarray(py)
+            .into()
+    };
+    PyZonalStats {
+        zone: stats
+            .iter()
+            .map(|s| s.zone)
+            .collect::<Vec<_>>()
+            .into_pyarray(py)
+            .into(),
+        count: stats
+            .iter()
+            .map(|s| s.count)
+            .collect::<Vec<_>>()
+            .into_pyarray(py)
+            .into(),
+        area: column(|s| s.area),
+        min: column(|s| s.min),
+        max: column(|s| s.max),
+        mean: column(|s| s.mean),
+        std: column(|s| s.std),
+        sum: column(|s| s.sum),
+    }
+}
+
+/// Volume and area of the rock between two surfaces. `hypsometry` is an
+/// (n, 3) array of depth, area above the depth and volume above the depth.
+#[pyclass(name = "Volumetrics", get_all)]
+#[derive(Debug)]
+pub struct PyVolumetrics {
+    pub volume: f64,
+    pub area: f64,
+    pub hypsometry: Py<PyArray2<f64>>,
+}
+
+#[pymethods]
+impl PyVolumetrics {
+    fn __repr__(&self) -> String {
+        format!("<Volumetrics(volume={}, area={})>", self.volume, self.area)
+    }
+}
+
+fn volumetrics_to_py(py: Python, result: &volumetrics::Volumetrics) -> PyVolumetrics {
+    let rows: Vec<[f64; 3]> = result
+        .hypsometry
+        .iter()
+        .map(|r| [r.depth, r.area, r.volume])
+        .collect();
+    PyVolumetrics {
+        volume: result.volume,
+        area: result.area,
+        hypsometry: xyz_to_pyarray(py, &rows),
+    }
+}
+
+fn pyarray_to_xy(py: Python, values: &Py<PyArray2<f64>>) -> PyResult<Vec<[f64; 2]>> {
+    let arr = values.bind(py).readonly();
+    let view = arr.as_array();
+    if view.ncols() < 2 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Expected array of shape (n, 2) or (n, 3), got {:?}",
+            view.shape()
+        )));
+    }
+    Ok(view.rows().into_iter().map(|r| [r[0], r[1]]).collect())
+}
+
+fn xyz_to_pyarray(py: Python, values: &[[f64; 3]]) -> Py<PyArray2<f64>> {
+    let flat = values.iter().flatten().copied().collect();
+    let np_arr = Array2::from_shape_vec((values.len(), 3), flat).expect("Error reshaping array");
+    np_arr.into_pyarray(py).into()
+}
+
+fn pyarray_to_xyz(py: Python, values: &Py<PyArray2<f64>>) -> PyResult<Vec<[f64; 3]>> {
+    let arr = values.bind(py).readonly();
+    let view = arr.as_array();
+    if view.ncols() != 3 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Expected array of shape (n, 3), got {:?}",
+            view.shape()
+        )));
+    }
+    Ok(view
+        .rows()
+        .into_iter()
+        .map(|r| [r[0], r[1], r[2]])
+        .collect())
+}
+
+fn pyarray_to_trajectory(py: Python, values: &Py<PyArray2<f64>>) -> PyResult<Vec<[f64; 4]>> {
+    let arr = values.bind(py).readonly();
+    let view = arr.as_array();
+    if view.ncols() != 4 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Expected array of shape (n, 4), got {:?}",
+            view.shape()
+        )));
+    }
+    Ok(view
+        .rows()
+        .into_iter()
+        .map(|r| [r[0], r[1], r[2], r[3]])
+        .collect())
+}
+
+fn crossings_to_pyarray(py: Python, crossings: &[wells::WellCrossing]) -> Py<PyArray2<f64>> {
+    let flat = crossings
+        .iter()
+        .flat_map(|c| [c.md, c.x, c.y, c.z, c.value])
+        .collect();
+    let np_arr = Array2::from_shape_vec((crossings.len(), 5), flat).expect("Error reshaping array");
+    np_arr.into_pyarray(py).into()
+}
+
+pub fn points_to_py(py: Python, points: &Points) -> PyPoints {
+    PyPoints {
+        values: xyz_to_pyarray(py, &points.values),
+    }
+}
+
+fn py_to_points(py: Python, values: &Py<PyArray2<f64>>) -> PyResult<Points> {
+    Ok(Points {
+        values: pyarray_to_xyz(py, values)?,
+    })
+}
+
+pub fn polygons_to_py(py: Python, polygons: &Polygons) -> PyPolygons {
+    PyPolygons {
+        values: polygons
+            .polygons
+            .iter()
+            .map(|p| xyz_to_pyarray(py, p))
+            .collect(),
+    }
+}
 
-    let values = arr.readonly().as_slice().unwrap().to_vec();
-    Irap { header, values }
+fn py_to_polygons(py: Python, values: &[Py<PyArray2<f64>>]) -> PyResult<Polygons> {
+    let polygons = values
+        .iter()
+        .map(|v| pyarray_to_xyz(py, v))
+        .collect::<PyResult<_>>()?;
+    Ok(Polygons { polygons })
 }
 
 #[pymodule]
 fn surfio_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<IrapSurface>()?;
     m.add_class::<IrapHeader>()?;
+    m.add_class::<PyPoints>()?;
+    m.add_class::<PyPolygons>()?;
+    m.add_class::<PyIsoband>()?;
+    m.add_class::<PyVolumetrics>()?;
+    m.add_class::<PyStack>()?;
+    m.add_class::<PyZonalStats>()?;
+    m.add_class::<PyClosure>()?;
+    m.add_class::<PyExtrema>()?;
+    m.add_class::<PyTrend>()?;
+    m.add_class::<PyVariogram>()?;
+    m.add_class::<PyVariogramModel>()?;
     Ok(())
 }
@@ -0,0 +1,163 @@
+use crate::irap::{Irap, IrapHeader};
+use crate::spatial::KdTree;
+use crate::utils::fill_header;
+use rayon::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// How overlapping surfaces are combined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeMode {
+    /// Value of the first surface, in the given order, defined at the node.
+    First,
+    /// Mean of the surfaces defined at the node.
+    Mean,
+    /// Weighted mean, with the weight of each surface rising linearly from
+    /// zero at the edge of its defined region to one `width` world units inside.
+    Feather { width: f64 },
+}
+
+/// Tolerance in nodes when rounding the union extent to whole nodes.
+const EXTENT_TOLERANCE: f64 = 1e-9;
+
+/// Merge surfaces onto `target`, or when `None` onto the smallest lattice
+/// aligned with the first surface that covers all of them.
+///
+/// Each surface is resampled bilinearly onto the target lattice. Nodes where
+/// no surface is defined are undefined.
+pub fn merge(surfaces: &[Irap], target: Option<&IrapHeader>, mode: MergeMode) -> Result<Irap> {
+    if surfaces.is_empty() {
+        return Err("No surfaces to merge".into());
+    }
+    if let MergeMode::Feather { width } = mode
+        && !(width > 0.0 && width.is_finite())
+    {
+        return Err(format!("Invalid feather width: {}", width).into());
+    }
+    let header = match target {
+        Some(header) => header.clone(),
+        None => union_header(surfaces),
+    };
+
+    let resampled: Vec<Irap> = surfaces.iter().map(|s| s.resample(&header)).collect();
+    let weights: Vec<Vec<f64>> = match mode {
+        MergeMode::Feather { width } => resampled.iter().map(|s| feather(s, width)).collect(),
+        _ => Vec::new(),
+    };
+
+    let values = (0..header.len())
+        .into_par_iter()
+        .map(|idx| {
+            let defined = resampled
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| !s.values[idx].is_nan());
+            match mode {
+                MergeMode::First => defined
+                    .map(|(_, s)| s.values[idx])
+                    .next()
+                    .unwrap_or(f32::NAN),
+                MergeMode::Mean => {
+                    let (sum, count) = defined.fold((0.0, 0), |(sum, count), (_, s)| {
+                        (sum + s.values[idx] as f64, count + 1)
+                    });
+                    if count == 0 {
+                        f32::NAN
+                    } else {
+                        (sum / count as f64) as f32
+                    }
+                }
+                MergeMode::Feather { .. } => {
+                    let (sum, total) = defined.fold((0.0, 0.0), |(sum, total), (k, s)| {
+                        let w = weights[k][idx];
+                        (sum + w * s.values[idx] as f64, total + w)
+                    });
+                    if total > 0.0 {
+                        (sum / total) as f32
+                    } else {
+                        f32::NAN
+                    }
+                }
+            }
+        })
+        .collect();
+    Ok(Irap { header, values })
+}
+
+/// Lattice with the rotation and increments of the first surface, aligned
+/// with its nodes, covering the corners of all surfaces.
+fn union_header(surfaces: &[Irap]) -> IrapHeader {
+    let first = &surfaces[0].header;
+    let (mut cmin, mut cmax) = (f64::INFINITY, f64::NEG_INFINITY);
+    let (mut rmin, mut rmax) = (f64::INFINITY, f64::NEG_INFINITY);
+    for surface in surfaces {
+        let h = &surface.header;
+        let last_col = h.ncol.saturating_sub(1) as f64;
+        let last_row = h.nrow.saturating_sub(1) as f64;
+        for (col, row) in [
+            (0.0, 0.0),
+            (last_col, 0.0),
+            (0.0, last_row),
+            (last_col, last_row),
+        ] {
+            let (x, y) = h.node_xy(col, row);
+            let (c, r) = first.world_to_grid(x, y);
+            cmin = cmin.min(c);
+            cmax = cmax.max(c);
+            rmin = rmin.min(r);
+            rmax = rmax.max(r);
+        }
+    }
+    let (cmin, rmin) = (
+        (cmin + EXTENT_TOLERANCE).floor(),
+        (rmin + EXTENT_TOLERANCE).floor(),
+    );
+    let (cmax, rmax) = (
+        (cmax - EXTENT_TOLERANCE).ceil(),
+        (rmax - EXTENT_TOLERANCE).ceil(),
+    );
+    let (xori, yori) = first.node_xy(cmin, rmin);
+    let mut header = IrapHeader {
+        ncol: (cmax - cmin) as u32 + 1,
+        nrow: (rmax - rmin) as u32 + 1,
+        xori,
+        yori,
+        xrot: xori,
+        yrot: yori,
+        ..first.clone()
+    };
+    fill_header(&mut header);
+    header
+}
+
+/// Feathering weight of each node: its distance to the closest undefined
+/// node, divided by `width` and capped at one.
+fn feather(surface: &Irap, width: f64) -> Vec<f64> {
+    let header = &surface.header;
+    let nrow = header.nrow as usize;
+    let node_xy = |idx: usize| header.node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+    let undefined: Vec<[f64; 2]> = (0..surface.values.len())
+        .filter(|&idx| surface.values[idx].is_nan())
+        .map(|idx| {
+            let (x, y) = node_xy(idx);
+            [x, y]
+        })
+        .collect();
+    if undefined.is_empty() {
+        return vec![1.0; surface.values.len()];
+    }
+    let tree = KdTree::new(&undefined);
+    (0..surface.values.len())
+        .into_par_iter()
+        .map(|idx| {
+            if surface.values[idx].is_nan() {
+                return 0.0;
+            }
+            let (x, y) = node_xy(idx);
+            match tree.k_nearest(x, y, 1, width).first() {
+                Some(&(_, d2)) => d2.sqrt() / width,
+                None => 1.0,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use crate::irap::Irap;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Values sampled along a polyline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    /// Distance along the polyline from its first vertex.
+    pub distance: Vec<f64>,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Sampled values, NaN where the surface is undefined.
+    pub values: Vec<f64>,
+}
+
+/// Several surfaces sampled at the same positions along a polyline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profiles {
+    pub distance: Vec<f64>,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// One column of sampled values per surface.
+    pub values: Vec<Vec<f64>>,
+}
+
+impl Irap {
+    /// Sample along `polyline` every `spacing` world units, see `profiles`.
+    pub fn profile(&self, polyline: &[[f64; 2]], spacing: f64) -> Result<Profile> {
+        let Profiles {
+            distance,
+            x,
+            y,
+            mut values,
+        } = profiles(std::slice::from_ref(self), polyline, spacing)?;
+        Ok(Profile {
+            distance,
+            x,
+            y,
+            values: values.pop().unwrap_or_default(),
+        })
+    }
+}
+
+/// Sample each surface along `polyline` every `spacing` world units.
+///
+/// Positions are at multiples of `spacing` along the line, and at each
+/// vertex so corners of the section are kept. Values are interpolated
+/// bilinearly in the rotated lattice of each surface and are NaN outside the
+/// lattice or next to undefined nodes, which breaks the profile there.
+pub fn profiles(surfaces: &[Irap], polyline: &[[f64; 2]], spacing: f64) -> Result<Profiles> {
+    if !(spacing > 0.0 && spacing.is_finite()) {
+        return Err(format!("Invalid spacing: {}", spacing).into());
+    }
+    if polyline.is_empty() {
+        return Err("Polyline has no vertices".into());
+    }
+
+    let mut distance = vec![0.0];
+    let mut x = vec![polyline[0][0]];
+    let mut y = vec![polyline[0][1]];
+    let mut start = 0.0;
+    for segment in polyline.windows(2) {
+        let [[x0, y0], [x1, y1]] = [segment[0], segment[1]];
+        let length = (x1 - x0).hypot(y1 - y0);
+        if length == 0.0 {
+            continue;
+        }
+        let end = start + length;
+        let mut k = (start / spacing).floor() as u64 + 1;
+        if k as f64 * spacing <= start + 1e-9 * spacing {
+            k += 1;
+        }
+        loop {
+            let d = k as f64 * spacing;
+            // Positions closer to the vertex than rounding are the vertex
+            if d >= end - 1e-9 * spacing {
+                break;
+            }
+            let t = (d - start) / length;
+            distance.push(d);
+            x.push(x0 + t * (x1 - x0));
+            y.push(y0 + t * (y1 - y0));
+            k += 1;
+        }
+        distance.push(end);
+        x.push(x1);
+        y.push(y1);
+        start = end;
+    }
+
+    let values = surfaces
+        .iter()
+        .map(|surface| {
+            x.iter()
+                .zip(&y)
+                .map(|(&x, &y)| surface.sample(x, y))
+                .collect()
+        })
+        .collect();
+    Ok(Profiles {
+        distance,
+        x,
+        y,
+        values,
+    })
+}
@@ -0,0 +1,85 @@
+use super::{Aggregation, Result};
+use crate::irap::{Irap, IrapHeader};
+use crate::utils::fill_header;
+use rayon::prelude::*;
+
+impl Irap {
+    /// Keep every `fx`-th column and `fy`-th row, with values aggregated over
+    /// blocks of `fx` by `fy` nodes centred on the kept nodes.
+    ///
+    /// The origin is unchanged and trailing nodes that do not make up a full
+    /// step are dropped. A coarse node is undefined when less than
+    /// `min_defined_fraction` of its block, clipped to the lattice, is defined.
+    /// Both factors must be positive.
+    pub fn coarsen(
+        &self,
+        fx: usize,
+        fy: usize,
+        agg: Aggregation,
+        min_defined_fraction: f64,
+    ) -> Result<Irap> {
+        if fx == 0 || fy == 0 {
+            return Err(format!("Factors must be positive, got fx={}, fy={}", fx, fy).into());
+        }
+        let source = &self.header;
+        let (ncol, nrow) = (source.ncol as usize, source.nrow as usize);
+
+        let mut header = IrapHeader {
+            ncol: (ncol.saturating_sub(1) / fx + 1) as u32,
+            nrow: (nrow.saturating_sub(1) / fy + 1) as u32,
+            xinc: source.xinc * fx as f64,
+            yinc: source.yinc * fy as f64,
+            ..source.clone()
+        };
+        fill_header(&mut header);
+
+        let block = |centre: usize, factor: usize, n: usize| {
+            let lo = centre.saturating_sub((factor - 1) / 2);
+            let hi = (centre + factor / 2).min(n - 1);
+            lo..=hi
+        };
+        let coarse_nrow = header.nrow as usize;
+        let values = (0..header.len())
+            .into_par_iter()
+            .map_init(Vec::new, |window, idx| {
+                let (i, j) = (idx / coarse_nrow, idx % coarse_nrow);
+                window.clear();
+                let mut total = 0;
+                for col in block(i * fx, fx, ncol) {
+                    for row in block(j * fy, fy, nrow) {
+                        total += 1;
+                        let v = self.values[source.index(col, row)];
+                        if !v.is_nan() {
+                            window.push(v);
+                        }
+                    }
+                }
+                if window.is_empty() || (window.len() as f64) < min_defined_fraction * total as f64
+                {
+                    return f32::NAN;
+                }
+                aggregate(window, agg)
+            })
+            .collect();
+        Ok(Irap { header, values })
+    }
+}
+
+fn aggregate(values: &mut [f32], agg: Aggregation) -> f32 {
+    match agg {
+        Aggregation::Mean => {
+            (values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64) as f32
+        }
+        Aggregation::Min => values.iter().copied().fold(f32::INFINITY, f32::min),
+        Aggregation::Max => values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        Aggregation::Median => {
+            values.sort_unstable_by(f32::total_cmp);
+            let mid = values.len() / 2;
+            if values.len().is_multiple_of(2) {
+                ((values[mid - 1] as f64 + values[mid] as f64) / 2.0) as f32
+            } else {
+                values[mid]
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+mod coarsen;
+mod refine;
+mod rotate;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// How the nodes of a block are combined when coarsening.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Mean,
+    Min,
+    Max,
+    Median,
+}
+
+/// How values between nodes are computed when refining.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    Bilinear,
+    /// Catmull-Rom cubic convolution, falling back to bilinear where any of
+    /// the 4x4 surrounding nodes is undefined.
+    Bicubic,
+}
@@ -0,0 +1,112 @@
+use super::{Interpolation, Result};
+use crate::irap::{Irap, IrapHeader};
+use crate::utils::fill_header;
+use rayon::prelude::*;
+
+impl Irap {
+    /// Insert `fx - 1` columns and `fy - 1` rows between existing nodes.
+    ///
+    /// The origin is unchanged and the original nodes keep their values, with
+    /// the new nodes interpolated between them. Both factors must be positive,
+    /// and the refined lattice must fit the header.
+    pub fn refine(&self, fx: usize, fy: usize, interp: Interpolation) -> Result<Irap> {
+        if fx == 0 || fy == 0 {
+            return Err(format!("Factors must be positive, got fx={}, fy={}", fx, fy).into());
+        }
+        let source = &self.header;
+        let count = |n: u32, factor: usize| -> Result<u32> {
+            (n.saturating_sub(1) as usize)
+                .checked_mul(factor)
+                .and_then(|cells| cells.checked_add(1))
+                .and_then(|nodes| u32::try_from(nodes).ok())
+                .ok_or_else(|| format!("Too many nodes for factor {}", factor).into())
+        };
+
+        let mut header = IrapHeader {
+            ncol: count(source.ncol, fx)?,
+            nrow: count(source.nrow, fy)?,
+            xinc: source.xinc / fx as f64,
+            yinc: source.yinc / fy as f64,
+            ..source.clone()
+        };
+        fill_header(&mut header);
+
+        let nrow = header.nrow as usize;
+        let values = (0..header.len())
+            .into_par_iter()
+            .map(|idx| {
+                let col = (idx / nrow) as f64 / fx as f64;
+                let row = (idx % nrow) as f64 / fy as f64;
+                match interp {
+                    Interpolation::Bilinear => self.sample_grid(col, row),
+                    Interpolation::Bicubic => self
+                        .sample_bicubic(col, row)
+                        .unwrap_or_else(|| self.sample_grid(col, row)),
+                }
+            })
+            .map(|v| v as f32)
+            .collect();
+        Ok(Irap { header, values })
+    }
+
+    /// Catmull-Rom interpolation at a fractional node position. Outside the
+    /// lattice, nodes are extrapolated linearly from the edge so planes are
+    /// reproduced. `None` if any of the nodes with nonzero weight is undefined.
+    fn sample_bicubic(&self, col: f64, row: f64) -> Option<f64> {
+        let ncol = self.header.ncol as usize;
+        let nrow = self.header.nrow as usize;
+        let (i0, tx) = split(col, ncol);
+        let (j0, ty) = split(row, nrow);
+        let (wx, wy) = (weights(tx), weights(ty));
+
+        let node = |i: usize, j: usize| {
+            let v = self.values[self.header.index(i, j)];
+            (!v.is_nan()).then_some(v as f64)
+        };
+        let column = |i: usize, j: isize| -> Option<f64> { extend(j, nrow, |j| node(i, j)) };
+        let value_at = |i: isize, j: isize| -> Option<f64> { extend(i, ncol, |i| column(i, j)) };
+
+        let mut value = 0.0;
+        for (di, wi) in wx.iter().enumerate() {
+            for (dj, wj) in wy.iter().enumerate() {
+                let w = wi * wj;
+                if w == 0.0 {
+                    continue;
+                }
+                value += w * value_at(i0 + di as isize - 1, j0 + dj as isize - 1)?;
+            }
+        }
+        Some(value)
+    }
+}
+
+/// Value at index `k` along an axis of `n` nodes, linearly extrapolated one
+/// node past either end.
+fn extend(k: isize, n: usize, at: impl Fn(usize) -> Option<f64>) -> Option<f64> {
+    let last = n as isize - 1;
+    if (0..=last).contains(&k) {
+        return at(k as usize);
+    }
+    if n == 1 {
+        return at(0);
+    }
+    let (edge, inner) = if k < 0 { (0, 1) } else { (last, last - 1) };
+    let e = at(edge as usize)?;
+    Some(2.0 * e - at(inner as usize)?)
+}
+
+/// Cell index and fraction of a position, keeping the last node in the last cell.
+fn split(t: f64, n: usize) -> (isize, f64) {
+    let i = (t.floor() as isize).clamp(0, n.saturating_sub(2) as isize);
+    (i, t - i as f64)
+}
+
+fn weights(t: f64) -> [f64; 4] {
+    let (t2, t3) = (t * t, t * t * t);
+    [
+        (-t3 + 2.0 * t2 - t) / 2.0,
+        (3.0 * t3 - 5.0 * t2 + 2.0) / 2.0,
+        (-3.0 * t3 + 4.0 * t2 + t) / 2.0,
+        (t3 - t2) / 2.0,
+    ]
+}
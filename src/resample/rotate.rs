@@ -0,0 +1,99 @@
+use super::Result;
+use crate::irap::{Irap, IrapHeader};
+use crate::utils::fill_header;
+use rayon::prelude::*;
+
+/// Tolerance in nodes for target nodes landing on the edge of the source
+/// lattice after the round trip through world coordinates.
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+impl Irap {
+    /// Resample onto the smallest lattice with rotation `rot` (degrees
+    /// counter-clockwise) and increments `xinc`, `yinc` covering this one.
+    ///
+    /// Like the rest of the crate, both lattices are rotated around their
+    /// origin (xori, yori). Values are interpolated bilinearly, and target
+    /// nodes outside the source lattice are undefined. The new header has
+    /// xrot/yrot at its origin. The increments must be positive.
+    pub fn rotate_to(&self, rot: f64, xinc: f64, yinc: f64) -> Result<Irap> {
+        if xinc.is_nan() || xinc <= 0.0 || yinc.is_nan() || yinc <= 0.0 {
+            return Err(format!(
+                "Increments must be positive, got xinc={}, yinc={}",
+                xinc, yinc
+            )
+            .into());
+        }
+        let (sin, cos) = rot.to_radians().sin_cos();
+        let ncol = self.header.ncol.max(1) as f64;
+        let nrow = self.header.nrow.max(1) as f64;
+
+        // Extent of the source corner nodes in the frame of the target rotation
+        let (mut umin, mut umax) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut vmin, mut vmax) = (f64::INFINITY, f64::NEG_INFINITY);
+        for (col, row) in [
+            (0.0, 0.0),
+            (ncol - 1.0, 0.0),
+            (0.0, nrow - 1.0),
+            (ncol - 1.0, nrow - 1.0),
+        ] {
+            let (x, y) = self.header.node_xy(col, row);
+            let (u, v) = (x * cos + y * sin, -x * sin + y * cos);
+            umin = umin.min(u);
+            umax = umax.max(u);
+            vmin = vmin.min(v);
+            vmax = vmax.max(v);
+        }
+        let count = |extent: f64, inc: f64| -> Result<u32> {
+            let cells = (extent / inc - EDGE_TOLERANCE).ceil().max(0.0);
+            if cells >= u32::MAX as f64 {
+                return Err(format!("Too many nodes for increment {}", inc).into());
+            }
+            Ok(cells as u32 + 1)
+        };
+
+        let mut header = IrapHeader {
+            ncol: count(umax - umin, xinc)?,
+            nrow: count(vmax - vmin, yinc)?,
+            xori: umin * cos - vmin * sin,
+            yori: umin * sin + vmin * cos,
+            xinc,
+            yinc,
+            rot,
+            ..Default::default()
+        };
+        header.xrot = header.xori;
+        header.yrot = header.yori;
+        fill_header(&mut header);
+
+        Ok(self.resample(&header))
+    }
+
+    /// Values at the nodes of another lattice, interpolated bilinearly and
+    /// undefined outside this one.
+    pub fn resample(&self, header: &IrapHeader) -> Irap {
+        let source = &self.header;
+        let nrow = header.nrow as usize;
+        let snap = |t: f64, n: u32| {
+            let last = n.saturating_sub(1) as f64;
+            if t < 0.0 && t > -EDGE_TOLERANCE {
+                0.0
+            } else if t > last && t < last + EDGE_TOLERANCE {
+                last
+            } else {
+                t
+            }
+        };
+        let values = (0..header.len())
+            .into_par_iter()
+            .map(|idx| {
+                let (x, y) = header.node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                let (col, row) = source.world_to_grid(x, y);
+                self.sample_grid(snap(col, source.ncol), snap(row, source.nrow)) as f32
+            })
+            .collect();
+        Irap {
+            header: header.clone(),
+            values,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+mod spectral;
+
+use crate::gridding::{Anisotropy, KrigingOptions, KrigingType, Neighbourhood};
+use crate::irap::{self, Irap, IrapHeader};
+use crate::variogram::VariogramModel;
+use rayon::prelude::*;
+use spectral::Spectrum;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Realizations written to disk at a time by `simulate_to_files`.
+const FILE_BATCH: usize = 16;
+
+/// Data a simulation is conditioned on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Conditioning<'a> {
+    pub xs: &'a [f64],
+    pub ys: &'a [f64],
+    pub zs: &'a [f64],
+    /// Search radius of the kriging along the major axis.
+    pub radius: f64,
+    /// Largest number of data points used for each node.
+    pub max_points: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimulationOptions<'a> {
+    pub model: VariogramModel,
+    /// Isotropic when `None`.
+    pub anisotropy: Option<Anisotropy>,
+    pub mean: f64,
+    pub seed: u64,
+    /// Unconditional when `None`.
+    pub conditioning: Option<Conditioning<'a>>,
+}
+
+/// Gaussian random field realizations on the lattice given by `header`.
+///
+/// Unconditional fields are generated by FFT moving average on a periodic
+/// lattice padded beyond the correlation range, so that the wrap-around does
+/// not reach back into the lattice. Conditional fields honour the data by
+/// simple kriging around the mean: each unconditional field is corrected by
+/// the kriged difference between the data and the field sampled at the data.
+/// Data outside the lattice or undefined are ignored.
+///
+/// Realization k only depends on the seed and k, so a realization is the
+/// same whatever the number of realizations generated with it.
+pub fn simulate(
+    header: &IrapHeader,
+    options: &SimulationOptions,
+    count: usize,
+) -> Result<Vec<Irap>> {
+    Simulator::new(header, options)?.realizations(0, count)
+}
+
+/// Like `simulate`, writing each realization to an IRAP binary file named by
+/// replacing `{}` in `pattern` with the realization number, starting at 0.
+/// Realizations are generated a few at a time to bound memory use, and the
+/// written paths are returned.
+pub fn simulate_to_files(
+    header: &IrapHeader,
+    options: &SimulationOptions,
+    count: usize,
+    pattern: &str,
+) -> Result<Vec<String>> {
+    if !pattern.contains("{}") {
+        return Err(format!("File name pattern has no {{}} placeholder: {}", pattern).into());
+    }
+    let simulator = Simulator::new(header, options)?;
+    let mut paths = Vec::with_capacity(count);
+    for first in (0..count).step_by(FILE_BATCH) {
+        let batch = simulator.realizations(first, FILE_BATCH.min(count - first))?;
+        for (k, irap) in batch.iter().enumerate() {
+            let path = pattern.replace("{}", &(first + k).to_string());
+            irap::binary::to_file(path.clone(), irap)?;
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+struct Simulator<'a> {
+    header: &'a IrapHeader,
+    options: &'a SimulationOptions<'a>,
+    kriging: KrigingOptions,
+    spectrum: Spectrum,
+    /// Conditioning data inside the lattice as (x, y, z).
+    data: Vec<[f64; 3]>,
+}
+
+impl<'a> Simulator<'a> {
+    fn new(header: &'a IrapHeader, options: &'a SimulationOptions<'a>) -> Result<Self> {
+        if header.ncol == 0 || header.nrow == 0 {
+            return Err(format!(
+                "Invalid dimensions: ncol={}, nrow={}",
+                header.ncol, header.nrow
+            )
+            .into());
+        }
+        for inc in [header.xinc, header.yinc] {
+            if inc == 0.0 || !inc.is_finite() {
+                return Err(format!("Invalid lattice increment: {}", inc).into());
+            }
+        }
+        let (radius, max_points) = options
+            .conditioning
+            .map_or((f64::INFINITY, 1), |c| (c.radius, c.max_points));
+        let kriging = KrigingOptions {
+            kind: KrigingType::Simple { mean: options.mean },
+            model: options.model,
+            anisotropy: options.anisotropy,
+            radius,
+            max_points,
+            std: false,
+        };
+        // Checks the model and anisotropy also when unconditional
+        Neighbourhood::new(&[], &kriging)?;
+
+        let mut data = Vec::new();
+        if let Some(c) = options.conditioning {
+            if c.xs.len() != c.ys.len() || c.xs.len() != c.zs.len() {
+                return Err(format!(
+                    "Coordinate arrays differ in length: x={}, y={}, z={}",
+                    c.xs.len(),
+                    c.ys.len(),
+                    c.zs.len()
+                )
+                .into());
+            }
+            let inside = |x: f64, y: f64| {
+                let (col, row) = header.world_to_grid(x, y);
+                col >= 0.0
+                    && row >= 0.0
+                    && col <= (header.ncol - 1) as f64
+                    && row <= (header.nrow - 1) as f64
+            };
+            data = (0..c.xs.len())
+                .map(|i| [c.xs[i], c.ys[i], c.zs[i]])
+                .filter(|p| p.iter().all(|v| v.is_finite()) && inside(p[0], p[1]))
+                .collect();
+        }
+
+        Ok(Simulator {
+            header,
+            options,
+            spectrum: Spectrum::new(header, &kriging)?,
+            kriging,
+            data,
+        })
+    }
+
+    /// Realizations `first` to `first + count - 1`.
+    fn realizations(&self, first: usize, count: usize) -> Result<Vec<Irap>> {
+        let mean = self.options.mean as f32;
+        let mut fields: Vec<Irap> = (first..first + count)
+            .step_by(2)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .flat_map_iter(|k| {
+                let (a, b) = self.spectrum.pair(self.options.seed, k as u64);
+                [a, b].into_iter().take(first + count - k)
+            })
+            .map(|values| Irap {
+                header: self.header.clone(),
+                values: values.into_iter().map(|v| v + mean).collect(),
+            })
+            .collect();
+        if self.options.conditioning.is_some() {
+            self.condition(&mut fields)?;
+        }
+        Ok(fields)
+    }
+
+    fn condition(&self, fields: &mut [Irap]) -> Result<()> {
+        let xy: Vec<[f64; 2]> = self.data.iter().map(|p| [p[0], p[1]]).collect();
+        let neighbourhood = Neighbourhood::new(&xy, &self.kriging)?;
+        // Data minus each field at the data, per data point and field
+        let mismatch: Vec<Vec<f64>> = self
+            .data
+            .iter()
+            .map(|&[x, y, z]| fields.iter().map(|f| z - f.sample(x, y)).collect())
+            .collect();
+
+        let mismatch = &mismatch;
+        let nrow = self.header.nrow as usize;
+        let count = fields.len();
+        let corrections: Vec<f64> = (0..self.header.len())
+            .into_par_iter()
+            .flat_map_iter(|idx| {
+                let (x, y) = self
+                    .header
+                    .node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                let weights = neighbourhood.weights(x, y).map(|(w, _)| w);
+                (0..count).map(move |r| {
+                    weights
+                        .as_ref()
+                        .map_or(0.0, |w| w.iter().map(|&(i, w)| w * mismatch[i][r]).sum())
+                })
+            })
+            .collect();
+        for (r, field) in fields.iter_mut().enumerate() {
+            for (idx, v) in field.values.iter_mut().enumerate() {
+                *v += corrections[idx * count + r] as f32;
+            }
+        }
+        Ok(())
+    }
+}
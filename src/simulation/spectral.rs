@@ -0,0 +1,152 @@
+use super::Result;
+use crate::gridding::{KrigingOptions, Transform};
+use crate::irap::IrapHeader;
+use crate::utils::Rng;
+use crate::variogram::ModelKind;
+use rayon::prelude::*;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+/// Largest periodic lattice, in nodes, that is allocated.
+const MAX_NODES: usize = 1 << 26;
+
+/// Square roots of the eigenvalues of the covariance matrix on a periodic
+/// lattice, the discrete Fourier transform of its first row, with the
+/// transforms needed to apply them.
+pub struct Spectrum {
+    ncol: usize,
+    nrow: usize,
+    /// Size of the periodic lattice, stored like the values of a surface.
+    n1: usize,
+    n2: usize,
+    roots: Vec<f64>,
+    forward: [Arc<dyn Fft<f64>>; 2],
+    inverse: [Arc<dyn Fft<f64>>; 2],
+}
+
+impl Spectrum {
+    pub fn new(header: &IrapHeader, options: &KrigingOptions) -> Result<Self> {
+        let model = &options.model;
+        let ncol = header.ncol as usize;
+        let nrow = header.nrow as usize;
+        // Spherical covariances vanish at the range, the others are small at
+        // twice the practical range.
+        let reach = match model.kind {
+            ModelKind::Spherical => 1.0,
+            ModelKind::Exponential | ModelKind::Gaussian => 2.0,
+        } * options
+            .anisotropy
+            .map_or(model.range, |a| a.minor_range.max(model.range));
+        let padded = |n: usize, inc: f64| {
+            let size = n as f64 + (reach / inc.abs()).ceil();
+            (size <= MAX_NODES as f64).then(|| smooth_size(size as usize))
+        };
+        let (n1, n2) = match (padded(ncol, header.xinc), padded(nrow, header.yinc)) {
+            (Some(n1), Some(n2)) if n1.saturating_mul(n2) <= MAX_NODES => (n1, n2),
+            _ => {
+                return Err(format!(
+                    "Range {} is too long for the lattice increments xinc={}, yinc={}",
+                    reach, header.xinc, header.yinc
+                )
+                .into());
+            }
+        };
+
+        let transform = Transform::new(options);
+        let (sin, cos) = header.rot.to_radians().sin_cos();
+        let signed = |k: usize, n: usize| {
+            if k <= n / 2 {
+                k as f64
+            } else {
+                k as f64 - n as f64
+            }
+        };
+        let mut covariance: Vec<Complex<f64>> = (0..n1 * n2)
+            .into_par_iter()
+            .map(|idx| {
+                let u = signed(idx / n2, n1) * header.xinc;
+                let v = signed(idx % n2, n2) * header.yinc;
+                let [a, b] = transform.apply(u * cos - v * sin, u * sin + v * cos);
+                Complex::new(model.covariance(a.hypot(b)), 0.0)
+            })
+            .collect();
+
+        let mut planner = FftPlanner::new();
+        let forward = [planner.plan_fft_forward(n1), planner.plan_fft_forward(n2)];
+        let inverse = [planner.plan_fft_inverse(n1), planner.plan_fft_inverse(n2)];
+        let mut spectrum = Spectrum {
+            ncol,
+            nrow,
+            n1,
+            n2,
+            roots: Vec::new(),
+            forward,
+            inverse,
+        };
+        spectrum.transform(&mut covariance, false);
+        // Small negative eigenvalues from truncating the covariance are dropped
+        spectrum.roots = covariance.iter().map(|c| c.re.max(0.0).sqrt()).collect();
+        Ok(spectrum)
+    }
+
+    /// Zero-mean realizations `k` and `k + 1` of `seed` on the lattice,
+    /// computed together as the real and imaginary parts of one transform.
+    pub fn pair(&self, seed: u64, k: u64) -> (Vec<f32>, Vec<f32>) {
+        let (mut first, mut second) = (Rng::stream(seed, k), Rng::stream(seed, k + 1));
+        let mut field: Vec<Complex<f64>> = (0..self.n1 * self.n2)
+            .map(|_| Complex::new(first.normal(), second.normal()))
+            .collect();
+        self.transform(&mut field, false);
+        for (f, root) in field.iter_mut().zip(&self.roots) {
+            *f *= root;
+        }
+        self.transform(&mut field, true);
+
+        let scale = 1.0 / (self.n1 * self.n2) as f64;
+        let mut a = Vec::with_capacity(self.ncol * self.nrow);
+        let mut b = Vec::with_capacity(self.ncol * self.nrow);
+        for col in 0..self.ncol {
+            for f in &field[col * self.n2..col * self.n2 + self.nrow] {
+                a.push((f.re * scale) as f32);
+                b.push((f.im * scale) as f32);
+            }
+        }
+        (a, b)
+    }
+
+    /// Unnormalized two-dimensional transform in place.
+    fn transform(&self, data: &mut [Complex<f64>], inverse: bool) {
+        let [along_col, along_row] = if inverse {
+            &self.inverse
+        } else {
+            &self.forward
+        };
+        let (n1, n2) = (self.n1, self.n2);
+        data.par_chunks_mut(n2)
+            .for_each(|row| along_row.process(row));
+        let mut transposed: Vec<Complex<f64>> =
+            (0..n1 * n2).map(|k| data[(k % n1) * n2 + k / n1]).collect();
+        transposed
+            .par_chunks_mut(n1)
+            .for_each(|col| along_col.process(col));
+        for (k, t) in transposed.into_iter().enumerate() {
+            data[(k % n1) * n2 + k / n1] = t;
+        }
+    }
+}
+
+/// Smallest size at least `n` with no prime factors above 5, for fast transforms.
+fn smooth_size(n: usize) -> usize {
+    (n.max(1)..)
+        .find(|&m| {
+            let mut m = m;
+            for p in [2, 3, 5] {
+                while m % p == 0 {
+                    m /= p;
+                }
+            }
+            m == 1
+        })
+        .unwrap_or(n)
+}
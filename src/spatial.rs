@@ -0,0 +1,106 @@
+/// Static 2D kd-tree over scattered points, used for neighbour searches when
+/// gridding and interpolating.
+///
+/// The tree is stored implicitly: the points are reordered so that each
+/// sub-range has its splitting point at the middle.
+pub struct KdTree {
+    points: Vec<[f64; 2]>,
+    indices: Vec<usize>,
+}
+
+impl KdTree {
+    pub fn new(points: &[[f64; 2]]) -> Self {
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        build(points, &mut indices, 0);
+        let points = indices.iter().map(|&i| points[i]).collect();
+        KdTree { points, indices }
+    }
+
+    /// Closest point to (x, y) as (original index, squared distance).
+    pub fn nearest(&self, x: f64, y: f64) -> Option<(usize, f64)> {
+        self.k_nearest(x, y, 1, f64::INFINITY).into_iter().next()
+    }
+
+    /// Up to `k` closest points within `max_distance` of (x, y), sorted by
+    /// increasing distance, as (original index, squared distance).
+    pub fn k_nearest(&self, x: f64, y: f64, k: usize, max_distance: f64) -> Vec<(usize, f64)> {
+        let mut found = Vec::with_capacity(k.min(64));
+        if k == 0 {
+            return found;
+        }
+        let mut search = Search {
+            query: [x, y],
+            k,
+            max_dist2: max_distance * max_distance,
+            found: &mut found,
+        };
+        self.search(0, self.points.len(), 0, &mut search);
+        found
+            .into_iter()
+            .map(|(i, d2)| (self.indices[i], d2))
+            .collect()
+    }
+
+    fn search(&self, lo: usize, hi: usize, depth: usize, s: &mut Search) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let p = self.points[mid];
+        let dx = p[0] - s.query[0];
+        let dy = p[1] - s.query[1];
+        s.offer(mid, dx * dx + dy * dy);
+
+        let axis = depth % 2;
+        let diff = s.query[axis] - p[axis];
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search(near.0, near.1, depth + 1, s);
+        if diff * diff <= s.bound() {
+            self.search(far.0, far.1, depth + 1, s);
+        }
+    }
+}
+
+struct Search<'a> {
+    query: [f64; 2],
+    k: usize,
+    max_dist2: f64,
+    found: &'a mut Vec<(usize, f64)>,
+}
+
+impl Search<'_> {
+    fn bound(&self) -> f64 {
+        if self.found.len() == self.k {
+            self.found[self.found.len() - 1].1
+        } else {
+            self.max_dist2
+        }
+    }
+
+    fn offer(&mut self, i: usize, d2: f64) {
+        if d2 > self.bound() || (self.found.len() == self.k && d2 == self.bound()) {
+            return;
+        }
+        let pos = self.found.partition_point(|&(_, d)| d <= d2);
+        if self.found.len() == self.k {
+            self.found.pop();
+        }
+        self.found.insert(pos, (i, d2));
+    }
+}
+
+fn build(points: &[[f64; 2]], indices: &mut [usize], depth: usize) {
+    if indices.len() <= 1 {
+        return;
+    }
+    let axis = depth % 2;
+    let mid = indices.len() / 2;
+    indices.select_nth_unstable_by(mid, |&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+    let (left, right) = indices.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
@@ -0,0 +1,153 @@
+use crate::irap::Irap;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// How crossing surfaces in a stack are resolved. Surfaces are ordered from
+/// youngest to oldest, and depths increase downwards, so each surface should
+/// be at or below the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StackRule {
+    /// Younger surfaces cut older ones: an older surface above a younger one
+    /// is moved down onto it.
+    Erosion,
+    /// Older surfaces cut younger ones: a younger surface below an older one
+    /// is moved up onto it.
+    Onlap,
+    /// Each surface is at least `thickness` below the younger ones, moving
+    /// older surfaces down where needed.
+    MinimumThickness(f64),
+}
+
+/// Change made to one surface of the stack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Adjustment {
+    /// Adjusted minus original depth, zero where unchanged and NaN where
+    /// the surface is undefined.
+    pub change: Irap,
+    /// Number of adjusted nodes.
+    pub nodes: usize,
+    /// Largest absolute change.
+    pub max_change: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stack {
+    pub surfaces: Vec<Irap>,
+    /// Thickness between each pair of consecutive surfaces.
+    pub isochores: Vec<Irap>,
+    /// One per surface, in the same order.
+    pub adjustments: Vec<Adjustment>,
+}
+
+/// Apply `rule` to an ordered stack of surfaces on a common lattice.
+///
+/// Rules are applied node by node. Undefined nodes are left undefined, and a
+/// surface is compared against the closest defined surface on the side that
+/// takes precedence.
+pub fn apply_rule(surfaces: &[Irap], rule: StackRule) -> Result<Stack> {
+    check_lattices(surfaces)?;
+    if let StackRule::MinimumThickness(thickness) = rule
+        && !(thickness >= 0.0 && thickness.is_finite())
+    {
+        return Err(format!("Invalid minimum thickness: {}", thickness).into());
+    }
+
+    let mut adjusted = surfaces.to_vec();
+    let len = surfaces.first().map_or(0, |s| s.values.len());
+    let mut column = vec![0.0f32; surfaces.len()];
+    for idx in 0..len {
+        for (z, surface) in column.iter_mut().zip(&adjusted) {
+            *z = surface.values[idx];
+        }
+        match rule {
+            StackRule::Erosion => push_down(&mut column, 0.0),
+            StackRule::MinimumThickness(thickness) => push_down(&mut column, thickness as f32),
+            StackRule::Onlap => push_up(&mut column),
+        }
+        for (z, surface) in column.iter().zip(&mut adjusted) {
+            surface.values[idx] = *z;
+        }
+    }
+
+    let adjustments = surfaces
+        .iter()
+        .zip(&adjusted)
+        .map(|(original, new)| adjustment(original, new))
+        .collect();
+    Ok(Stack {
+        isochores: isochores(&adjusted)?,
+        surfaces: adjusted,
+        adjustments,
+    })
+}
+
+/// Thickness between each pair of consecutive surfaces, older minus younger.
+pub fn isochores(surfaces: &[Irap]) -> Result<Vec<Irap>> {
+    check_lattices(surfaces)?;
+    Ok(surfaces
+        .windows(2)
+        .map(|pair| Irap {
+            header: pair[0].header.clone(),
+            values: pair[0]
+                .values
+                .iter()
+                .zip(&pair[1].values)
+                .map(|(top, base)| base - top)
+                .collect(),
+        })
+        .collect())
+}
+
+fn check_lattices(surfaces: &[Irap]) -> Result<()> {
+    if let Some(first) = surfaces.first() {
+        for (k, surface) in surfaces.iter().enumerate().skip(1) {
+            if !surface.header.same_lattice(&first.header) {
+                return Err(
+                    format!("Surface {} is not on the same lattice as surface 0", k).into(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Move each depth down to at least `thickness` below the deepest defined
+/// depth before it.
+fn push_down(column: &mut [f32], thickness: f32) {
+    let mut floor = f32::NEG_INFINITY;
+    for z in column.iter_mut().filter(|z| !z.is_nan()) {
+        if *z < floor {
+            *z = floor;
+        }
+        floor = *z + thickness;
+    }
+}
+
+/// Move each depth up to at most the shallowest defined depth after it.
+fn push_up(column: &mut [f32]) {
+    let mut ceiling = f32::INFINITY;
+    for z in column.iter_mut().rev().filter(|z| !z.is_nan()) {
+        if *z > ceiling {
+            *z = ceiling;
+        }
+        ceiling = *z;
+    }
+}
+
+fn adjustment(original: &Irap, new: &Irap) -> Adjustment {
+    let values: Vec<f32> = original
+        .values
+        .iter()
+        .zip(&new.values)
+        .map(|(a, b)| b - a)
+        .collect();
+    let changed = values.iter().filter(|&&d| d != 0.0 && !d.is_nan());
+    Adjustment {
+        nodes: changed.clone().count(),
+        max_change: changed.fold(0.0, |m: f64, &d| m.max(d.abs() as f64)),
+        change: Irap {
+            header: original.header.clone(),
+            values,
+        },
+    }
+}
@@ -0,0 +1,195 @@
+use crate::irap::{Irap, IrapHeader};
+use crate::utils::solve_linear;
+use rayon::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Least-squares polynomial trend in world coordinates.
+///
+/// The polynomial is in `x - center[0]` and `y - center[1]`, with one
+/// coefficient per term x^i * y^j with i + j at most `order`, ordered by
+/// degree and then by decreasing power of x: 1, x, y, x², xy, y², x³, x²y,
+/// xy², y³.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trend {
+    pub order: usize,
+    /// Mean position of the fitted data.
+    pub center: [f64; 2],
+    pub coefficients: Vec<f64>,
+    /// Fraction of the variance of the data explained by the trend.
+    pub r_squared: f64,
+}
+
+/// Trend fitted to the defined nodes of a surface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrendFit {
+    pub trend: Trend,
+    /// Trend evaluated at every node.
+    pub surface: Irap,
+    /// Surface minus trend, undefined where the surface is.
+    pub residual: Irap,
+}
+
+impl Trend {
+    /// Fit a trend of order 1 to 3 to scattered points, ignoring points with
+    /// undefined coordinates or values.
+    pub fn fit(xs: &[f64], ys: &[f64], zs: &[f64], order: usize) -> Result<Trend> {
+        if xs.len() != ys.len() || xs.len() != zs.len() {
+            return Err(format!(
+                "Coordinate arrays differ in length: x={}, y={}, z={}",
+                xs.len(),
+                ys.len(),
+                zs.len()
+            )
+            .into());
+        }
+        let points: Vec<[f64; 3]> = (0..xs.len())
+            .map(|i| [xs[i], ys[i], zs[i]])
+            .filter(|p| p.iter().all(|v| v.is_finite()))
+            .collect();
+        fit(&points, order)
+    }
+
+    /// Trend value at a world coordinate.
+    pub fn evaluate(&self, x: f64, y: f64) -> f64 {
+        terms(x - self.center[0], y - self.center[1], self.order)
+            .zip(&self.coefficients)
+            .map(|(t, c)| t * c)
+            .sum()
+    }
+
+    /// Trend evaluated at every node of a lattice.
+    pub fn to_surface(&self, header: &IrapHeader) -> Irap {
+        let nrow = header.nrow as usize;
+        let values = (0..header.len())
+            .into_par_iter()
+            .map(|idx| {
+                let (x, y) = header.node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                self.evaluate(x, y) as f32
+            })
+            .collect();
+        Irap {
+            header: header.clone(),
+            values,
+        }
+    }
+}
+
+impl Irap {
+    /// Fit a polynomial trend of order 1 to 3 to the defined nodes.
+    pub fn fit_trend(&self, order: usize) -> Result<TrendFit> {
+        let nrow = self.header.nrow as usize;
+        let points: Vec<[f64; 3]> = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .map(|idx| {
+                let (x, y) = self
+                    .header
+                    .node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                [x, y, self.values[idx] as f64]
+            })
+            .collect();
+        let trend = fit(&points, order)?;
+        let surface = trend.to_surface(&self.header);
+        let residual = Irap {
+            header: self.header.clone(),
+            values: self
+                .values
+                .iter()
+                .zip(&surface.values)
+                .map(|(v, t)| v - t)
+                .collect(),
+        };
+        Ok(TrendFit {
+            trend,
+            surface,
+            residual,
+        })
+    }
+}
+
+fn fit(points: &[[f64; 3]], order: usize) -> Result<Trend> {
+    if !(1..=3).contains(&order) {
+        return Err(format!("Trend order must be 1, 2 or 3, got {}", order).into());
+    }
+    let nterms = (order + 1) * (order + 2) / 2;
+    if points.len() < nterms {
+        return Err(format!(
+            "A trend of order {} needs at least {} points, got {}",
+            order,
+            nterms,
+            points.len()
+        )
+        .into());
+    }
+
+    let n = points.len() as f64;
+    let center = [
+        points.iter().map(|p| p[0]).sum::<f64>() / n,
+        points.iter().map(|p| p[1]).sum::<f64>() / n,
+    ];
+    // Fitting in coordinates scaled to [-1, 1] keeps the normal equations
+    // well conditioned for world coordinates and higher orders.
+    let scale = points
+        .iter()
+        .map(|p| (p[0] - center[0]).abs().max((p[1] - center[1]).abs()))
+        .fold(0.0, f64::max);
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+
+    let zero = || (vec![0.0; nterms * nterms], vec![0.0; nterms]);
+    let (matrix, rhs) = points
+        .par_iter()
+        .fold(zero, |(mut matrix, mut rhs), p| {
+            let u = (p[0] - center[0]) / scale;
+            let v = (p[1] - center[1]) / scale;
+            let t: Vec<f64> = terms(u, v, order).collect();
+            for r in 0..nterms {
+                for c in 0..nterms {
+                    matrix[r * nterms + c] += t[r] * t[c];
+                }
+                rhs[r] += t[r] * p[2];
+            }
+            (matrix, rhs)
+        })
+        .reduce(zero, |mut a, b| {
+            a.0.iter_mut().zip(b.0).for_each(|(s, t)| *s += t);
+            a.1.iter_mut().zip(b.1).for_each(|(s, t)| *s += t);
+            a
+        });
+    let scaled = solve_linear(matrix, rhs)
+        .ok_or("The points do not determine a unique trend, they may be collinear")?;
+
+    let coefficients = scaled
+        .iter()
+        .zip(degrees(order))
+        .map(|(c, degree)| c / scale.powi(degree as i32))
+        .collect();
+    let mut trend = Trend {
+        order,
+        center,
+        coefficients,
+        r_squared: 1.0,
+    };
+
+    let mean = points.iter().map(|p| p[2]).sum::<f64>() / n;
+    let total: f64 = points.iter().map(|p| (p[2] - mean).powi(2)).sum();
+    let residual: f64 = points
+        .iter()
+        .map(|p| (p[2] - trend.evaluate(p[0], p[1])).powi(2))
+        .sum();
+    if total > 0.0 {
+        trend.r_squared = 1.0 - residual / total;
+    }
+    Ok(trend)
+}
+
+/// Degree of each term, in the order of the coefficients.
+fn degrees(order: usize) -> impl Iterator<Item = usize> {
+    (0..=order).flat_map(|degree| std::iter::repeat_n(degree, degree + 1))
+}
+
+/// Terms x^i * y^j in the order of the coefficients.
+fn terms(x: f64, y: f64, order: usize) -> impl Iterator<Item = f64> {
+    (0..=order).flat_map(move |degree| {
+        (0..=degree).map(move |j| x.powi((degree - j) as i32) * y.powi(j as i32))
+    })
+}
@@ -1,3 +1,5 @@
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 pub fn column_major_to_row_major_index(idx: usize, ncol: usize, nrow: usize) -> usize {
     idx / ncol + (idx % ncol) * nrow
 }
@@ -6,3 +8,194 @@ pub fn fill_header(header: &mut crate::irap::IrapHeader) {
     header.xmax = header.xori + (header.ncol - 1) as f64 * header.xinc;
     header.ymax = header.yori + (header.nrow - 1) as f64 * header.yinc;
 }
+
+pub fn is_ascii_whitespace(byte: u8) -> bool {
+    matches!(byte, 0x20 | 0x09 | 0x0A | 0x0D | 0x0C | 0x0B)
+}
+
+pub fn skip_whitespace(buffer: &[u8]) -> usize {
+    buffer
+        .iter()
+        .position(|&c| !is_ascii_whitespace(c))
+        .unwrap_or(buffer.len())
+}
+
+pub fn read_value_float(buffer: &[u8]) -> Result<(f64, usize)> {
+    let pos = skip_whitespace(buffer);
+    if pos == buffer.len() {
+        return Err("Unexpected end of file".into());
+    }
+    let (num, len) = fast_float::parse_partial::<f64, _>(&buffer[pos..])?;
+    Ok((num, len + pos))
+}
+
+/// Write one (x, y, z) line of an IRAP points or polygons file, with an
+/// undefined z as the IRAP ASCII undefined value.
+pub fn write_xyz<W: std::io::Write>(out: &mut W, [x, y, z]: [f64; 3]) -> std::io::Result<()> {
+    if !x.is_finite() || !y.is_finite() || z.is_infinite() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Cannot write point ({}, {}, {})", x, y, z),
+        ));
+    }
+    if z.is_nan() {
+        writeln!(out, "{} {} {:.6}", x, y, crate::irap::UNDEF_MAP_IRAP_ASCII)
+    } else {
+        writeln!(out, "{} {} {}", x, y, z)
+    }
+}
+
+/// Undefined z values of a points or polygons file as NaN.
+pub fn undefined_z([x, y, z]: [f64; 3]) -> [f64; 3] {
+    if z >= crate::irap::UNDEF_MAP_IRAP_ASCII as f64 {
+        [x, y, f64::NAN]
+    } else {
+        [x, y, z]
+    }
+}
+
+/// Even-odd point in polygon test. Rings with fewer than three points
+/// contain nothing.
+pub fn point_in_polygon(ring: &[[f64; 2]], [px, py]: [f64; 2]) -> bool {
+    let n = ring.len();
+    if n < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = n - 1;
+    for i in 0..n {
+        let [xi, yi] = ring[i];
+        let [xj, yj] = ring[j];
+        if (yi > py) != (yj > py) && px < (xj - xi) * (py - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Signed area of a ring, positive when counter-clockwise.
+pub fn shoelace(ring: &[[f64; 2]]) -> f64 {
+    let n = ring.len();
+    (0..n)
+        .map(|k| {
+            let [x0, y0] = ring[k];
+            let [x1, y1] = ring[(k + 1) % n];
+            x0 * y1 - x1 * y0
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// The 4-connected neighbours of a node inside an `ncol` by `nrow` lattice.
+pub fn neighbours(
+    col: usize,
+    row: usize,
+    ncol: usize,
+    nrow: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        .into_iter()
+        .filter_map(move |(dc, dr)| {
+            let c = col.checked_add_signed(dc)?;
+            let r = row.checked_add_signed(dr)?;
+            (c < ncol && r < nrow).then_some((c, r))
+        })
+}
+
+/// Root of `idx` in a union-find forest, halving the path on the way.
+pub fn find_root(parent: &mut [usize], mut idx: usize) -> usize {
+    while parent[idx] != idx {
+        parent[idx] = parent[parent[idx]];
+        idx = parent[idx];
+    }
+    idx
+}
+
+/// Solve the dense n by n system `matrix * x = rhs`, with the matrix in
+/// row-major order, by Gaussian elimination with partial pivoting. `None`
+/// when the matrix is singular to working precision.
+pub fn solve_linear(mut matrix: Vec<f64>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    let scale = matrix.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    for k in 0..n {
+        let pivot =
+            (k..n).max_by(|&a, &b| matrix[a * n + k].abs().total_cmp(&matrix[b * n + k].abs()))?;
+        if matrix[pivot * n + k].abs() <= 1e-13 * scale {
+            return None;
+        }
+        if pivot != k {
+            for c in 0..n {
+                matrix.swap(k * n + c, pivot * n + c);
+            }
+            rhs.swap(k, pivot);
+        }
+        for r in k + 1..n {
+            let factor = matrix[r * n + k] / matrix[k * n + k];
+            if factor == 0.0 {
+                continue;
+            }
+            for c in k..n {
+                matrix[r * n + c] -= factor * matrix[k * n + c];
+            }
+            rhs[r] -= factor * rhs[k];
+        }
+    }
+    for k in (0..n).rev() {
+        let sum: f64 = (k + 1..n).map(|c| matrix[k * n + c] * rhs[c]).sum();
+        rhs[k] = (rhs[k] - sum) / matrix[k * n + k];
+    }
+    Some(rhs)
+}
+
+/// Small seeded random generator (SplitMix64), so that results depending on
+/// a seed are the same on every platform and release.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Generator for stream `index` of `seed`, independent of the other streams.
+    pub fn stream(seed: u64, index: u64) -> Self {
+        let mut mixer = Rng::new(seed ^ index.wrapping_mul(0xD1B54A32D192ED03));
+        Rng::new(mixer.next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal, by the Box-Muller transform.
+    pub fn normal(&mut self) -> f64 {
+        let radius = (-2.0 * (1.0 - self.uniform()).ln()).sqrt();
+        radius * (std::f64::consts::TAU * self.uniform()).cos()
+    }
+
+    /// `count` distinct indices below `len` in increasing order, or all of
+    /// them when `count` is at least `len`.
+    pub fn sample_indices(&mut self, len: usize, count: usize) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..len).collect();
+        if count < len {
+            for k in 0..count {
+                let pick = k + (self.uniform() * (len - k) as f64) as usize;
+                indices.swap(k, pick.min(len - 1));
+            }
+            indices.truncate(count);
+            indices.sort_unstable();
+        }
+        indices
+    }
+}
@@ -0,0 +1,171 @@
+mod model;
+
+pub use model::{ModelKind, VariogramModel};
+
+use crate::irap::Irap;
+use crate::utils::Rng;
+use rayon::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Direction of a directional variogram.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Direction {
+    /// Degrees clockwise from north.
+    pub azimuth: f64,
+    /// Largest angle in degrees between a pair and the azimuth, at most 90.
+    pub tolerance: f64,
+    /// Largest distance of a pair across the azimuth, unlimited when `None`.
+    pub bandwidth: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VariogramOptions {
+    /// Width of the lag bins. Bin k holds pairs with separation in
+    /// [k * lag, (k + 1) * lag).
+    pub lag: f64,
+    pub nlags: usize,
+    /// Omnidirectional when `None`.
+    pub direction: Option<Direction>,
+    /// Random subset of the points to use, for large grids.
+    pub max_points: Option<usize>,
+    /// Seed of the random subset.
+    pub seed: u64,
+}
+
+/// Experimental semivariogram, with one entry per lag bin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variogram {
+    /// Mean separation of the pairs in each bin, NaN for empty bins.
+    pub lags: Vec<f64>,
+    /// Half the mean squared difference of the pairs, NaN for empty bins.
+    pub gamma: Vec<f64>,
+    pub pairs: Vec<usize>,
+}
+
+/// Experimental variogram of scattered points, ignoring points with
+/// undefined coordinates or values.
+pub fn from_points(
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    options: &VariogramOptions,
+) -> Result<Variogram> {
+    if xs.len() != ys.len() || xs.len() != zs.len() {
+        return Err(format!(
+            "Coordinate arrays differ in length: x={}, y={}, z={}",
+            xs.len(),
+            ys.len(),
+            zs.len()
+        )
+        .into());
+    }
+    let points: Vec<[f64; 3]> = (0..xs.len())
+        .map(|i| [xs[i], ys[i], zs[i]])
+        .filter(|p| p.iter().all(|v| v.is_finite()))
+        .collect();
+    compute(points, options)
+}
+
+impl Irap {
+    /// Experimental variogram of the defined nodes.
+    pub fn variogram(&self, options: &VariogramOptions) -> Result<Variogram> {
+        let nrow = self.header.nrow as usize;
+        let points = (0..self.values.len())
+            .filter(|&idx| !self.values[idx].is_nan())
+            .map(|idx| {
+                let (x, y) = self
+                    .header
+                    .node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                [x, y, self.values[idx] as f64]
+            })
+            .collect();
+        compute(points, options)
+    }
+}
+
+fn compute(points: Vec<[f64; 3]>, options: &VariogramOptions) -> Result<Variogram> {
+    check_options(options)?;
+    let points = match options.max_points {
+        Some(max_points) if max_points < points.len() => Rng::new(options.seed)
+            .sample_indices(points.len(), max_points)
+            .into_iter()
+            .map(|i| points[i])
+            .collect(),
+        _ => points,
+    };
+
+    let nlags = options.nlags;
+    let direction = options.direction.map(|d| {
+        let (sin, cos) = d.azimuth.to_radians().sin_cos();
+        (sin, cos, d.tolerance.to_radians(), d.bandwidth)
+    });
+    // (sum of separations, sum of half squared differences, pairs) per bin
+    let zero = || vec![(0.0, 0.0, 0usize); nlags];
+    let sums = (0..points.len())
+        .into_par_iter()
+        .fold(zero, |mut sums, i| {
+            let [xi, yi, zi] = points[i];
+            for &[xj, yj, zj] in &points[i + 1..] {
+                let (dx, dy) = (xj - xi, yj - yi);
+                let h = dx.hypot(dy);
+                let bin = (h / options.lag) as usize;
+                if bin >= nlags {
+                    continue;
+                }
+                if let Some((sin, cos, tolerance, bandwidth)) = direction {
+                    let along = (dx * sin + dy * cos).abs();
+                    let across = (dx * cos - dy * sin).abs();
+                    if across.atan2(along) > tolerance + 1e-12
+                        || bandwidth.is_some_and(|b| across > b)
+                    {
+                        continue;
+                    }
+                }
+                let sum = &mut sums[bin];
+                sum.0 += h;
+                sum.1 += 0.5 * (zi - zj) * (zi - zj);
+                sum.2 += 1;
+            }
+            sums
+        })
+        .reduce(zero, |mut a, b| {
+            for (s, t) in a.iter_mut().zip(b) {
+                s.0 += t.0;
+                s.1 += t.1;
+                s.2 += t.2;
+            }
+            a
+        });
+
+    let mean = |sum: f64, n: usize| if n > 0 { sum / n as f64 } else { f64::NAN };
+    Ok(Variogram {
+        lags: sums.iter().map(|s| mean(s.0, s.2)).collect(),
+        gamma: sums.iter().map(|s| mean(s.1, s.2)).collect(),
+        pairs: sums.iter().map(|s| s.2).collect(),
+    })
+}
+
+fn check_options(options: &VariogramOptions) -> Result<()> {
+    if !(options.lag > 0.0 && options.lag.is_finite()) {
+        return Err(format!("Invalid lag width: {}", options.lag).into());
+    }
+    if options.nlags == 0 {
+        return Err("Number of lags must be positive".into());
+    }
+    if let Some(direction) = options.direction {
+        if !(direction.tolerance > 0.0 && direction.tolerance <= 90.0) {
+            return Err(format!(
+                "Angle tolerance must be in (0, 90] degrees, got {}",
+                direction.tolerance
+            )
+            .into());
+        }
+        if let Some(bandwidth) = direction.bandwidth
+            && (bandwidth.is_nan() || bandwidth <= 0.0)
+        {
+            return Err(format!("Invalid bandwidth: {}", bandwidth).into());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use super::{Result, Variogram};
+
+/// Number of ranges tried before refining the best one.
+const RANGE_CANDIDATES: usize = 200;
+const REFINEMENT_STEPS: usize = 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelKind {
+    Spherical,
+    Exponential,
+    Gaussian,
+}
+
+/// Variogram model `nugget + sill * f(h / range)`, where the exponential and
+/// Gaussian models use the practical range, reaching 95% of the sill there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VariogramModel {
+    pub kind: ModelKind,
+    pub nugget: f64,
+    /// Partial sill, so the total sill is `nugget + sill`.
+    pub sill: f64,
+    pub range: f64,
+}
+
+impl VariogramModel {
+    /// Semivariance at separation `h`, zero at zero separation.
+    pub fn gamma(&self, h: f64) -> f64 {
+        if h == 0.0 {
+            0.0
+        } else {
+            self.nugget + self.sill * shape(self.kind, h / self.range)
+        }
+    }
+
+    /// Covariance at separation `h`, the total sill minus the semivariance.
+    pub fn covariance(&self, h: f64) -> f64 {
+        self.nugget + self.sill - self.gamma(h)
+    }
+}
+
+fn shape(kind: ModelKind, r: f64) -> f64 {
+    match kind {
+        ModelKind::Spherical if r < 1.0 => 1.5 * r - 0.5 * r * r * r,
+        ModelKind::Spherical => 1.0,
+        ModelKind::Exponential => 1.0 - (-3.0 * r).exp(),
+        ModelKind::Gaussian => 1.0 - (-3.0 * r * r).exp(),
+    }
+}
+
+impl Variogram {
+    /// Fit a model by weighted least squares over the non-empty bins.
+    ///
+    /// Bins are weighted by their number of pairs over the squared lag, so
+    /// that short lags, which matter most for interpolation, fit best. The
+    /// nugget and sill are solved for exactly at each range, constrained to
+    /// be non-negative, and the range is found by a search over a log-spaced
+    /// grid refined by golden-section search.
+    pub fn fit(&self, kind: ModelKind) -> Result<VariogramModel> {
+        let bins: Vec<(f64, f64, f64)> = (0..self.gamma.len())
+            .filter(|&k| self.pairs[k] > 0 && self.lags[k] > 0.0)
+            .map(|k| {
+                (
+                    self.lags[k],
+                    self.gamma[k],
+                    self.pairs[k] as f64 / self.lags[k].powi(2),
+                )
+            })
+            .collect();
+        if bins.len() < 2 {
+            return Err("At least two non-empty lag bins are needed to fit a variogram".into());
+        }
+
+        let shortest = bins.iter().map(|b| b.0).fold(f64::INFINITY, f64::min);
+        let longest = bins.iter().map(|b| b.0).fold(0.0, f64::max);
+        let (lo, hi) = ((0.5 * shortest).ln(), (3.0 * longest).ln());
+        let misfit = |log_range: f64| fit_at_range(&bins, kind, log_range.exp()).1;
+
+        let step = (hi - lo) / (RANGE_CANDIDATES - 1) as f64;
+        let best = (0..RANGE_CANDIDATES)
+            .map(|k| lo + k as f64 * step)
+            .min_by(|&a, &b| misfit(a).total_cmp(&misfit(b)))
+            .unwrap_or(lo);
+
+        let (mut a, mut b) = (best - step, best + step);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..REFINEMENT_STEPS {
+            let (c, d) = (b - ratio * (b - a), a + ratio * (b - a));
+            if misfit(c) < misfit(d) {
+                b = d;
+            } else {
+                a = c;
+            }
+        }
+        let log_range = [best, (a + b) / 2.0]
+            .into_iter()
+            .min_by(|&x, &y| misfit(x).total_cmp(&misfit(y)))
+            .unwrap_or(best);
+        let range = log_range.exp();
+        let ((nugget, sill), _) = fit_at_range(&bins, kind, range);
+        Ok(VariogramModel {
+            kind,
+            nugget,
+            sill,
+            range,
+        })
+    }
+}
+
+/// Non-negative (nugget, sill) minimising the weighted misfit to the bins
+/// (lag, gamma, weight) at a given range, with the misfit.
+fn fit_at_range(bins: &[(f64, f64, f64)], kind: ModelKind, range: f64) -> ((f64, f64), f64) {
+    let (mut sw, mut sf, mut sff, mut sg, mut sfg) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for &(h, g, w) in bins {
+        let f = shape(kind, h / range);
+        sw += w;
+        sf += w * f;
+        sff += w * f * f;
+        sg += w * g;
+        sfg += w * f * g;
+    }
+    let determinant = sw * sff - sf * sf;
+    let mut nugget = if determinant > 0.0 {
+        (sff * sg - sf * sfg) / determinant
+    } else {
+        0.0
+    };
+    let mut sill = if determinant > 0.0 {
+        (sw * sfg - sf * sg) / determinant
+    } else if sff > 0.0 {
+        sfg / sff
+    } else {
+        0.0
+    };
+    if nugget < 0.0 {
+        nugget = 0.0;
+        sill = if sff > 0.0 { sfg / sff } else { 0.0 };
+    }
+    if sill < 0.0 {
+        sill = 0.0;
+        nugget = sg / sw;
+    }
+    let misfit = bins
+        .iter()
+        .map(|&(h, g, w)| w * (g - nugget - sill * shape(kind, h / range)).powi(2))
+        .sum();
+    ((nugget, sill), misfit)
+}
@@ -0,0 +1,93 @@
+/// Triangle vertex in lattice coordinates with linearly varying attributes:
+/// the top, and the upper bounds whose minimum is the base of the column.
+#[derive(Clone, Copy)]
+pub struct Vertex {
+    pub pos: [f64; 2],
+    pub attrs: [f64; ATTRIBUTES],
+}
+
+pub const TOP: usize = 0;
+pub const ATTRIBUTES: usize = 4;
+
+/// Area and volume of max(0, min(uppers) - top) over a convex polygon on
+/// which all attributes are linear.
+///
+/// The polygon is split where one upper bound crosses another, so on each
+/// piece the minimum is a single linear function and the integral is exact.
+pub fn integrate(polygon: &[Vertex], uppers: &[usize]) -> (f64, f64) {
+    if polygon.len() < 3 {
+        return (0.0, 0.0);
+    }
+    match uppers {
+        [] => (0.0, 0.0),
+        [upper] => {
+            let thickness = |v: &Vertex| v.attrs[*upper] - v.attrs[TOP];
+            // Strict, so that zero-thickness rock adds no area
+            let clipped = clip(polygon, thickness, true);
+            (area(&clipped), volume(&clipped, thickness))
+        }
+        [first, second, rest @ ..] => {
+            let (first, second) = (*first, *second);
+            let difference = |v: &Vertex| v.attrs[second] - v.attrs[first];
+            let mut remaining: Vec<usize> = rest.to_vec();
+
+            // Where first <= second the second bound does not matter. Where
+            // they are equal only the first half counts, so no area is doubled.
+            remaining.push(first);
+            let (a1, v1) = integrate(&clip(polygon, difference, false), &remaining);
+            remaining.pop();
+            remaining.push(second);
+            let (a2, v2) = integrate(&clip(polygon, |v| -difference(v), true), &remaining);
+            (a1 + a2, v1 + v2)
+        }
+    }
+}
+
+/// Part of a convex polygon where the linear function `f` is non-negative,
+/// or positive when `strict`.
+fn clip(polygon: &[Vertex], f: impl Fn(&Vertex) -> f64, strict: bool) -> Vec<Vertex> {
+    let inside = |value: f64| if strict { value > 0.0 } else { value >= 0.0 };
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for k in 0..polygon.len() {
+        let p = &polygon[k];
+        let q = &polygon[(k + 1) % polygon.len()];
+        let (fp, fq) = (f(p), f(q));
+        if inside(fp) {
+            out.push(*p);
+        }
+        if inside(fp) != inside(fq) {
+            let t = fp / (fp - fq);
+            let mut v = *p;
+            for d in 0..2 {
+                v.pos[d] = p.pos[d] + t * (q.pos[d] - p.pos[d]);
+            }
+            for d in 0..ATTRIBUTES {
+                v.attrs[d] = p.attrs[d] + t * (q.attrs[d] - p.attrs[d]);
+            }
+            out.push(v);
+        }
+    }
+    out
+}
+
+fn triangle_area(a: &Vertex, b: &Vertex, c: &Vertex) -> f64 {
+    ((b.pos[0] - a.pos[0]) * (c.pos[1] - a.pos[1]) - (c.pos[0] - a.pos[0]) * (b.pos[1] - a.pos[1]))
+        .abs()
+        / 2.0
+}
+
+fn area(polygon: &[Vertex]) -> f64 {
+    (1..polygon.len().saturating_sub(1))
+        .map(|k| triangle_area(&polygon[0], &polygon[k], &polygon[k + 1]))
+        .sum()
+}
+
+/// Integral of a linear function over a convex polygon, by fan triangulation.
+fn volume(polygon: &[Vertex], f: impl Fn(&Vertex) -> f64) -> f64 {
+    (1..polygon.len().saturating_sub(1))
+        .map(|k| {
+            let (a, b, c) = (&polygon[0], &polygon[k], &polygon[k + 1]);
+            triangle_area(a, b, c) * (f(a) + f(b) + f(c)) / 3.0
+        })
+        .sum()
+}
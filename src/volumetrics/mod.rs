@@ -0,0 +1,273 @@
+mod integration;
+
+use crate::irap::{Irap, IrapHeader};
+use crate::utils::point_in_polygon;
+use integration::{ATTRIBUTES, TOP, Vertex, integrate};
+use rayon::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const BASE: usize = 1;
+const CONTACT: usize = 2;
+const CUTOFF: usize = 3;
+
+/// Fluid contact truncating the volume from below.
+#[derive(Clone, Copy, Debug)]
+pub enum Contact<'a> {
+    /// Constant depth.
+    Level(f64),
+    /// Depth given by a surface on the same lattice as the top and base.
+    Surface(&'a Irap),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct VolumeOptions<'a> {
+    pub contact: Option<Contact<'a>>,
+    /// Closed ring in world coordinates the volume is restricted to.
+    pub polygon: Option<&'a [[f64; 2]]>,
+    /// Depth spacing of the hypsometry table; no table when `None`.
+    pub slice_interval: Option<f64>,
+    /// Number of sub-cells along each axis of a lattice cell.
+    pub subdivisions: usize,
+}
+
+impl Default for VolumeOptions<'_> {
+    fn default() -> Self {
+        VolumeOptions {
+            contact: None,
+            polygon: None,
+            slice_interval: None,
+            subdivisions: 4,
+        }
+    }
+}
+
+/// Area and volume of the rock above `depth`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HypsometryRow {
+    pub depth: f64,
+    pub area: f64,
+    pub volume: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Volumetrics {
+    /// Gross rock volume in world units.
+    pub volume: f64,
+    /// Map area where the rock has positive thickness.
+    pub area: f64,
+    pub hypsometry: Vec<HypsometryRow>,
+}
+
+/// Gross rock volume between `top` and `base`, truncated at the contact.
+///
+/// Depths increase downwards, so rock is where the top is above both the base
+/// and the contact. Each lattice cell is split into sub-cells, the surfaces are
+/// interpolated bilinearly at their corners, and each sub-cell is split into
+/// two triangles on which the thickness is integrated exactly, including where
+/// the surfaces cross. Cells with an undefined corner on any of the surfaces
+/// are excluded. With a polygon, sub-cell triangles are counted when their
+/// centroid is inside it.
+pub fn volumetrics(top: &Irap, base: &Irap, options: &VolumeOptions) -> Result<Volumetrics> {
+    check_lattice(&top.header, &base.header, "base")?;
+    if let Some(Contact::Surface(contact)) = options.contact {
+        check_lattice(&top.header, &contact.header, "contact")?;
+    }
+    if options.subdivisions == 0 {
+        return Err("Number of subdivisions must be positive".into());
+    }
+    if let Some(interval) = options.slice_interval
+        && !(interval > 0.0 && interval.is_finite())
+    {
+        return Err(format!("Invalid slice interval: {}", interval).into());
+    }
+
+    let depths = match options.slice_interval {
+        Some(interval) => slice_depths(top, base, options, interval),
+        None => Vec::new(),
+    };
+    let mut uppers = vec![BASE];
+    if options.contact.is_some() {
+        uppers.push(CONTACT);
+    }
+
+    let ncol = top.header.ncol as usize;
+    let nrow = top.header.nrow as usize;
+    let cell_area = (top.header.xinc * top.header.yinc).abs();
+
+    let zero = || (0.0, 0.0, vec![(0.0, 0.0); depths.len()]);
+    let (area, volume, slices) = (0..ncol.saturating_sub(1))
+        .into_par_iter()
+        .map(|i| {
+            let mut sums = zero();
+            for j in 0..nrow.saturating_sub(1) {
+                let Some(corners) = cell_corners(top, base, options.contact, i, j) else {
+                    continue;
+                };
+                for triangle in sub_triangles(corners, i, j, options.subdivisions) {
+                    if let Some(polygon) = options.polygon {
+                        let col = triangle.iter().map(|v| v.pos[0]).sum::<f64>() / 3.0;
+                        let row = triangle.iter().map(|v| v.pos[1]).sum::<f64>() / 3.0;
+                        let (x, y) = top.header.node_xy(col, row);
+                        if !point_in_polygon(polygon, [x, y]) {
+                            continue;
+                        }
+                    }
+                    accumulate(&triangle, &uppers, &depths, &mut sums);
+                }
+            }
+            sums
+        })
+        .reduce(zero, |mut a, b| {
+            a.0 += b.0;
+            a.1 += b.1;
+            for (s, t) in a.2.iter_mut().zip(b.2) {
+                s.0 += t.0;
+                s.1 += t.1;
+            }
+            a
+        });
+
+    Ok(Volumetrics {
+        volume: volume * cell_area,
+        area: area * cell_area,
+        hypsometry: depths
+            .iter()
+            .zip(slices)
+            .map(|(&depth, (area, volume))| HypsometryRow {
+                depth,
+                area: area * cell_area,
+                volume: volume * cell_area,
+            })
+            .collect(),
+    })
+}
+
+fn check_lattice(top: &IrapHeader, other: &IrapHeader, name: &str) -> Result<()> {
+    if !top.same_lattice(other) {
+        return Err(format!("The {} surface is not on the same lattice as the top", name).into());
+    }
+    Ok(())
+}
+
+/// Depths at multiples of `interval` spanning the defined top and base.
+fn slice_depths(top: &Irap, base: &Irap, options: &VolumeOptions, interval: f64) -> Vec<f64> {
+    let (lo, hi) = top
+        .values
+        .iter()
+        .chain(&base.values)
+        .filter(|v| !v.is_nan())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v as f64), hi.max(v as f64))
+        });
+    if lo > hi {
+        return Vec::new();
+    }
+    let hi = match options.contact {
+        Some(Contact::Level(level)) => hi.min(level).max(lo),
+        _ => hi,
+    };
+    let first = (lo / interval).floor() as i64;
+    let last = (hi / interval).ceil() as i64;
+    (first..=last).map(|k| k as f64 * interval).collect()
+}
+
+/// Attributes at the corners (i, j), (i + 1, j), (i, j + 1), (i + 1, j + 1),
+/// or `None` if any is undefined.
+fn cell_corners(
+    top: &Irap,
+    base: &Irap,
+    contact: Option<Contact>,
+    i: usize,
+    j: usize,
+) -> Option<[[f64; ATTRIBUTES]; 4]> {
+    let mut corners = [[0.0; ATTRIBUTES]; 4];
+    for (k, (di, dj)) in [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().enumerate() {
+        let idx = top.header.index(i + di, j + dj);
+        corners[k][TOP] = top.values[idx] as f64;
+        corners[k][BASE] = base.values[idx] as f64;
+        corners[k][CONTACT] = match contact {
+            Some(Contact::Level(level)) => level,
+            Some(Contact::Surface(surface)) => surface.values[idx] as f64,
+            None => 0.0,
+        };
+        if corners[k].iter().any(|v| v.is_nan()) {
+            return None;
+        }
+    }
+    Some(corners)
+}
+
+/// Triangles of the `n` by `n` sub-cells of cell (i, j), in lattice coordinates.
+fn sub_triangles(
+    corners: [[f64; ATTRIBUTES]; 4],
+    i: usize,
+    j: usize,
+    n: usize,
+) -> impl Iterator<Item = [Vertex; 3]> {
+    let vertex = move |a: usize, b: usize| {
+        let (u, v) = (a as f64 / n as f64, b as f64 / n as f64);
+        let weights = [(1.0 - u) * (1.0 - v), u * (1.0 - v), (1.0 - u) * v, u * v];
+        let mut attrs = [0.0; ATTRIBUTES];
+        for (w, corner) in weights.iter().zip(&corners) {
+            for d in 0..ATTRIBUTES {
+                attrs[d] += w * corner[d];
+            }
+        }
+        Vertex {
+            pos: [i as f64 + u, j as f64 + v],
+            attrs,
+        }
+    };
+    (0..n).flat_map(move |a| {
+        (0..n).flat_map(move |b| {
+            let (p00, p10) = (vertex(a, b), vertex(a + 1, b));
+            let (p01, p11) = (vertex(a, b + 1), vertex(a + 1, b + 1));
+            [[p00, p10, p11], [p00, p11, p01]]
+        })
+    })
+}
+
+fn accumulate(
+    triangle: &[Vertex; 3],
+    uppers: &[usize],
+    depths: &[f64],
+    sums: &mut (f64, f64, Vec<(f64, f64)>),
+) {
+    let (area, volume) = integrate(triangle, uppers);
+    sums.0 += area;
+    sums.1 += volume;
+    if area == 0.0 || depths.is_empty() {
+        return;
+    }
+
+    let shallowest = triangle
+        .iter()
+        .map(|v| v.attrs[TOP])
+        .fold(f64::INFINITY, f64::min);
+    // Below this depth the cutoff never bounds the column
+    let deepest = triangle
+        .iter()
+        .flat_map(|v| uppers.iter().map(|&u| v.attrs[u]))
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let mut uppers = uppers.to_vec();
+    uppers.push(CUTOFF);
+    for (&depth, slice) in depths.iter().zip(sums.2.iter_mut()) {
+        if depth <= shallowest {
+            continue;
+        }
+        if depth >= deepest {
+            slice.0 += area;
+            slice.1 += volume;
+            continue;
+        }
+        let mut cut = *triangle;
+        for v in &mut cut {
+            v.attrs[CUTOFF] = depth;
+        }
+        let (a, v) = integrate(&cut, &uppers);
+        slice.0 += a;
+        slice.1 += v;
+    }
+}
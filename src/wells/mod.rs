@@ -0,0 +1,139 @@
+use crate::irap::Irap;
+use rayon::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Point where a well trajectory crosses a surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WellCrossing {
+    pub md: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    /// Surface value at (x, y), equal to z up to rounding.
+    pub value: f64,
+}
+
+/// Crossings of a well trajectory with the surface, ordered by measured depth.
+///
+/// `trajectory` holds (x, y, z, md) stations with non-decreasing md, and z in
+/// the same convention as the surface values. The trajectory is linear
+/// between stations, and the surface is bilinear within each cell, so the
+/// difference between them is quadratic along each piece of a segment inside
+/// a cell and its roots are found exactly. A well that leaves and re-enters
+/// a surface gives several crossings, and a well touching the surface without
+/// crossing it gives one. Pieces over undefined cells or outside the lattice
+/// have no crossings.
+pub fn intersect_well(surface: &Irap, trajectory: &[[f64; 4]]) -> Result<Vec<WellCrossing>> {
+    check_trajectory(trajectory)?;
+    Ok(crossings(surface, trajectory))
+}
+
+/// `intersect_well` for each of `surfaces`, in parallel.
+pub fn intersect_well_batch(
+    surfaces: &[Irap],
+    trajectory: &[[f64; 4]],
+) -> Result<Vec<Vec<WellCrossing>>> {
+    check_trajectory(trajectory)?;
+    Ok(surfaces
+        .par_iter()
+        .map(|surface| crossings(surface, trajectory))
+        .collect())
+}
+
+fn check_trajectory(trajectory: &[[f64; 4]]) -> Result<()> {
+    if trajectory.iter().flatten().any(|v| !v.is_finite()) {
+        return Err("Trajectory has non-finite values".into());
+    }
+    if let Some(k) = trajectory
+        .windows(2)
+        .position(|pair| pair[1][3] < pair[0][3])
+    {
+        return Err(format!("Measured depth decreases after station {}", k + 1).into());
+    }
+    Ok(())
+}
+
+fn crossings(surface: &Irap, trajectory: &[[f64; 4]]) -> Vec<WellCrossing> {
+    let header = &surface.header;
+    let mut crossings: Vec<WellCrossing> = Vec::new();
+    for pair in trajectory.windows(2) {
+        let [p0, p1] = [pair[0], pair[1]];
+        let station = |s: f64| -> [f64; 4] { std::array::from_fn(|d| p0[d] + s * (p1[d] - p0[d])) };
+        let difference = |s: f64| {
+            let [x, y, z, _] = station(s);
+            z - surface.sample(x, y)
+        };
+
+        let (c0, r0) = header.world_to_grid(p0[0], p0[1]);
+        let (c1, r1) = header.world_to_grid(p1[0], p1[1]);
+        let mut breaks = vec![0.0, 1.0];
+        breaks.extend(lattice_lines(c0, c1));
+        breaks.extend(lattice_lines(r0, r1));
+        breaks.sort_by(f64::total_cmp);
+
+        for piece in breaks.windows(2) {
+            let (s0, s1) = (piece[0], piece[1]);
+            if s1 <= s0 {
+                continue;
+            }
+            let samples = [difference(s0), difference((s0 + s1) / 2.0), difference(s1)];
+            if samples.iter().any(|v| v.is_nan()) {
+                continue;
+            }
+            for t in quadratic_roots(samples) {
+                let [x, y, z, md] = station(s0 + t * (s1 - s0));
+                // Crossings on piece boundaries are found from both sides
+                if crossings
+                    .last()
+                    .is_some_and(|c| (c.md - md).abs() <= 1e-9 * md.abs().max(1.0))
+                {
+                    continue;
+                }
+                crossings.push(WellCrossing {
+                    md,
+                    x,
+                    y,
+                    z,
+                    value: surface.sample(x, y),
+                });
+            }
+        }
+    }
+    crossings
+}
+
+/// Fractions along the way from `a` to `b` where integer values are passed.
+fn lattice_lines(a: f64, b: f64) -> impl Iterator<Item = f64> {
+    let (lo, hi) = (a.min(b), a.max(b));
+    (lo.floor() as i64 + 1..=hi.ceil() as i64 - 1).map(move |k| (k as f64 - a) / (b - a))
+}
+
+/// Roots in [0, 1], in increasing order, of the quadratic through the values
+/// at 0, 1/2 and 1.
+fn quadratic_roots([f0, fm, f1]: [f64; 3]) -> Vec<f64> {
+    let a = 2.0 * (f0 - 2.0 * fm + f1);
+    let b = f1 - f0 - a;
+    let c = f0;
+    let scale = f0.abs().max(fm.abs()).max(f1.abs());
+    let mut roots = if a.abs() <= 1e-12 * scale {
+        if b == 0.0 { vec![] } else { vec![-c / b] }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            vec![]
+        } else {
+            // Avoids cancellation in the root closer to zero
+            let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+            if q == 0.0 {
+                vec![0.0]
+            } else {
+                vec![q / a, c / q]
+            }
+        }
+    };
+    roots.retain(|t| (0.0..=1.0).contains(t));
+    roots.sort_by(f64::total_cmp);
+    roots.dedup();
+    roots
+}
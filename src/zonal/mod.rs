@@ -0,0 +1,157 @@
+use crate::irap::{Irap, Polygons};
+use crate::utils::point_in_polygon;
+use std::collections::BTreeMap;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Zones to compute statistics for.
+#[derive(Clone, Copy, Debug)]
+pub enum Zones<'a> {
+    /// Each polygon is a zone, numbered by its position. Overlapping polygons
+    /// all count the nodes they share.
+    Polygons(&'a Polygons),
+    /// Zone number of each node, rounded to the nearest integer, on the same
+    /// lattice as the surface. Undefined nodes belong to no zone.
+    Surface(&'a Irap),
+}
+
+/// Statistics of the defined nodes of a zone. Min, max, mean and std are NaN
+/// for zones without defined nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZoneStats {
+    pub zone: i64,
+    pub count: usize,
+    /// Number of nodes times the cell area.
+    pub area: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std: f64,
+    pub sum: f64,
+}
+
+/// Running statistics, with the variance accumulated by Welford's method.
+#[derive(Clone, Copy)]
+struct Accumulator {
+    count: usize,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+    sum: f64,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Accumulator {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+            sum: 0.0,
+        }
+    }
+}
+
+impl Accumulator {
+    fn add(&mut self, v: f64) {
+        self.count += 1;
+        self.min = self.min.min(v);
+        self.max = self.max.max(v);
+        self.sum += v;
+        let delta = v - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (v - self.mean);
+    }
+
+    fn finish(&self, zone: i64, cell_area: f64) -> ZoneStats {
+        let defined = self.count > 0;
+        let or_nan = |v: f64| if defined { v } else { f64::NAN };
+        ZoneStats {
+            zone,
+            count: self.count,
+            area: self.count as f64 * cell_area,
+            min: or_nan(self.min),
+            max: or_nan(self.max),
+            mean: or_nan(self.mean),
+            std: or_nan((self.m2 / self.count as f64).sqrt()),
+            sum: self.sum,
+        }
+    }
+}
+
+/// Count, area, min, max, mean, std and sum of the defined nodes of
+/// `surface` in each zone, computed in a single pass over the lattice and
+/// sorted by zone.
+pub fn zonal_stats(surface: &Irap, zones: &Zones) -> Result<Vec<ZoneStats>> {
+    let header = &surface.header;
+    let nrow = header.nrow as usize;
+    let cell_area = (header.xinc * header.yinc).abs();
+
+    match zones {
+        Zones::Surface(zone_surface) => {
+            if !zone_surface.header.same_lattice(header) {
+                return Err("The zone surface is not on the same lattice as the surface".into());
+            }
+            let mut accumulators: BTreeMap<i64, Accumulator> = BTreeMap::new();
+            for (&v, &zone) in surface.values.iter().zip(&zone_surface.values) {
+                if zone.is_nan() {
+                    continue;
+                }
+                let accumulator = accumulators.entry(zone.round() as i64).or_default();
+                if !v.is_nan() {
+                    accumulator.add(v as f64);
+                }
+            }
+            Ok(accumulators
+                .iter()
+                .map(|(&zone, a)| a.finish(zone, cell_area))
+                .collect())
+        }
+        Zones::Polygons(polygons) => {
+            let rings: Vec<Vec<[f64; 2]>> = polygons
+                .polygons
+                .iter()
+                .map(|p| p.iter().map(|&[x, y, _]| [x, y]).collect())
+                .collect();
+            let bounds: Vec<[f64; 4]> = rings.iter().map(|r| bounding_box(r)).collect();
+            let mut accumulators = vec![Accumulator::default(); rings.len()];
+            for (idx, &v) in surface.values.iter().enumerate() {
+                if v.is_nan() {
+                    continue;
+                }
+                let (x, y) = header.node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+                for (k, ring) in rings.iter().enumerate() {
+                    let [xmin, ymin, xmax, ymax] = bounds[k];
+                    if x >= xmin
+                        && x <= xmax
+                        && y >= ymin
+                        && y <= ymax
+                        && point_in_polygon(ring, [x, y])
+                    {
+                        accumulators[k].add(v as f64);
+                    }
+                }
+            }
+            Ok(accumulators
+                .iter()
+                .enumerate()
+                .map(|(k, a)| a.finish(k as i64, cell_area))
+                .collect())
+        }
+    }
+}
+
+fn bounding_box(ring: &[[f64; 2]]) -> [f64; 4] {
+    ring.iter().fold(
+        [
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ],
+        |[xmin, ymin, xmax, ymax], &[x, y]| [xmin.min(x), ymin.min(y), xmax.max(x), ymax.max(y)],
+    )
+}
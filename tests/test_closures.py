@@ -0,0 +1,36 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def two_domes():
+    cols, rows = np.meshgrid(np.arange(41.0), np.arange(31.0), indexing="ij")
+    dist = np.minimum(np.hypot(cols - 12, rows - 15), np.hypot(cols - 28, rows - 15))
+    header = surfio.IrapHeader(ncol=41, nrow=31, xinc=25.0, yinc=25.0)
+    return surfio.IrapSurface(header, (1000.0 + 5.0 * dist).astype(np.float32))
+
+
+def test_closures():
+    closures = two_domes().closures()
+    assert len(closures) == 3
+    leaves = [c for c in closures if c.parent is not None]
+    assert sorted(c.crest for c in leaves) == [(12, 15), (28, 15)]
+    assert all(c.spill == (20, 15) and c.spill_depth == 1040.0 for c in leaves)
+    assert all(c.outline.shape[1] == 2 for c in closures)
+
+
+def test_closure_polygons():
+    polygons = two_domes().closure_polygons()
+    assert len(polygons) == 3
+    assert np.all(polygons.values[0][:, 2] == 1040.0)
+    assert surfio.Polygons.from_string(polygons.to_string()).values[0].shape[0] > 0
+
+
+def test_closures_undefined_as_sink():
+    srf = two_domes()
+    srf.values[12, 19] = np.nan
+    left = [c for c in srf.closures("sink") if c.crest == (12, 15)]
+    assert left[0].spill_depth == 1015.0
+    with pytest.raises(ValueError):
+        srf.closures("wall")
@@ -0,0 +1,106 @@
+use surfio_rs::closures::{UndefinedNodes, to_polygons};
+use surfio_rs::irap::polygons;
+use surfio_rs::{Irap, IrapHeader};
+
+/// Two conical highs at columns 12 and 28 of row 15, with a saddle between
+/// them at depth 1040 and the lowest spill off the lattice at depth 1060.
+fn two_domes() -> Irap {
+    let header = IrapHeader {
+        ncol: 41,
+        nrow: 31,
+        xinc: 25.0,
+        yinc: 25.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..41 {
+        for row in 0..31 {
+            let d = |c: f64| (col as f64 - c).hypot(row as f64 - 15.0);
+            values[header.index(col, row)] = (1000.0 + 5.0 * d(12.0).min(d(28.0))) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_closures_of_two_domes() {
+    let closures = two_domes().closures(UndefinedNodes::Boundary);
+    assert_eq!(closures.len(), 3);
+
+    let (leaves, parents): (Vec<_>, Vec<_>) = closures.iter().partition(|c| c.parent.is_some());
+    assert_eq!(leaves.len(), 2);
+    let mut crests: Vec<(usize, usize)> = leaves.iter().map(|c| c.crest).collect();
+    crests.sort();
+    assert_eq!(crests, [(12, 15), (28, 15)]);
+    for leaf in &leaves {
+        assert_eq!(leaf.crest_depth, 1000.0);
+        assert_eq!(leaf.spill, (20, 15));
+        assert_eq!(leaf.spill_depth, 1040.0);
+        // Circle of radius 8 nodes
+        let expected = std::f64::consts::PI * 64.0 * 625.0;
+        assert!((leaf.area / expected - 1.0).abs() < 0.05, "{}", leaf.area);
+        assert_eq!(leaf.outline.first(), leaf.outline.last());
+        assert_eq!(leaf.parent, Some(2));
+    }
+
+    let composite = parents[0];
+    assert_eq!(composite.crest_depth, 1000.0);
+    assert_eq!(composite.spill_depth, 1060.0);
+    assert_eq!(composite.spill.1, 15);
+    assert!(composite.area > leaves[0].area + leaves[1].area);
+    assert_eq!(composite.parent, None);
+}
+
+#[test]
+fn test_undefined_nodes_as_sinks_or_boundaries() {
+    let mut irap = two_domes();
+    let idx = irap.header.index(12, 19);
+    irap.values[idx] = f32::NAN;
+
+    // As a boundary, the hole shadows the node behind it, which becomes a
+    // small closure. It merges with the upper part of the dome, and the
+    // combined closure spills at the saddle as before.
+    let sealed = irap.closures(UndefinedNodes::Boundary);
+    assert_eq!(sealed.len(), 5);
+    let shadow = sealed.iter().find(|c| c.crest == (12, 20)).unwrap();
+    let sibling = sealed
+        .iter()
+        .find(|c| c.crest == (12, 15) && c.spill == shadow.spill)
+        .unwrap();
+    assert_eq!(sibling.parent, shadow.parent);
+    let left = &sealed[shadow.parent.unwrap()];
+    assert_eq!(left.spill, (20, 15));
+    assert_eq!(left.spill_depth, 1040.0);
+
+    let leaking = irap.closures(UndefinedNodes::Sink);
+    let left = leaking.iter().find(|c| c.crest == (12, 15)).unwrap();
+    assert_eq!(left.spill, (12, 18));
+    assert_eq!(left.spill_depth, 1015.0);
+    assert_eq!(left.parent, None);
+    // The right dome now spills into the leaked region at the saddle
+    let right = leaking.iter().find(|c| c.crest == (28, 15)).unwrap();
+    assert_eq!(right.spill_depth, 1040.0);
+    assert_eq!(leaking.len(), 2);
+}
+
+#[test]
+fn test_monotone_surface_has_no_closures() {
+    let mut irap = two_domes();
+    for (idx, v) in irap.values.iter_mut().enumerate() {
+        *v = 1000.0 + idx as f32;
+    }
+    assert!(irap.closures(UndefinedNodes::Boundary).is_empty());
+}
+
+#[test]
+fn test_closure_polygons_round_trip() {
+    let closures = two_domes().closures(UndefinedNodes::Boundary);
+    let polygons = to_polygons(&closures);
+    assert_eq!(polygons.polygons.len(), 3);
+    assert!(polygons.polygons[0].iter().all(|p| p[2] == 1040.0));
+
+    let text = polygons::to_string(&polygons).unwrap();
+    let read = polygons::from_string(&text).unwrap();
+    assert_eq!(read.polygons.len(), 3);
+    assert_eq!(read.polygons[2].len(), closures[2].outline.len());
+}
@@ -0,0 +1,57 @@
+import json
+
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def cone_surface():
+    cols, rows = np.meshgrid(np.arange(21.0), np.arange(21.0), indexing="ij")
+    return surfio.IrapSurface(
+        surfio.IrapHeader(ncol=21, nrow=21, xinc=1.0, yinc=1.0, xmax=20.0, ymax=20.0),
+        np.hypot(cols - 10.0, rows - 10.0).astype(np.float32),
+    )
+
+
+def test_contours_returns_arrays_labelled_with_level():
+    lines = cone_surface().contours([3.0, 6.0])
+
+    assert len(lines) == 2
+    assert all(line.shape[1] == 3 for line in lines)
+    assert np.all(lines[0][:, 2] == 3.0)
+    assert np.allclose(np.hypot(lines[1][:, 0] - 10.0, lines[1][:, 1] - 10.0), 6.0, atol=0.1)
+
+
+def test_contours_can_be_written_as_polygons():
+    lines = cone_surface().contours([3.0])
+    polygons = surfio.Polygons.from_string(surfio.Polygons(lines).to_string())
+    assert np.array_equal(polygons.values[0], lines[0])
+
+
+def test_contours_to_geojson():
+    geojson = json.loads(cone_surface().contours_to_geojson([3.0, 6.0]))
+    assert geojson["type"] == "FeatureCollection"
+    assert [f["properties"]["level"] for f in geojson["features"]] == [3.0, 6.0]
+
+
+def test_isobands_area_per_band():
+    bands = cone_surface().isobands([0.0, 3.0, 6.0, 100.0])
+
+    assert [(b.lower, b.upper) for b in bands] == [(0.0, 3.0), (3.0, 6.0), (6.0, 100.0)]
+    assert sum(b.area for b in bands) == pytest.approx(400.0)
+    assert bands[0].area == pytest.approx(np.pi * 9.0, rel=0.05)
+
+
+def test_isobands_polygons_have_holes():
+    bands = cone_surface().isobands([3.0, 6.0])
+
+    assert len(bands[0].polygons) == 1
+    exterior, hole = bands[0].polygons[0]
+    assert exterior.shape[1] == 2
+    assert hole.shape[1] == 2
+
+
+def test_isobands_to_geojson():
+    geojson = json.loads(cone_surface().isobands_to_geojson([0.0, 3.0]))
+    assert geojson["features"][0]["geometry"]["type"] == "MultiPolygon"
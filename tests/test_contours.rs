@@ -0,0 +1,113 @@
+use surfio_rs::{Irap, IrapHeader, contours, irap};
+
+fn surface(ncol: u32, nrow: u32, rot: f64, f: impl Fn(f64, f64) -> f32) -> Irap {
+    let header = IrapHeader {
+        ncol,
+        nrow,
+        xori: 100.0,
+        yori: 200.0,
+        xinc: 2.0,
+        yinc: 2.0,
+        rot,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..ncol as usize {
+        for row in 0..nrow as usize {
+            values[header.index(col, row)] = f(col as f64, row as f64);
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_cone_gives_closed_ring_at_radius() {
+    let irap = surface(21, 21, 0.0, |c, r| ((c - 10.0).hypot(r - 10.0)) as f32);
+    let lines = irap.contours(&[5.0]);
+
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].closed);
+    assert_eq!(lines[0].points.first(), lines[0].points.last());
+    for &[x, y] in &lines[0].points {
+        let radius = (x - 120.0).hypot(y - 220.0) / 2.0;
+        assert!((radius - 5.0).abs() < 0.1);
+    }
+}
+
+#[test]
+fn test_each_level_is_labelled() {
+    let irap = surface(10, 5, 0.0, |c, _| c as f32);
+    let lines = irap.contours(&[1.5, 4.5, 100.0]);
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].level, 1.5);
+    assert_eq!(lines[1].level, 4.5);
+    assert!(!lines[0].closed);
+    for &[x, _] in &lines[0].points {
+        assert!((x - 103.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_undefined_node_breaks_line() {
+    let mut irap = surface(5, 5, 0.0, |c, _| c as f32);
+    let idx = irap.header.index(2, 2);
+    irap.values[idx] = f32::NAN;
+    let lines = irap.contours(&[2.5]);
+
+    assert_eq!(lines.len(), 2);
+    let total: usize = lines.iter().map(|l| l.points.len()).sum();
+    assert_eq!(total, 4);
+}
+
+#[test]
+fn test_saddle_is_resolved_by_center_value() {
+    // Corners (0,0) and (1,1) are high, so the center (0.5) is above 0.4
+    // and the high diagonal is connected.
+    let irap = surface(2, 2, 0.0, |c, r| if c == r { 1.0 } else { 0.0 });
+    let lines = irap.contours(&[0.4]);
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        // Each line cuts off one of the low corners
+        let [x, y] = line.points[0];
+        let [x1, y1] = line.points[1];
+        let near_low_corner = |px: f64, py: f64| {
+            ((px - 102.0).abs() < 1.3 && (py - 200.0).abs() < 1.3)
+                || ((px - 100.0).abs() < 1.3 && (py - 202.0).abs() < 1.3)
+        };
+        assert!(near_low_corner(x, y) && near_low_corner(x1, y1));
+    }
+
+    let lines = irap.contours(&[0.6]);
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn test_contours_respect_rotation() {
+    let irap = surface(5, 5, 90.0, |c, _| c as f32);
+    let lines = irap.contours(&[2.0]);
+
+    assert_eq!(lines.len(), 1);
+    for &[x, y] in &lines[0].points {
+        // Columns run along the y axis when rotated 90 degrees
+        assert!((y - 204.0).abs() < 1e-9);
+        assert!((100.0 - 8.0 - 1e-9..=100.0 + 1e-9).contains(&x));
+    }
+}
+
+#[test]
+fn test_contours_to_polygons_and_geojson() {
+    let irap = surface(10, 5, 0.0, |c, _| c as f32);
+    let lines = irap.contours(&[1.5, 4.5]);
+
+    let polygons = contours::to_polygons(&lines);
+    assert_eq!(polygons.polygons.len(), 2);
+    assert!(polygons.polygons[1].iter().all(|p| p[2] == 4.5));
+    let text = irap::polygons::to_string(&polygons).unwrap();
+    assert_eq!(irap::polygons::from_string(&text).unwrap(), polygons);
+
+    let geojson = contours::to_geojson_string(&lines).unwrap();
+    assert!(geojson.starts_with("{\"type\":\"FeatureCollection\""));
+    assert_eq!(geojson.matches("\"LineString\"").count(), 2);
+    assert!(geojson.contains("\"level\":4.5"));
+}
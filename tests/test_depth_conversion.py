@@ -0,0 +1,33 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def constant(value):
+    header = surfio.IrapHeader(ncol=4, nrow=3, xinc=25.0, yinc=25.0)
+    return surfio.IrapSurface(header, np.full((4, 3), value, dtype=np.float32))
+
+
+def test_time_to_depth_round_trip():
+    times = [constant(400.0), constant(1000.0)]
+    velocities = [constant(1500.0), (1800.0, 0.5)]
+    depths = surfio.IrapSurface.time_to_depth(times, velocities)
+    assert np.allclose(depths[0].values, 300.0)
+    expected = (300.0 + 3600.0) * np.exp(0.5 * 0.3) - 3600.0
+    assert np.allclose(depths[1].values, expected, atol=1e-3)
+
+    back = surfio.IrapSurface.depth_to_time(depths, velocities)
+    assert np.allclose(back[1].values, 1000.0, atol=1e-3)
+
+
+def test_time_convention():
+    depths = surfio.IrapSurface.time_to_depth([constant(0.5)], [2000.0], unit="s", two_way=False)
+    assert np.allclose(depths[0].values, 1000.0)
+    with pytest.raises(ValueError, match="Unknown time unit"):
+        surfio.IrapSurface.time_to_depth([constant(0.5)], [2000.0], unit="us")
+
+
+def test_invalid_velocity():
+    with pytest.raises(ValueError, match="Invalid interval velocity"):
+        surfio.IrapSurface.time_to_depth([constant(1000.0)], [-2000.0])
@@ -0,0 +1,112 @@
+use surfio_rs::depth_conversion::{
+    TimeConvention, TimeUnit, Velocity, depth_to_time, time_to_depth,
+};
+use surfio_rs::{Irap, IrapHeader};
+
+fn constant(value: f32) -> Irap {
+    let header = IrapHeader {
+        ncol: 4,
+        nrow: 3,
+        xinc: 25.0,
+        yinc: 25.0,
+        ..Default::default()
+    };
+    Irap {
+        values: vec![value; header.len()],
+        header,
+    }
+}
+
+fn assert_close(surface: &Irap, expected: f64, tolerance: f64) {
+    for &v in &surface.values {
+        assert!(
+            (v as f64 - expected).abs() < tolerance,
+            "{} != {}",
+            v,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_interval_velocities() {
+    let times = [constant(1000.0), constant(2000.0)];
+    let velocities = [Velocity::Interval(2000.0), Velocity::Interval(3000.0)];
+    let depths = time_to_depth(&times, &velocities, TimeConvention::default()).unwrap();
+    assert_eq!(depths.len(), 2);
+    assert_close(&depths[0], 1000.0, 1e-9);
+    assert_close(&depths[1], 2500.0, 1e-9);
+    assert_eq!(depths[0].header, times[0].header);
+
+    let back = depth_to_time(&depths, &velocities, TimeConvention::default()).unwrap();
+    assert_close(&back[0], 1000.0, 1e-9);
+    assert_close(&back[1], 2000.0, 1e-9);
+}
+
+#[test]
+fn test_one_way_seconds() {
+    let convention = TimeConvention {
+        unit: TimeUnit::Seconds,
+        two_way: false,
+    };
+    let depths =
+        time_to_depth(&[constant(0.5)], &[Velocity::Interval(2000.0)], convention).unwrap();
+    assert_close(&depths[0], 1000.0, 1e-9);
+}
+
+#[test]
+fn test_linear_velocity() {
+    let (v0, k) = (1800.0, 0.5);
+    let times = [constant(400.0), constant(1000.0)];
+    let velocities = [Velocity::Interval(1500.0), Velocity::Linear { v0, k }];
+    let depths = time_to_depth(&times, &velocities, TimeConvention::default()).unwrap();
+    assert_close(&depths[0], 300.0, 1e-9);
+    // z(t) = (z0 + v0 / k) * exp(k * t) - v0 / k over 0.3 s one-way
+    let expected = (300.0 + v0 / k) * (k * 0.3f64).exp() - v0 / k;
+    assert_close(&depths[1], expected, 1e-3);
+
+    let back = depth_to_time(&depths, &velocities, TimeConvention::default()).unwrap();
+    assert_close(&back[1], 1000.0, 1e-3);
+
+    // Without gradient it is a constant velocity
+    let flat = [velocities[0], Velocity::Linear { v0, k: 0.0 }];
+    let depths = time_to_depth(&times, &flat, TimeConvention::default()).unwrap();
+    assert_close(&depths[1], 300.0 + v0 * 0.3, 1e-9);
+}
+
+#[test]
+fn test_velocity_surface_and_undefined_nodes() {
+    let mut times = [constant(1000.0), constant(2000.0)];
+    times[0].values[5] = f32::NAN;
+    let mut velocity = constant(2000.0);
+    velocity.values[7] = f32::NAN;
+    let velocities = [Velocity::Surface(&velocity), Velocity::Interval(3000.0)];
+    let depths = time_to_depth(&times, &velocities, TimeConvention::default()).unwrap();
+    for idx in [5, 7] {
+        assert!(depths[0].values[idx].is_nan());
+        assert!(depths[1].values[idx].is_nan());
+    }
+    assert_eq!(depths[1].values[0], 2500.0);
+    assert_eq!(depths[1].values.iter().filter(|v| v.is_nan()).count(), 2);
+}
+
+#[test]
+fn test_invalid_models() {
+    let times = [constant(1000.0)];
+    let convention = TimeConvention::default();
+    assert!(time_to_depth(&times, &[], convention).is_err());
+    assert!(time_to_depth(&times, &[Velocity::Interval(0.0)], convention).is_err());
+    assert!(time_to_depth(&times, &[Velocity::Linear { v0: -1.0, k: 0.1 }], convention).is_err());
+
+    let mut other = constant(2000.0);
+    other.header.xinc = 50.0;
+    assert!(time_to_depth(&times, &[Velocity::Surface(&other)], convention).is_err());
+    assert!(
+        depth_to_time(
+            &[constant(1.0), other.clone()],
+            &[Velocity::Interval(1.0); 2],
+            convention
+        )
+        .is_err()
+    );
+}
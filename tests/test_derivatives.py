@@ -0,0 +1,34 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def plane_surface(rot=0.0):
+    header = surfio.IrapHeader(ncol=5, nrow=4, xinc=10.0, yinc=10.0, rot=rot)
+    cols, rows = np.meshgrid(np.arange(5.0), np.arange(4.0), indexing="ij")
+    angle = np.radians(rot)
+    xs = 10.0 * (cols * np.cos(angle) - rows * np.sin(angle))
+    return surfio.IrapSurface(header, (0.1 * xs).astype(np.float32))
+
+
+@pytest.mark.parametrize("rot", [0.0, 45.0])
+def test_slope_and_aspect_of_plane(rot):
+    srf = plane_surface(rot)
+
+    assert np.allclose(srf.slope().values, np.degrees(np.arctan(0.1)), atol=1e-3)
+    assert np.allclose(srf.aspect().values, 90.0, atol=1e-3)
+
+
+@pytest.mark.parametrize(
+    "kind", ["mean", "gaussian", "most_positive", "most_negative"]
+)
+def test_curvature_of_plane_is_zero(kind):
+    curvature = plane_surface().curvature(kind)
+    assert curvature.values.shape == (5, 4)
+    assert np.allclose(curvature.values, 0.0, atol=1e-6)
+
+
+def test_unknown_curvature_kind_results_in_value_error():
+    with pytest.raises(ValueError, match="curvature"):
+        plane_surface().curvature("shape_index")
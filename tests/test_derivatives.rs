@@ -0,0 +1,110 @@
+use surfio_rs::derivatives::{self, Curvature};
+use surfio_rs::{Irap, IrapHeader};
+
+fn surface(rot: f64, f: impl Fn(f64, f64) -> f64) -> Irap {
+    let header = IrapHeader {
+        ncol: 21,
+        nrow: 21,
+        xori: 1000.0,
+        yori: 2000.0,
+        xinc: 10.0,
+        yinc: 5.0,
+        rot,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..21 {
+        for row in 0..21 {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = f(x, y) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+fn at_center(irap: &Irap) -> f64 {
+    irap.values[irap.header.index(10, 10)] as f64
+}
+
+#[test]
+fn test_slope_and_aspect_of_plane_on_rotated_grid() {
+    for rot in [0.0, 30.0, 135.0] {
+        // Deepening towards the east
+        let irap = surface(rot, |x, _| 0.1 * (x - 1000.0));
+        let slope = derivatives::slope(&irap);
+        let aspect = derivatives::aspect(&irap);
+
+        for (s, a) in slope.values.iter().zip(&aspect.values) {
+            assert!((*s as f64 - 0.1f64.atan().to_degrees()).abs() < 1e-3);
+            assert!((*a as f64 - 90.0).abs() < 1e-3, "rot {} gives {}", rot, a);
+        }
+    }
+}
+
+#[test]
+fn test_aspect_is_geographic_azimuth() {
+    // Deepening towards the south west
+    let irap = surface(60.0, |x, y| -(x - 1000.0) - (y - 2000.0));
+    let aspect = derivatives::aspect(&irap);
+    assert!((at_center(&aspect) - 225.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_flat_surface_has_undefined_aspect() {
+    let irap = surface(0.0, |_, _| 1500.0);
+    assert!(derivatives::aspect(&irap).values.iter().all(|v| v.is_nan()));
+    assert!(derivatives::slope(&irap).values.iter().all(|&v| v == 0.0));
+}
+
+#[test]
+fn test_curvature_of_paraboloid() {
+    let radius = 500.0;
+    let center = surface(20.0, |_, _| 0.0).header.node_xy(10.0, 10.0);
+    let irap = surface(20.0, |x, y| {
+        ((x - center.0).powi(2) + (y - center.1).powi(2)) / (2.0 * radius)
+    });
+
+    let k = 1.0 / radius;
+    assert!((at_center(&derivatives::curvature(&irap, Curvature::Mean)) - k).abs() < 1e-6);
+    assert!((at_center(&derivatives::curvature(&irap, Curvature::Gaussian)) - k * k).abs() < 1e-8);
+    assert!((at_center(&derivatives::curvature(&irap, Curvature::MostPositive)) - k).abs() < 1e-5);
+    assert!((at_center(&derivatives::curvature(&irap, Curvature::MostNegative)) - k).abs() < 1e-5);
+}
+
+#[test]
+fn test_curvature_of_cylinder() {
+    let radius = 200.0;
+    let irap = surface(0.0, |x, _| (x - 1100.0).powi(2) / (2.0 * radius));
+
+    let k = 1.0 / radius;
+    assert!(at_center(&derivatives::curvature(&irap, Curvature::Gaussian)).abs() < 1e-9);
+    assert!((at_center(&derivatives::curvature(&irap, Curvature::MostPositive)) - k).abs() < 1e-5);
+    assert!(at_center(&derivatives::curvature(&irap, Curvature::MostNegative)).abs() < 1e-5);
+}
+
+#[test]
+fn test_undefined_neighbours_use_one_sided_differences() {
+    let mut irap = surface(0.0, |x, y| 0.1 * (x - 1000.0) + 0.2 * (y - 2000.0));
+    let idx = irap.header.index(10, 10);
+    irap.values[idx] = f32::NAN;
+    let slope = derivatives::slope(&irap);
+    let expected = 0.1f64.hypot(0.2).atan().to_degrees();
+
+    assert!(slope.values[idx].is_nan());
+    for (col, row) in [(9, 10), (11, 10), (10, 9), (10, 11)] {
+        let v = slope.values[irap.header.index(col, row)] as f64;
+        assert!((v - expected).abs() < 1e-3);
+    }
+    let curvature = derivatives::curvature(&irap, Curvature::Mean);
+    assert!(curvature.values[irap.header.index(9, 10)].abs() < 1e-4);
+}
+
+#[test]
+fn test_derivatives_keep_header() {
+    let irap = surface(10.0, |x, _| x);
+    assert_eq!(derivatives::slope(&irap).header, irap.header);
+    assert_eq!(
+        derivatives::curvature(&irap, Curvature::Gaussian).header,
+        irap.header
+    );
+}
@@ -0,0 +1,35 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def two_peaks():
+    cols, rows = np.meshgrid(np.arange(41.0), np.arange(21.0), indexing="ij")
+    header = surfio.IrapHeader(ncol=41, nrow=21, xinc=10.0, yinc=10.0)
+    values = np.maximum(
+        100.0 - 5.0 * np.hypot(cols - 10, rows - 10),
+        60.0 - 5.0 * np.hypot(cols - 30, rows - 10),
+    )
+    return surfio.IrapSurface(header, np.maximum(values, 0.0).astype(np.float32))
+
+
+def test_maxima():
+    maxima = two_peaks().extrema()
+    assert len(maxima) == 2
+    assert list(maxima.col) == [10, 30]
+    assert list(maxima.prominence) == pytest.approx([100.0, 30.0])
+    assert maxima.x[0] == pytest.approx(100.0)
+    assert len(two_peaks().extrema(min_prominence=50.0)) == 1
+
+
+def test_saddles():
+    saddles = two_peaks().extrema("saddle")
+    assert len(saddles) == 1
+    assert (saddles.col[0], saddles.row[0]) == (24, 10)
+    assert np.isnan(saddles.prominence[0])
+
+
+def test_unknown_kind():
+    with pytest.raises(ValueError, match="Unknown extremum kind"):
+        two_peaks().extrema("ridge")
@@ -0,0 +1,83 @@
+use surfio_rs::extrema::ExtremumKind;
+use surfio_rs::{Irap, IrapHeader};
+
+/// Cones of height 100 at node (10, 10) and 60 at (30, 10) on a flat base,
+/// meeting in a col of height 30 at (24, 10).
+fn two_peaks() -> Irap {
+    let header = IrapHeader {
+        ncol: 41,
+        nrow: 21,
+        xori: 1000.0,
+        yori: 2000.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..41 {
+        for row in 0..21 {
+            let d = |c: f64| (col as f64 - c).hypot(row as f64 - 10.0);
+            let v = (100.0 - 5.0 * d(10.0)).max(60.0 - 5.0 * d(30.0)).max(0.0);
+            values[header.index(col, row)] = v as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_maxima_with_prominence() {
+    let maxima = two_peaks().extrema(ExtremumKind::Maximum, 0.0, 1);
+    assert_eq!(maxima.len(), 2);
+    assert_eq!((maxima[0].col, maxima[0].row), (10, 10));
+    assert_eq!((maxima[0].x, maxima[0].y), (1100.0, 2100.0));
+    assert_eq!(maxima[0].value, 100.0);
+    assert_eq!(maxima[0].prominence, 100.0);
+    assert_eq!((maxima[1].col, maxima[1].row), (30, 10));
+    assert_eq!(maxima[1].prominence, 30.0);
+
+    let prominent = two_peaks().extrema(ExtremumKind::Maximum, 50.0, 1);
+    assert_eq!(prominent.len(), 1);
+}
+
+#[test]
+fn test_minima_are_maxima_of_negated_surface() {
+    let mut irap = two_peaks();
+    for v in irap.values.iter_mut() {
+        *v = 500.0 - *v;
+    }
+    let minima = irap.extrema(ExtremumKind::Minimum, 10.0, 1);
+    assert_eq!(minima.len(), 2);
+    assert_eq!(minima[0].value, 400.0);
+    assert_eq!(minima[1].prominence, 30.0);
+}
+
+#[test]
+fn test_window_suppresses_nearby_maxima() {
+    let mut irap = two_peaks();
+    // A small bump next to the main peak
+    let idx = irap.header.index(13, 10);
+    irap.values[idx] += 10.0;
+    assert_eq!(irap.extrema(ExtremumKind::Maximum, 0.0, 1).len(), 3);
+    assert_eq!(irap.extrema(ExtremumKind::Maximum, 0.0, 3).len(), 2);
+}
+
+#[test]
+fn test_saddle_between_peaks() {
+    let saddles = two_peaks().extrema(ExtremumKind::Saddle, 0.0, 1);
+    assert_eq!(saddles.len(), 1);
+    assert_eq!((saddles[0].col, saddles[0].row), (24, 10));
+    assert_eq!(saddles[0].value, 30.0);
+    assert!(saddles[0].prominence.is_nan());
+}
+
+#[test]
+fn test_undefined_nodes_are_ignored() {
+    let mut irap = two_peaks();
+    let idx = irap.header.index(10, 10);
+    irap.values[idx] = f32::NAN;
+    let maxima = irap.extrema(ExtremumKind::Maximum, 1.0, 1);
+    // The four nodes around the hole tie, and the first of them wins
+    assert_eq!(maxima.len(), 2);
+    assert_eq!((maxima[0].col, maxima[0].row), (9, 10));
+    assert_eq!(maxima[0].value, 95.0);
+}
@@ -0,0 +1,47 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def plane_with_hole():
+    cols, rows = np.meshgrid(np.arange(10.0), np.arange(8.0), indexing="ij")
+    values = (100.0 + 2.0 * cols + 3.0 * rows).astype(np.float32)
+    expected = values.copy()
+    values[3:6, 2:5] = np.nan
+    values[0, 0] = np.nan
+    srf = surfio.IrapSurface(
+        surfio.IrapHeader(ncol=10, nrow=8, xinc=10.0, yinc=10.0), values
+    )
+    return srf, expected
+
+
+@pytest.mark.parametrize("method", ["nearest", "inverse_distance", "laplace"])
+def test_fill_undefined_returns_mask_of_filled_nodes(method):
+    srf, _ = plane_with_hole()
+    filled, mask = srf.fill_undefined(method)
+
+    assert mask.dtype == bool
+    assert mask.shape == (10, 8)
+    assert mask.sum() == 9
+    assert np.isnan(filled.values[0, 0])
+    assert not np.isnan(filled.values[3:6, 2:5]).any()
+
+
+def test_laplace_fill_reproduces_plane():
+    srf, expected = plane_with_hole()
+    filled, mask = srf.fill_undefined("laplace")
+    assert np.allclose(filled.values[mask], expected[mask], atol=1e-3)
+
+
+def test_fill_undefined_can_extrapolate():
+    srf, _ = plane_with_hole()
+    filled, mask = srf.fill_undefined("nearest", extrapolate=True)
+    assert mask.sum() == 10
+    assert not np.isnan(filled.values).any()
+
+
+def test_fill_undefined_max_distance():
+    srf, _ = plane_with_hole()
+    _, mask = srf.fill_undefined("nearest", max_distance=5.0)
+    assert mask.sum() == 0
@@ -0,0 +1,122 @@
+use surfio_rs::fill::{FillExtent, FillMethod};
+use surfio_rs::{Irap, IrapHeader};
+
+fn plane() -> Irap {
+    let header = IrapHeader {
+        ncol: 12,
+        nrow: 10,
+        xinc: 10.0,
+        yinc: 20.0,
+        rot: 25.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..12 {
+        for row in 0..10 {
+            values[header.index(col, row)] = (100 + 2 * col + 3 * row) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+fn punch(irap: &mut Irap, cols: std::ops::Range<usize>, rows: std::ops::Range<usize>) {
+    for col in cols {
+        for row in rows.clone() {
+            let idx = irap.header.index(col, row);
+            irap.values[idx] = f32::NAN;
+        }
+    }
+}
+
+#[test]
+fn test_laplace_fills_enclosed_hole_on_plane_exactly() {
+    let original = plane();
+    let mut irap = original.clone();
+    punch(&mut irap, 3..8, 2..6);
+    let (filled, mask) =
+        irap.fill_undefined(&FillMethod::Laplace, f64::INFINITY, FillExtent::Enclosed);
+
+    assert_eq!(mask.iter().filter(|&&m| m).count(), 20);
+    for (a, b) in filled.values.iter().zip(&original.values) {
+        assert!((a - b).abs() < 1e-3, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn test_enclosed_leaves_regions_touching_edge() {
+    let mut irap = plane();
+    punch(&mut irap, 0..3, 0..3);
+    punch(&mut irap, 6..8, 4..6);
+    let (filled, mask) =
+        irap.fill_undefined(&FillMethod::Nearest, f64::INFINITY, FillExtent::Enclosed);
+
+    assert_eq!(mask.iter().filter(|&&m| m).count(), 4);
+    assert!(filled.values[irap.header.index(0, 0)].is_nan());
+    assert!(!filled.values[irap.header.index(6, 4)].is_nan());
+}
+
+#[test]
+fn test_extrapolate_fills_to_edge() {
+    let mut irap = plane();
+    punch(&mut irap, 0..3, 0..3);
+    for method in [
+        FillMethod::Nearest,
+        FillMethod::InverseDistance {
+            power: 2.0,
+            max_neighbours: 8,
+        },
+        FillMethod::Laplace,
+    ] {
+        let (filled, mask) = irap.fill_undefined(&method, f64::INFINITY, FillExtent::Extrapolate);
+        assert_eq!(mask.iter().filter(|&&m| m).count(), 9);
+        assert!(filled.values.iter().all(|v| !v.is_nan()));
+    }
+}
+
+#[test]
+fn test_max_distance_limits_filling() {
+    let mut irap = plane();
+    punch(&mut irap, 0..6, 0..10);
+    let (filled, mask) = irap.fill_undefined(&FillMethod::Nearest, 25.0, FillExtent::Extrapolate);
+
+    // Columns 4 and 5 are within 25 of the defined column 6
+    assert_eq!(mask.iter().filter(|&&m| m).count(), 20);
+    assert_eq!(
+        filled.values[irap.header.index(5, 3)],
+        irap.values[irap.header.index(6, 3)]
+    );
+    assert!(filled.values[irap.header.index(3, 3)].is_nan());
+}
+
+#[test]
+fn test_inverse_distance_fills_between_neighbours() {
+    let header = IrapHeader {
+        ncol: 3,
+        nrow: 3,
+        xinc: 1.0,
+        yinc: 1.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; 9];
+    values[header.index(1, 1)] = f32::NAN;
+    values[header.index(1, 0)] = 4.0;
+    let irap = Irap { header, values };
+    let method = FillMethod::InverseDistance {
+        power: 2.0,
+        max_neighbours: 4,
+    };
+    let (filled, _) = irap.fill_undefined(&method, f64::INFINITY, FillExtent::Enclosed);
+
+    assert_eq!(filled.values[irap.header.index(1, 1)], 1.0);
+}
+
+#[test]
+fn test_fully_undefined_surface_is_unchanged() {
+    let mut irap = plane();
+    punch(&mut irap, 0..12, 0..10);
+    let (filled, mask) =
+        irap.fill_undefined(&FillMethod::Laplace, f64::INFINITY, FillExtent::Extrapolate);
+
+    assert!(filled.values.iter().all(|v| v.is_nan()));
+    assert!(mask.iter().all(|&m| !m));
+}
@@ -0,0 +1,53 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def constant_surface(value=5.0):
+    return surfio.IrapSurface(
+        surfio.IrapHeader(ncol=10, nrow=8, xinc=25.0, yinc=25.0),
+        np.full((10, 8), value, dtype=np.float32),
+    )
+
+
+@pytest.mark.parametrize(
+    "name", ["mean_filter", "median_filter", "gaussian_filter", "despike"]
+)
+def test_filters_keep_undefined_nodes_and_constants(name):
+    srf = constant_surface()
+    srf.values[4, 4] = np.nan
+    filtered = getattr(srf, name)(2.0)
+
+    assert np.isnan(filtered.values[4, 4])
+    assert np.isnan(filtered.values).sum() == 1
+    assert np.allclose(filtered.values[~np.isnan(filtered.values)], 5.0)
+
+
+def test_filter_radius_in_world_units():
+    srf = constant_surface()
+    srf.values[::2, ::3] = 0.0
+    assert np.array_equal(
+        srf.mean_filter(50.0, units="world").values, srf.mean_filter(2.0).values
+    )
+
+
+def test_median_filter_removes_spike():
+    srf = constant_surface()
+    srf.values[3, 3] = 1000.0
+    assert srf.median_filter(1.0).values[3, 3] == 5.0
+    assert srf.despike(1.0, threshold=3.0).values[3, 3] == 5.0
+
+
+def test_unknown_units_results_in_value_error():
+    with pytest.raises(ValueError, match="units"):
+        constant_surface().mean_filter(1.0, units="feet")
+
+
+def test_invalid_filter_size_raises():
+    with pytest.raises(ValueError, match="Invalid filter size"):
+        constant_surface().mean_filter(np.nan)
+    with pytest.raises(ValueError, match="Invalid filter size"):
+        constant_surface().median_filter(-1.0)
+    with pytest.raises(ValueError, match="positive"):
+        constant_surface().gaussian_filter(0.0)
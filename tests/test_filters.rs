@@ -0,0 +1,135 @@
+use surfio_rs::filters::Radius;
+use surfio_rs::{Irap, IrapHeader};
+
+fn surface(f: impl Fn(usize, usize) -> f32) -> Irap {
+    let header = IrapHeader {
+        ncol: 15,
+        nrow: 12,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..15 {
+        for row in 0..12 {
+            values[header.index(col, row)] = f(col, row);
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_mean_filter_renormalizes_around_undefined_nodes() {
+    let mut irap = surface(|_, _| 5.0);
+    let hole = irap.header.index(7, 6);
+    irap.values[hole] = f32::NAN;
+    let filtered = irap.mean_filter(Radius::Nodes(2.0)).unwrap();
+
+    assert!(filtered.values[hole].is_nan());
+    for (k, v) in filtered.values.iter().enumerate() {
+        if k != hole {
+            assert!((v - 5.0).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_mean_filter_preserves_plane_in_interior() {
+    let irap = surface(|c, r| (c * 2 + r) as f32);
+    let filtered = irap.mean_filter(Radius::Nodes(1.0)).unwrap();
+
+    for col in 1..14 {
+        for row in 1..11 {
+            let idx = irap.header.index(col, row);
+            assert!((filtered.values[idx] - irap.values[idx]).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_world_radius_is_converted_to_nodes() {
+    let irap = surface(|c, r| ((c * 7 + r * 3) % 5) as f32);
+    assert_eq!(
+        irap.mean_filter(Radius::World(20.0)).unwrap(),
+        irap.mean_filter(Radius::Nodes(2.0)).unwrap()
+    );
+}
+
+#[test]
+fn test_gaussian_filter_smooths_noise_and_keeps_constants() {
+    let constant = surface(|_, _| 3.0);
+    let filtered = constant.gaussian_filter(Radius::Nodes(1.5)).unwrap();
+    assert!(filtered.values.iter().all(|v| (v - 3.0).abs() < 1e-6));
+
+    let noisy = surface(|c, r| if (c + r) % 2 == 0 { 1.0 } else { -1.0 });
+    let filtered = noisy.gaussian_filter(Radius::World(15.0)).unwrap();
+    let idx = noisy.header.index(7, 6);
+    assert!(filtered.values[idx].abs() < 0.1);
+}
+
+#[test]
+fn test_median_filter_removes_spike() {
+    let mut irap = surface(|_, _| 1.0);
+    let spike = irap.header.index(4, 4);
+    irap.values[spike] = 1000.0;
+    let filtered = irap.median_filter(Radius::Nodes(1.0)).unwrap();
+
+    assert_eq!(filtered.values[spike], 1.0);
+}
+
+#[test]
+fn test_median_filter_ignores_undefined_nodes() {
+    let mut irap = surface(|c, _| c as f32);
+    let hole = irap.header.index(4, 4);
+    irap.values[hole] = f32::NAN;
+    let filtered = irap.median_filter(Radius::Nodes(1.0)).unwrap();
+
+    assert!(filtered.values[hole].is_nan());
+    assert_eq!(filtered.values[irap.header.index(4, 5)], 4.0);
+    assert_eq!(filtered.values.iter().filter(|v| v.is_nan()).count(), 1);
+}
+
+#[test]
+fn test_despike_only_changes_outliers() {
+    let mut irap = surface(|c, r| (c + r) as f32 + if (c * r) % 3 == 0 { 0.1 } else { 0.0 });
+    let original = irap.clone();
+    let spike = irap.header.index(8, 5);
+    irap.values[spike] = 500.0;
+    let despiked = irap.despike(Radius::Nodes(2.0), 3.0).unwrap();
+
+    assert!((despiked.values[spike] - 13.0).abs() < 1.0);
+    for (k, (a, b)) in despiked.values.iter().zip(&original.values).enumerate() {
+        if k != spike {
+            assert_eq!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_invalid_sizes_and_increments_error() {
+    let irap = surface(|c, r| (c + r) as f32);
+    assert!(irap.mean_filter(Radius::Nodes(f64::NAN)).is_err());
+    assert!(irap.gaussian_filter(Radius::World(f64::INFINITY)).is_err());
+    assert!(irap.mean_filter(Radius::Nodes(-1.0)).is_err());
+    assert!(irap.median_filter(Radius::World(-20.0)).is_err());
+    assert!(irap.gaussian_filter(Radius::Nodes(0.0)).is_err());
+    assert!(irap.gaussian_filter(Radius::World(0.0)).is_err());
+    // A zero radius is a valid, if trivial, window
+    assert_eq!(irap.mean_filter(Radius::Nodes(0.0)).unwrap(), irap);
+
+    let mut flat = irap.clone();
+    flat.header.xinc = 0.0;
+    assert!(flat.median_filter(Radius::World(20.0)).is_err());
+    assert!(flat.despike(Radius::World(20.0), 3.0).is_err());
+    // Sizes in nodes do not use the increments
+    assert!(flat.mean_filter(Radius::Nodes(1.0)).is_ok());
+}
+
+#[test]
+fn test_windows_larger_than_the_lattice() {
+    let irap = surface(|c, r| (c + r) as f32);
+    let mean = irap.values.iter().sum::<f32>() / irap.values.len() as f32;
+    let filtered = irap.mean_filter(Radius::World(1e300)).unwrap();
+    assert!(filtered.values.iter().all(|v| (v - mean).abs() < 1e-4));
+    assert!(irap.gaussian_filter(Radius::Nodes(1e300)).is_ok());
+}
@@ -0,0 +1,44 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def cone():
+    cols, rows = np.meshgrid(np.arange(21.0), np.arange(21.0), indexing="ij")
+    header = surfio.IrapHeader(ncol=21, nrow=21, xinc=25.0, yinc=25.0)
+    values = 1000.0 + 25.0 * np.hypot(cols - 10, rows - 10)
+    return surfio.IrapSurface(header, values.astype(np.float32))
+
+
+@pytest.mark.parametrize("method", ["d8", "dinf"])
+def test_flow_direction_and_accumulation(method):
+    srf = cone()
+    direction = srf.flow_direction(method)
+    assert np.isnan(direction.values[10, 10])
+    # West of the crest, flow goes east
+    assert direction.values[5, 10] == pytest.approx(90.0)
+
+    accumulation = srf.flow_accumulation(method)
+    assert accumulation.values[10, 10] > accumulation.values[5, 10]
+
+
+@pytest.mark.parametrize("method", ["d8", "dinf"])
+def test_flow_paths(method):
+    srf = cone()
+    paths = srf.flow_paths(np.array([[30.0, 420.0], [-100.0, -100.0]]), method)
+    assert paths[0].shape[1] == 2
+    assert np.allclose(paths[0][-1], [250.0, 250.0])
+    assert paths[1].shape == (0, 2)
+
+
+def test_drainage():
+    closures, labels, areas = cone().drainage()
+    assert len(closures) == 1
+    assert areas[0] > 0
+    assert labels.values[10, 10] == 0
+
+
+def test_unknown_flow_method():
+    with pytest.raises(ValueError):
+        cone().flow_direction("mfd")
@@ -0,0 +1,125 @@
+use surfio_rs::closures::UndefinedNodes;
+use surfio_rs::flow::FlowMethod;
+use surfio_rs::{Irap, IrapHeader};
+
+fn from_world(header: IrapHeader, f: impl Fn(f64, f64) -> f64) -> Irap {
+    let mut values = vec![0.0; header.len()];
+    for col in 0..header.ncol as usize {
+        for row in 0..header.nrow as usize {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = f(x, y) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+fn header(ncol: u32, nrow: u32, rot: f64) -> IrapHeader {
+    IrapHeader {
+        ncol,
+        nrow,
+        xinc: 25.0,
+        yinc: 25.0,
+        rot,
+        ..Default::default()
+    }
+}
+
+/// Cone with its crest at node (10, 10).
+fn cone(rot: f64) -> Irap {
+    let h = header(21, 21, rot);
+    let (cx, cy) = h.node_xy(10.0, 10.0);
+    from_world(h, |x, y| 1000.0 + (x - cx).hypot(y - cy))
+}
+
+#[test]
+fn test_flow_direction_on_rotated_plane() {
+    // Depth increases to the east, so flow goes west
+    let irap = from_world(header(15, 12, 30.0), |x, _| 1000.0 + 0.1 * x);
+    let dinf = irap.flow_direction(FlowMethod::DInfinity);
+    for col in 1..14 {
+        for row in 1..11 {
+            let v = dinf.values[dinf.header.index(col, row)];
+            assert!((v - 270.0).abs() < 1e-2, "{}", v);
+        }
+    }
+    // The steepest of the eight neighbours is the diagonal 15 degrees off
+    let d8 = irap.flow_direction(FlowMethod::D8);
+    let interior = d8.header.index(7, 6);
+    assert!((d8.values[interior] - 285.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_flow_accumulation_on_plane() {
+    let irap = from_world(header(8, 5, 0.0), |x, _| 1000.0 + x);
+    for method in [FlowMethod::D8, FlowMethod::DInfinity] {
+        let acc = irap.flow_accumulation(method);
+        for row in 0..5 {
+            assert_eq!(acc.values[acc.header.index(0, row)], 8.0 * 625.0);
+            assert_eq!(acc.values[acc.header.index(7, row)], 625.0);
+        }
+    }
+}
+
+#[test]
+fn test_flow_accumulation_conserves_area() {
+    let mut irap = cone(15.0);
+    irap.values[irap.header.index(4, 13)] = f32::NAN;
+    let total = 440.0 * 625.0;
+    for method in [FlowMethod::D8, FlowMethod::DInfinity] {
+        let acc = irap.flow_accumulation(method);
+        let direction = irap.flow_direction(method);
+        // Flow ends at nodes without a direction: the crest and edge highs
+        let ends: f64 = acc
+            .values
+            .iter()
+            .zip(&direction.values)
+            .filter(|(a, d)| !a.is_nan() && d.is_nan())
+            .map(|(&a, _)| a as f64)
+            .sum();
+        assert!((ends - total).abs() < 1e-3 * total, "{} != {}", ends, total);
+        assert!(acc.values[irap.header.index(4, 13)].is_nan());
+    }
+}
+
+#[test]
+fn test_flow_paths_end_at_crest() {
+    let irap = cone(20.0);
+    let crest = irap.header.node_xy(10.0, 10.0);
+    let start = irap.header.node_xy(2.3, 17.6);
+    for method in [FlowMethod::D8, FlowMethod::DInfinity] {
+        let paths = irap.flow_paths(&[[start.0, start.1], [-1e6, 0.0]], method);
+        let path = &paths[0];
+        assert_eq!(path[0], [start.0, start.1]);
+        let [x, y] = *path.last().unwrap();
+        assert!((x - crest.0).abs() < 1e-6 && (y - crest.1).abs() < 1e-6);
+        let depths: Vec<f64> = path[1..].iter().map(|p| irap.sample(p[0], p[1])).collect();
+        assert!(depths.windows(2).all(|w| w[1] < w[0]));
+        assert!(paths[1].is_empty());
+    }
+}
+
+#[test]
+fn test_drainage_of_two_domes() {
+    let h = header(41, 31, 0.0);
+    let (a, b) = (h.node_xy(12.0, 15.0), h.node_xy(28.0, 15.0));
+    let irap = from_world(h, |x, y| {
+        1000.0 + 0.2 * (x - a.0).hypot(y - a.1).min((x - b.0).hypot(y - b.1))
+    });
+    let drainage = irap.drainage(UndefinedNodes::Boundary);
+    assert_eq!(drainage.closures.len(), 3);
+
+    let total = 41.0 * 31.0 * 625.0;
+    let leaves: Vec<usize> = (0..3)
+        .filter(|&k| drainage.closures[k].parent.is_some())
+        .collect();
+    let parent = drainage.closures[leaves[0]].parent.unwrap();
+    assert_eq!(drainage.areas[leaves[0]] + drainage.areas[leaves[1]], total);
+    assert_eq!(drainage.areas[parent], total);
+    for &k in &leaves {
+        assert!((drainage.areas[k] / (total / 2.0) - 1.0).abs() < 0.05);
+    }
+
+    let label = |col, row| drainage.labels.values[drainage.labels.header.index(col, row)];
+    assert_eq!(drainage.closures[label(0, 0) as usize].crest, (12, 15));
+    assert_eq!(drainage.closures[label(40, 30) as usize].crest, (28, 15));
+}
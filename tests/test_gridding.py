@@ -0,0 +1,80 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def plane_points(n=200, seed=0):
+    rng = np.random.default_rng(seed)
+    xs = rng.uniform(-1.0, 10.0, n)
+    ys = rng.uniform(-1.0, 10.0, n)
+    zs = 100.0 + 2.0 * xs - ys
+    return xs, ys, zs
+
+
+@pytest.mark.parametrize(
+    "method", ["nearest", "inverse_distance", "linear", "minimum_curvature"]
+)
+def test_from_points_returns_surface_on_header(method):
+    header = surfio.IrapHeader(ncol=10, nrow=10, xinc=1.0, yinc=1.0)
+    srf = surfio.IrapSurface.from_points(*plane_points(), header, method=method)
+
+    assert srf.values.shape == (10, 10)
+    assert srf.header.ncol == 10
+    assert srf.header.xmax == 9.0
+
+
+def test_linear_gridding_reproduces_plane():
+    header = surfio.IrapHeader(ncol=10, nrow=10, xinc=1.0, yinc=1.0)
+    srf = surfio.IrapSurface.from_points(*plane_points(), header, method="linear")
+
+    xs, ys = np.meshgrid(np.arange(10.0), np.arange(10.0), indexing="ij")
+    defined = ~np.isnan(srf.values)
+    assert np.allclose(srf.values[defined], (100.0 + 2.0 * xs - ys)[defined], atol=1e-3)
+
+
+def test_inverse_distance_radius_leaves_undefined_nodes():
+    header = surfio.IrapHeader(ncol=10, nrow=10, xinc=1.0, yinc=1.0)
+    srf = surfio.IrapSurface.from_points(
+        np.array([0.0]),
+        np.array([0.0]),
+        np.array([1.0]),
+        header,
+        method="inverse_distance",
+        radius=1.5,
+    )
+    assert srf.values[0, 0] == 1.0
+    assert np.isnan(srf.values[9, 9])
+
+
+def test_unknown_gridding_method_results_in_value_error():
+    header = surfio.IrapHeader(ncol=2, nrow=2)
+    with pytest.raises(ValueError, match="method"):
+        surfio.IrapSurface.from_points(*plane_points(), header, method="spline")
+
+
+def test_tie_to_points():
+    header = surfio.IrapHeader(ncol=41, nrow=41, xori=1000.0, yori=2000.0, xinc=10.0, yinc=10.0)
+    surface = surfio.IrapSurface(header, np.full((41, 41), 1500.0, dtype=np.float32))
+    xs, ys, zs = np.array([1100.0, 1300.0]), np.array([2100.0, 2300.0]), np.array([1510.0, 1495.0])
+    tied, residuals, misfits = surface.tie_to_points(xs, ys, zs, 150.0, method="inverse_distance")
+    assert residuals == pytest.approx([10.0, -5.0])
+    assert np.abs(misfits).max() < 1e-4
+    assert tied.values[10, 10] == pytest.approx(1510.0)
+    assert tied.values[40, 0] == 1500.0
+
+
+def test_krige():
+    header = surfio.IrapHeader(ncol=21, nrow=11, xori=1000.0, yori=2000.0, xinc=10.0, yinc=20.0)
+    xs, ys, zs = np.array([1050.0, 1120.0, 1150.0]), np.array([2040.0, 2100.0, 2180.0]), np.array([3.0, 7.0, 5.0])
+    model = surfio.VariogramModel("spherical", sill=4.0, range=100.0)
+    surface, std = surfio.IrapSurface.krige(xs, ys, zs, header, model, return_std=True)
+    assert surface.values[5, 2] == pytest.approx(3.0, abs=1e-4)
+    assert std.values[5, 2] == pytest.approx(0.0, abs=1e-3)
+
+    surface, std = surfio.IrapSurface.krige(xs, ys, zs, header, model, kind="simple", mean=5.0, radius=30.0)
+    assert std is None
+    assert surface.values[20, 0] == pytest.approx(5.0)
+
+    with pytest.raises(ValueError, match="Unknown kriging type"):
+        surfio.IrapSurface.krige(xs, ys, zs, header, model, kind="universal")
@@ -0,0 +1,436 @@
+use surfio_rs::gridding::{self, Anisotropy, GriddingMethod, KrigingOptions, KrigingType};
+use surfio_rs::variogram::{ModelKind, VariogramModel};
+use surfio_rs::{Irap, IrapHeader};
+
+fn header(ncol: u32, nrow: u32, rot: f64) -> IrapHeader {
+    IrapHeader {
+        ncol,
+        nrow,
+        xori: 1000.0,
+        yori: 2000.0,
+        xmax: 1000.0 + (ncol - 1) as f64 * 10.0,
+        ymax: 2000.0 + (nrow - 1) as f64 * 20.0,
+        xinc: 10.0,
+        yinc: 20.0,
+        rot,
+        xrot: 0.0,
+        yrot: 0.0,
+    }
+}
+
+fn plane(x: f64, y: f64) -> f64 {
+    1500.0 + 0.1 * (x - 1000.0) - 0.05 * (y - 2000.0)
+}
+
+/// Deterministic scattered points covering the lattice.
+fn scattered(header: &IrapHeader, n: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let mut state: u64 = 12345;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let (mut xs, mut ys, mut zs) = (vec![], vec![], vec![]);
+    for _ in 0..n {
+        let col = -1.0 + next() * (header.ncol + 1) as f64;
+        let row = -1.0 + next() * (header.nrow + 1) as f64;
+        let (x, y) = header.node_xy(col, row);
+        xs.push(x);
+        ys.push(y);
+        zs.push(plane(x, y));
+    }
+    (xs, ys, zs)
+}
+
+#[test]
+fn test_nearest_reproduces_values_on_nodes() {
+    let h = header(4, 3, 30.0);
+    let (mut xs, mut ys, mut zs) = (vec![], vec![], vec![]);
+    for col in 0..4 {
+        for row in 0..3 {
+            let (x, y) = h.node_xy(col as f64, row as f64);
+            xs.push(x + 0.1);
+            ys.push(y - 0.1);
+            zs.push((col * 10 + row) as f64);
+        }
+    }
+    let irap = gridding::from_points(&xs, &ys, &zs, &h, &GriddingMethod::Nearest).unwrap();
+
+    for col in 0..4 {
+        for row in 0..3 {
+            assert_eq!(irap.values[h.index(col, row)], (col * 10 + row) as f32);
+        }
+    }
+}
+
+#[test]
+fn test_inverse_distance_is_undefined_outside_radius() {
+    let h = header(10, 10, 0.0);
+    let method = GriddingMethod::InverseDistance {
+        power: 2.0,
+        radius: 15.0,
+        max_neighbours: 8,
+    };
+    let irap = gridding::from_points(&[1000.0], &[2000.0], &[5.0], &h, &method).unwrap();
+
+    assert_eq!(irap.values[h.index(0, 0)], 5.0);
+    assert_eq!(irap.values[h.index(1, 0)], 5.0);
+    assert!(irap.values[h.index(0, 1)].is_nan());
+    assert!(irap.values[h.index(9, 9)].is_nan());
+}
+
+#[test]
+fn test_inverse_distance_weights_closer_points_higher() {
+    let h = header(3, 1, 0.0);
+    let method = GriddingMethod::InverseDistance {
+        power: 2.0,
+        radius: f64::INFINITY,
+        max_neighbours: 2,
+    };
+    let irap = gridding::from_points(
+        &[1000.0, 1020.0],
+        &[2000.0, 2000.0],
+        &[0.0, 10.0],
+        &h,
+        &method,
+    )
+    .unwrap();
+
+    assert_eq!(irap.values, vec![0.0, 5.0, 10.0]);
+}
+
+#[test]
+fn test_linear_reproduces_plane_inside_hull() {
+    let h = header(20, 15, 20.0);
+    let (xs, ys, zs) = scattered(&h, 400);
+    let irap = gridding::from_points(&xs, &ys, &zs, &h, &GriddingMethod::Linear).unwrap();
+
+    let mut defined = 0;
+    for col in 0..20 {
+        for row in 0..15 {
+            let v = irap.values[h.index(col, row)];
+            if !v.is_nan() {
+                let (x, y) = h.node_xy(col as f64, row as f64);
+                assert!((v as f64 - plane(x, y)).abs() < 1e-2);
+                defined += 1;
+            }
+        }
+    }
+    assert!(defined > 250);
+}
+
+#[test]
+fn test_linear_is_undefined_outside_hull() {
+    let h = header(5, 5, 0.0);
+    let irap = gridding::from_points(
+        &[1000.0, 1020.0, 1000.0],
+        &[2000.0, 2000.0, 2040.0],
+        &[1.0, 1.0, 1.0],
+        &h,
+        &GriddingMethod::Linear,
+    )
+    .unwrap();
+
+    assert_eq!(irap.values[h.index(0, 0)], 1.0);
+    assert_eq!(irap.values[h.index(1, 1)], 1.0);
+    assert!(irap.values[h.index(4, 4)].is_nan());
+}
+
+#[test]
+fn test_minimum_curvature_honours_data_and_reproduces_plane() {
+    let h = header(40, 30, 45.0);
+    let (xs, ys, zs) = scattered(&h, 150);
+    let method = GriddingMethod::MinimumCurvature {
+        tension: 0.25,
+        max_iterations: 5000,
+        tolerance: 1e-7,
+    };
+    let irap = gridding::from_points(&xs, &ys, &zs, &h, &method).unwrap();
+
+    assert!(irap.values.iter().all(|v| v.is_finite()));
+    for col in 0..40 {
+        for row in 0..30 {
+            let (x, y) = h.node_xy(col as f64, row as f64);
+            let v = irap.values[h.index(col, row)] as f64;
+            assert!((v - plane(x, y)).abs() < 1e-3, "{} vs {}", v, plane(x, y));
+        }
+    }
+    let mut inside = 0;
+    for ((&x, &y), &z) in xs.iter().zip(&ys).zip(&zs) {
+        let v = irap.sample(x, y);
+        if !v.is_nan() {
+            inside += 1;
+            assert!((v - z).abs() < 1e-3, "{} vs {}", v, z);
+        }
+    }
+    assert!(inside > 100);
+}
+
+#[test]
+fn test_minimum_curvature_rejects_invalid_tension() {
+    let h = header(5, 5, 0.0);
+    let method = GriddingMethod::MinimumCurvature {
+        tension: 1.0,
+        max_iterations: 10,
+        tolerance: 1e-4,
+    };
+    assert!(gridding::from_points(&[1000.0], &[2000.0], &[1.0], &h, &method).is_err());
+}
+
+#[test]
+fn test_inverse_distance_rejects_invalid_power_and_radius() {
+    let h = header(5, 5, 0.0);
+    let method = |power, radius| GriddingMethod::InverseDistance {
+        power,
+        radius,
+        max_neighbours: 4,
+    };
+    let grid = |m: &GriddingMethod| gridding::from_points(&[1000.0], &[2000.0], &[1.0], &h, m);
+    assert!(grid(&method(-1.0, 50.0)).is_err());
+    assert!(grid(&method(f64::NAN, 50.0)).is_err());
+    assert!(grid(&method(2.0, -50.0)).is_err());
+    assert!(grid(&method(2.0, 0.0)).is_err());
+    assert!(grid(&method(2.0, f64::NAN)).is_err());
+    assert!(grid(&method(0.0, f64::INFINITY)).is_ok());
+}
+
+#[test]
+fn test_mismatched_lengths_errors() {
+    let h = header(5, 5, 0.0);
+    let result = gridding::from_points(&[1.0, 2.0], &[1.0], &[1.0], &h, &GriddingMethod::Nearest);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_nearest_on_many_points() {
+    let h = header(50, 50, 10.0);
+    let (xs, ys, zs) = scattered(&h, 5000);
+    let irap = gridding::from_points(&xs, &ys, &zs, &h, &GriddingMethod::Nearest).unwrap();
+
+    // Compare a few nodes against a brute force search
+    for &(col, row) in &[(0, 0), (13, 27), (49, 49), (25, 3)] {
+        let (x, y) = h.node_xy(col as f64, row as f64);
+        let best = (0..xs.len())
+            .min_by(|&a, &b| {
+                let da = (xs[a] - x).powi(2) + (ys[a] - y).powi(2);
+                let db = (xs[b] - x).powi(2) + (ys[b] - y).powi(2);
+                da.total_cmp(&db)
+            })
+            .unwrap();
+        assert_eq!(irap.values[h.index(col, row)], zs[best] as f32);
+    }
+}
+
+fn flat(h: &IrapHeader, value: f32) -> Irap {
+    Irap {
+        header: h.clone(),
+        values: vec![value; h.len()],
+    }
+}
+
+#[test]
+fn test_tie_to_points_honours_points_on_nodes() {
+    let h = header(41, 41, 0.0);
+    let surface = flat(&h, 1500.0);
+    let (xs, ys, zs) = ([1100.0, 1300.0], [2200.0, 2600.0], [1510.0, 1495.0]);
+    let method = GriddingMethod::InverseDistance {
+        power: 2.0,
+        radius: 150.0,
+        max_neighbours: 12,
+    };
+    let tie = surface
+        .tie_to_points(&xs, &ys, &zs, &method, 150.0)
+        .unwrap();
+    assert_eq!(tie.residuals, [10.0, -5.0]);
+    assert!(tie.misfits.iter().all(|m| m.abs() < 1e-4));
+    assert_eq!(tie.surface.header, h);
+
+    // The correction fades out towards the taper radius
+    let near = tie.surface.sample(1100.0, 2240.0) - 1500.0;
+    let far = tie.surface.sample(1100.0, 2320.0) - 1500.0;
+    assert!(near > far && far > 0.0);
+    assert_eq!(tie.surface.sample(1100.0, 2400.0), 1500.0);
+    assert_eq!(tie.surface.values[h.index(40, 0)], 1500.0);
+}
+
+#[test]
+fn test_tie_to_points_with_minimum_curvature() {
+    let h = header(41, 41, 10.0);
+    let mut surface = flat(&h, 1500.0);
+    surface.values[h.index(0, 0)] = f32::NAN;
+    let points: Vec<(f64, f64)> = [(10.0, 10.0), (20.0, 15.0), (30.0, 30.0)]
+        .iter()
+        .map(|&(col, row)| h.node_xy(col, row))
+        .collect();
+    let xs: Vec<f64> = points.iter().map(|p| p.0).collect();
+    let ys: Vec<f64> = points.iter().map(|p| p.1).collect();
+    let method = GriddingMethod::MinimumCurvature {
+        tension: 0.25,
+        max_iterations: 2000,
+        tolerance: 1e-6,
+    };
+    let tie = surface
+        .tie_to_points(&xs, &ys, &[1520.0, 1480.0, 1505.0], &method, 200.0)
+        .unwrap();
+    assert_eq!(tie.residuals, [20.0, -20.0, 5.0]);
+    assert!(tie.misfits.iter().all(|m| m.abs() < 0.1));
+    assert!(tie.surface.values[0].is_nan());
+}
+
+#[test]
+fn test_tie_to_points_skips_points_off_the_surface() {
+    let h = header(11, 11, 0.0);
+    let mut surface = flat(&h, 1500.0);
+    surface.values[h.index(5, 5)] = f32::NAN;
+    let tie = surface
+        .tie_to_points(
+            &[500.0, 1050.0],
+            &[2000.0, 2100.0],
+            &[1510.0, 1510.0],
+            &GriddingMethod::Nearest,
+            50.0,
+        )
+        .unwrap();
+    assert!(tie.residuals.iter().all(|r| r.is_nan()));
+    let bits = |irap: &Irap| irap.values.iter().map(|v| v.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(&tie.surface), bits(&surface));
+
+    let method = GriddingMethod::Nearest;
+    assert!(
+        surface
+            .tie_to_points(&[1.0], &[1.0], &[], &method, 50.0)
+            .is_err()
+    );
+    assert!(
+        surface
+            .tie_to_points(&[1.0], &[1.0], &[1.0], &method, 0.0)
+            .is_err()
+    );
+}
+
+fn kriging_options(kind: KrigingType) -> KrigingOptions {
+    KrigingOptions {
+        kind,
+        model: VariogramModel {
+            kind: ModelKind::Spherical,
+            nugget: 0.0,
+            sill: 4.0,
+            range: 100.0,
+        },
+        anisotropy: None,
+        radius: f64::INFINITY,
+        max_points: 16,
+        std: true,
+    }
+}
+
+#[test]
+fn test_kriging_honours_data_on_nodes() {
+    let h = header(21, 11, 0.0);
+    let (xs, ys, zs) = (
+        [1050.0, 1120.0, 1150.0],
+        [2040.0, 2100.0, 2180.0],
+        [3.0, 7.0, 5.0],
+    );
+    for kind in [KrigingType::Ordinary, KrigingType::Simple { mean: 4.0 }] {
+        let kriging = gridding::krige(&xs, &ys, &zs, &h, &kriging_options(kind)).unwrap();
+        let std = kriging.std.unwrap();
+        for k in 0..3 {
+            let (col, row) = h.world_to_grid(xs[k], ys[k]);
+            let idx = h.index(col.round() as usize, row.round() as usize);
+            assert!((kriging.surface.values[idx] as f64 - zs[k]).abs() < 1e-4);
+            assert!(std.values[idx] < 1e-3);
+        }
+        // Simple kriging never exceeds the prior standard deviation
+        if kind != KrigingType::Ordinary {
+            assert!(std.values.iter().all(|&s| (0.0..=2.0 + 1e-6).contains(&s)));
+        }
+    }
+}
+
+#[test]
+fn test_kriging_far_from_data() {
+    let h = header(101, 5, 0.0);
+    let (xs, ys, zs) = ([1000.0, 1010.0], [2000.0, 2000.0], [3.0, 5.0]);
+    let simple = gridding::krige(
+        &xs,
+        &ys,
+        &zs,
+        &h,
+        &kriging_options(KrigingType::Simple { mean: 10.0 }),
+    )
+    .unwrap();
+    let far = h.index(100, 0);
+    assert_eq!(simple.surface.values[far], 10.0);
+    assert_eq!(simple.std.unwrap().values[far], 2.0);
+
+    let ordinary =
+        gridding::krige(&xs, &ys, &zs, &h, &kriging_options(KrigingType::Ordinary)).unwrap();
+    assert!((ordinary.surface.values[far] - 4.0).abs() < 1e-4);
+    // Halfway between the points
+    let mid = gridding::krige(
+        &xs,
+        &ys,
+        &zs,
+        &header(3, 1, 0.0),
+        &kriging_options(KrigingType::Ordinary),
+    )
+    .unwrap();
+    assert!((mid.surface.values[0] - 3.0).abs() < 1e-5);
+
+    let mut limited = kriging_options(KrigingType::Ordinary);
+    limited.radius = 50.0;
+    limited.std = false;
+    let limited = gridding::krige(&xs, &ys, &zs, &h, &limited).unwrap();
+    assert!(limited.surface.values[far].is_nan());
+    assert!(!limited.surface.values[h.index(5, 0)].is_nan());
+    assert!(limited.std.is_none());
+}
+
+#[test]
+fn test_kriging_anisotropy() {
+    let h = header(21, 21, 0.0);
+    let mut options = kriging_options(KrigingType::Simple { mean: 0.0 });
+    // Longer correlation east-west
+    options.anisotropy = Some(Anisotropy {
+        azimuth: 90.0,
+        minor_range: 50.0,
+    });
+    let kriging = gridding::krige(&[1100.0], &[2200.0], &[10.0], &h, &options).unwrap();
+    // 40 m east and 40 m north of the point
+    let east = kriging.surface.values[h.index(14, 10)] as f64;
+    let north = kriging.surface.values[h.index(10, 12)] as f64;
+    let shape = |r: f64| 1.0 - 1.5 * r + 0.5 * r * r * r;
+    assert!((east - 10.0 * shape(0.4)).abs() < 1e-4);
+    assert!((north - 10.0 * shape(0.8)).abs() < 1e-4);
+}
+
+#[test]
+fn test_kriging_rejects_invalid_options() {
+    let h = header(5, 5, 0.0);
+    let krige =
+        |options: &KrigingOptions| gridding::krige(&[1000.0], &[2000.0], &[1.0], &h, options);
+    let mut options = kriging_options(KrigingType::Ordinary);
+    options.model.range = 0.0;
+    assert!(krige(&options).is_err());
+    let mut options = kriging_options(KrigingType::Ordinary);
+    options.max_points = 0;
+    assert!(krige(&options).is_err());
+    let mut options = kriging_options(KrigingType::Ordinary);
+    options.anisotropy = Some(Anisotropy {
+        azimuth: 0.0,
+        minor_range: -1.0,
+    });
+    assert!(krige(&options).is_err());
+    assert!(
+        gridding::krige(
+            &[1.0],
+            &[],
+            &[1.0],
+            &h,
+            &kriging_options(KrigingType::Ordinary)
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,27 @@
+import numpy as np
+
+import surfio_rs as surfio
+
+
+def plane(ncol, nrow, inc, f):
+    header = surfio.IrapHeader(ncol=ncol, nrow=nrow, xori=500.0, yori=800.0, xinc=inc, yinc=inc)
+    cols, rows = np.meshgrid(np.arange(ncol), np.arange(nrow), indexing="ij")
+    values = f(500.0 + inc * cols, 800.0 + inc * rows)
+    return surfio.IrapSurface(header, values.astype(np.float32))
+
+
+def test_intersection():
+    horizon = plane(31, 11, 10.0, lambda x, y: 1000.0 + (x - 500.0) / 10.0)
+    contact = plane(31, 11, 10.0, lambda x, y: np.full_like(x, 1015.0))
+    lines = horizon.intersection(contact).values
+    assert len(lines) == 1
+    assert lines[0].shape == (11, 3)
+    assert np.allclose(lines[0][:, 0], 650.0)
+    assert np.allclose(lines[0][:, 2], 1015.0)
+
+
+def test_intersection_breaks_at_undefined_nodes():
+    horizon = plane(31, 11, 10.0, lambda x, y: 1000.0 + (x - 500.0) / 10.0)
+    horizon.values[15, 5] = np.nan
+    contact = plane(31, 11, 10.0, lambda x, y: np.full_like(x, 1015.0))
+    assert len(horizon.intersection(contact).values) == 2
@@ -0,0 +1,90 @@
+use surfio_rs::intersection::intersection;
+use surfio_rs::{Irap, IrapHeader};
+
+fn header(ncol: u32, nrow: u32, inc: f64) -> IrapHeader {
+    IrapHeader {
+        ncol,
+        nrow,
+        xori: 500.0,
+        yori: 800.0,
+        xinc: inc,
+        yinc: inc,
+        ..Default::default()
+    }
+}
+
+fn surface(header: IrapHeader, f: impl Fn(f64, f64) -> f64) -> Irap {
+    let mut values = vec![0.0; header.len()];
+    for col in 0..header.ncol as usize {
+        for row in 0..header.nrow as usize {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = f(x, y) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_dipping_plane_cut_by_contact() {
+    // Depth increases by 1 per 10 m eastwards, reaching 1100 at x = 650
+    let horizon = surface(header(31, 11, 10.0), |x, _| 1000.0 + (x - 500.0) / 10.0);
+    let contact = surface(header(31, 11, 10.0), |_, _| 1015.0);
+    let lines = intersection(&horizon, &contact).polygons;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].len(), 11);
+    for &[x, y, z] in &lines[0] {
+        assert!((x - 650.0).abs() < 1e-9);
+        assert!((800.0..=900.0).contains(&y));
+        assert!((z - 1015.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_closed_ring_around_dome() {
+    let dome = surface(header(41, 41, 5.0), |x, y| {
+        1000.0 + 0.5 * (x - 600.0).hypot(y - 900.0)
+    });
+    let contact = surface(header(41, 41, 5.0), |_, _| 1030.0);
+    let lines = intersection(&dome, &contact).polygons;
+    assert_eq!(lines.len(), 1);
+    let ring = &lines[0];
+    assert_eq!(ring.first(), ring.last());
+    for &[x, y, z] in ring {
+        assert!(((x - 600.0).hypot(y - 900.0) - 60.0).abs() < 1.0);
+        assert!((z - 1030.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_undefined_nodes_break_line() {
+    let mut horizon = surface(header(31, 11, 10.0), |x, _| 1000.0 + (x - 500.0) / 10.0);
+    let contact = surface(header(31, 11, 10.0), |_, _| 1015.0);
+    let idx = horizon.header.index(15, 5);
+    horizon.values[idx] = f32::NAN;
+    let lines = intersection(&horizon, &contact).polygons;
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().flatten().all(|p| !p[2].is_nan()));
+}
+
+#[test]
+fn test_other_lattice_is_resampled() {
+    let horizon = surface(header(31, 11, 10.0), |x, _| 1000.0 + (x - 500.0) / 10.0);
+    let mut coarse = header(9, 5, 40.0);
+    coarse.xori = 480.0;
+    coarse.yori = 780.0;
+    let contact = surface(coarse, |x, _| 1010.0 + (x - 500.0) / 20.0);
+    // 1000 + d / 10 = 1010 + d / 20 at d = 200
+    let lines = intersection(&horizon, &contact).polygons;
+    assert_eq!(lines.len(), 1);
+    for &[x, _, z] in &lines[0] {
+        assert!((x - 700.0).abs() < 1e-3);
+        assert!((z - 1020.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_no_intersection() {
+    let horizon = surface(header(11, 11, 10.0), |_, _| 1000.0);
+    let contact = surface(header(11, 11, 10.0), |_, _| 1200.0);
+    assert!(intersection(&horizon, &contact).polygons.is_empty());
+}
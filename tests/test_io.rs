@@ -1,5 +1,5 @@
 use core::default::Default;
-use surfio_rs::{Irap, IrapHeader, irap};
+use surfio_rs::{Irap, IrapHeader, Points, Polygons, irap};
 
 fn create_dummy_irap() -> Irap {
     let header = IrapHeader {
@@ -56,3 +56,106 @@ fn test_round_trip_ascii_file() {
 
     let _ = std::fs::remove_file(path);
 }
+
+fn create_dummy_points() -> Points {
+    Points {
+        values: vec![
+            [456000.5, 6780000.25, 1500.0],
+            [456010.0, 6780010.0, 1510.125],
+            [456020.0, 6780020.0, f64::NAN],
+        ],
+    }
+}
+
+fn create_dummy_polygons() -> Polygons {
+    Polygons {
+        polygons: vec![
+            vec![[0.0, 0.0, 10.0], [1.0, 0.0, 10.0], [1.0, 1.0, 10.0]],
+            vec![[5.0, 5.0, 20.0], [6.0, 5.0, 20.0]],
+        ],
+    }
+}
+
+#[test]
+fn test_round_trip_points_string() {
+    let points = create_dummy_points();
+    let ascii = irap::points::to_string(&points).unwrap();
+    let points_read = irap::points::from_string(&ascii).unwrap();
+
+    assert_eq!(points.values.len(), points_read.values.len());
+    assert_eq!(points.values[..2], points_read.values[..2]);
+    assert!(points_read.values[2][2].is_nan());
+    assert!(!ascii.contains("NaN"));
+    assert!(ascii.contains("9999900.000000"));
+}
+
+#[test]
+fn test_round_trip_points_file() {
+    let points = Points {
+        values: create_dummy_points().values[..2].to_vec(),
+    };
+    let path = "test_output.poi";
+    irap::points::to_file(path.to_string(), &points).unwrap();
+    let points_read = irap::points::from_file(path.to_string()).unwrap();
+
+    assert_eq!(points, points_read);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_reading_truncated_points_errors() {
+    assert!(irap::points::from_string("1.0 2.0 3.0\n4.0 5.0").is_err());
+}
+
+#[test]
+fn test_round_trip_polygons_string() {
+    let polygons = create_dummy_polygons();
+    let ascii = irap::polygons::to_string(&polygons).unwrap();
+    let polygons_read = irap::polygons::from_string(&ascii).unwrap();
+
+    assert_eq!(ascii.matches("999.000000").count(), 6);
+    assert_eq!(polygons, polygons_read);
+}
+
+#[test]
+fn test_round_trip_polygons_file() {
+    let polygons = create_dummy_polygons();
+    let path = "test_output.pol";
+    irap::polygons::to_file(path.to_string(), &polygons).unwrap();
+    let polygons_read = irap::polygons::from_file(path.to_string()).unwrap();
+
+    assert_eq!(polygons, polygons_read);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_round_trip_polygons_with_undefined_z() {
+    let polygons = Polygons {
+        polygons: vec![vec![[0.0, 0.0, f64::NAN], [1.0, 0.0, 10.0]]],
+    };
+    let ascii = irap::polygons::to_string(&polygons).unwrap();
+    let polygons_read = irap::polygons::from_string(&ascii).unwrap();
+
+    assert!(!ascii.contains("NaN"));
+    assert!(polygons_read.polygons[0][0][2].is_nan());
+    assert_eq!(polygons_read.polygons[0][1], [1.0, 0.0, 10.0]);
+}
+
+#[test]
+fn test_writing_undefined_coordinates_errors() {
+    let points = Points {
+        values: vec![[f64::NAN, 0.0, 1.0]],
+    };
+    assert!(irap::points::to_string(&points).is_err());
+}
+
+#[test]
+fn test_polygons_without_final_separator() {
+    let polygons =
+        irap::polygons::from_string("0 0 1\n1 0 1\n999.0 999.0 999.0\n2 2 2\n3 3 3\n").unwrap();
+
+    assert_eq!(polygons.polygons.len(), 2);
+    assert_eq!(polygons.polygons[1], vec![[2.0, 2.0, 2.0], [3.0, 3.0, 3.0]]);
+}
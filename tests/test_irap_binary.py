@@ -69,6 +69,29 @@ def test_surfio_can_export_values_in_fortran_order():
     assert srf.header == srf_imported.header
 
 
+def test_surfio_can_export_non_contiguous_values():
+    values = np.arange(12, dtype=np.float32).reshape((3, 4))[:, ::2]
+    srf = surfio.IrapSurface(
+        surfio.IrapHeader(ncol=3, nrow=2, xinc=1.0, yinc=1.0, xmax=2.0, ymax=1.0),
+        values=values,
+    )
+    buffer = srf.to_binary_buffer()
+    srf_imported = surfio.IrapSurface.from_binary_buffer(buffer)
+
+    assert np.array_equal(srf_imported.values, values)
+
+
+def test_mismatched_values_shape_raises():
+    srf = surfio.IrapSurface(
+        surfio.IrapHeader(ncol=3, nrow=2, xinc=1.0, yinc=1.0),
+        values=np.zeros((2, 3), dtype=np.float32),
+    )
+    with pytest.raises(ValueError, match="shape"):
+        srf.to_binary_buffer()
+    with pytest.raises(ValueError, match="shape"):
+        srf.slope()
+
+
 def test_xtgeo_can_import_data_exported_from_surfio(tmp_path):
     srf = surfio.IrapSurface(
         surfio.IrapHeader(ncol=3, nrow=2, xinc=1.0, yinc=1.0, xmax=2.0, ymax=1.0),
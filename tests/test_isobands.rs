@@ -0,0 +1,123 @@
+use surfio_rs::{Irap, IrapHeader, contours};
+
+fn surface(ncol: u32, nrow: u32, rot: f64, f: impl Fn(f64, f64) -> f32) -> Irap {
+    let header = IrapHeader {
+        ncol,
+        nrow,
+        xori: 100.0,
+        yori: 200.0,
+        xinc: 2.0,
+        yinc: 3.0,
+        rot,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..ncol as usize {
+        for row in 0..nrow as usize {
+            values[header.index(col, row)] = f(col as f64, row as f64);
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_band_area_on_plane() {
+    for rot in [0.0, 30.0, 200.0] {
+        let irap = surface(11, 6, rot, |c, _| c as f32);
+        let bands = irap.isobands(&[2.0, 5.0]);
+
+        assert_eq!(bands.len(), 1);
+        assert!((bands[0].area - 3.0 * 2.0 * 5.0 * 3.0).abs() < 1e-9);
+        assert_eq!(bands[0].polygons.len(), 1);
+        assert!(bands[0].polygons[0].holes.is_empty());
+    }
+}
+
+#[test]
+fn test_bands_cover_defined_area() {
+    let irap = surface(21, 21, 15.0, |c, r| ((c - 10.0).hypot(r - 10.0)) as f32);
+    let bands = irap.isobands(&[0.0, 3.0, 6.0, 100.0]);
+    let total: f64 = bands.iter().map(|b| b.area).sum();
+
+    assert_eq!(bands.len(), 3);
+    assert!((total - 20.0 * 2.0 * 20.0 * 3.0).abs() < 1e-6);
+    // Annulus between 3 and 6 has a hole
+    assert_eq!(bands[1].polygons.len(), 1);
+    assert_eq!(bands[1].polygons[0].holes.len(), 1);
+    // Outside 6 the band is the grid with a hole
+    assert_eq!(bands[2].polygons.len(), 1);
+    assert_eq!(bands[2].polygons[0].holes.len(), 1);
+}
+
+#[test]
+fn test_unsorted_levels_are_sorted() {
+    let irap = surface(11, 6, 0.0, |c, _| c as f32);
+    let bands = irap.isobands(&[5.0, 2.0, 0.0]);
+
+    assert_eq!((bands[0].lower, bands[0].upper), (0.0, 2.0));
+    assert_eq!((bands[1].lower, bands[1].upper), (2.0, 5.0));
+}
+
+#[test]
+fn test_undefined_nodes_are_excluded() {
+    let mut irap = surface(5, 5, 0.0, |_, _| 1.0);
+    let idx = irap.header.index(2, 2);
+    irap.values[idx] = f32::NAN;
+    let bands = irap.isobands(&[0.0, 2.0]);
+
+    // Four cells around the undefined node are removed
+    assert!((bands[0].area - 12.0 * 6.0).abs() < 1e-9);
+    assert_eq!(bands[0].polygons.len(), 1);
+    assert_eq!(bands[0].polygons[0].holes.len(), 1);
+}
+
+#[test]
+fn test_disjoint_regions_give_separate_polygons() {
+    let irap = surface(
+        11,
+        3,
+        0.0,
+        |c, _| if c == 2.0 || c == 8.0 { 10.0 } else { 0.0 },
+    );
+    let bands = irap.isobands(&[5.0, 20.0]);
+
+    assert_eq!(bands[0].polygons.len(), 2);
+    for polygon in &bands[0].polygons {
+        assert_eq!(polygon.exterior.first(), polygon.exterior.last());
+    }
+}
+
+#[test]
+fn test_isobands_to_polygons_and_geojson() {
+    let irap = surface(21, 21, 0.0, |c, r| ((c - 10.0).hypot(r - 10.0)) as f32);
+    let bands = irap.isobands(&[0.0, 3.0, 6.0]);
+
+    let polygons = contours::isobands_to_polygons(&bands);
+    assert_eq!(polygons.polygons.len(), 3);
+    assert!(polygons.polygons[2].iter().all(|p| p[2] == 3.0));
+
+    let geojson = contours::isobands_to_geojson_string(&bands).unwrap();
+    assert_eq!(geojson.matches("\"MultiPolygon\"").count(), 2);
+    assert!(geojson.contains("\"lower\":3,\"upper\":6"));
+}
+
+#[test]
+fn test_saddles_are_resolved_like_contours() {
+    // Corners (0, 0) and (1, 1) are high, with a centre mean of 0.5
+    let irap = surface(2, 2, 0.0, |c, r| if c == r { 1.0 } else { 0.0 });
+    for (level, pieces) in [(0.4, 1), (0.6, 2)] {
+        let bands = irap.isobands(&[level, 2.0]);
+        assert_eq!(bands[0].polygons.len(), pieces);
+
+        let contours = irap.contours(&[level]);
+        assert_eq!(contours.len(), 2);
+        for point in contours.iter().flat_map(|c| &c.points) {
+            let on_band = bands[0].polygons.iter().any(|p| {
+                p.exterior
+                    .iter()
+                    .any(|v| (v[0] - point[0]).abs() < 1e-9 && (v[1] - point[1]).abs() < 1e-9)
+            });
+            assert!(on_band, "{:?}", point);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def patch(xori, ncol, value):
+    header = surfio.IrapHeader(
+        ncol=ncol, nrow=5, xori=xori, yori=100.0, xinc=10.0, yinc=10.0
+    )
+    return surfio.IrapSurface(header, np.full((ncol, 5), value, dtype=np.float32))
+
+
+def test_merge_first_and_mean():
+    surfaces = [patch(0.0, 6, 1.0), patch(40.0, 6, 3.0)]
+    first = surfio.IrapSurface.merge(surfaces)
+    assert first.values.shape == (10, 5)
+    assert np.array_equal(first.values[:, 0], [1.0] * 6 + [3.0] * 4)
+
+    mean = surfio.IrapSurface.merge(surfaces, mode="mean")
+    assert np.array_equal(mean.values[4:6, 0], [2.0, 2.0])
+
+
+def test_merge_onto_header():
+    header = surfio.IrapHeader(
+        ncol=3, nrow=3, xori=10.0, yori=100.0, xinc=20.0, yinc=20.0
+    )
+    merged = surfio.IrapSurface.merge([patch(0.0, 6, 1.0)], header=header)
+    assert (merged.header.ncol, merged.header.nrow) == (3, 3)
+    assert (merged.header.xori, merged.header.yori) == (10.0, 100.0)
+    assert (merged.header.xmax, merged.header.ymax) == (50.0, 140.0)
+    assert merged.values.shape == (3, 3)
+
+
+def test_merge_feather():
+    surfaces = [patch(0.0, 11, 0.0), patch(50.0, 11, 10.0)]
+    merged = surfio.IrapSurface.merge(surfaces, mode="feather", width=40.0)
+    assert np.all(np.diff(merged.values[4:12, 2]) > 0)
+    with pytest.raises(ValueError):
+        surfio.IrapSurface.merge(surfaces, mode="feather")
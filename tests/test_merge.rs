@@ -0,0 +1,101 @@
+use surfio_rs::merge::{MergeMode, merge};
+use surfio_rs::{Irap, IrapHeader};
+
+fn patch(xori: f64, ncol: u32, value: f32) -> Irap {
+    let header = IrapHeader {
+        ncol,
+        nrow: 5,
+        xori,
+        yori: 100.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    Irap {
+        values: vec![value; header.len()],
+        header,
+    }
+}
+
+fn row(irap: &Irap, row: usize) -> Vec<f32> {
+    (0..irap.header.ncol as usize)
+        .map(|col| irap.values[irap.header.index(col, row)])
+        .collect()
+}
+
+#[test]
+fn test_merge_union_extent() {
+    // Columns 0-5 and 4-9 of a common lattice
+    let surfaces = [patch(0.0, 6, 1.0), patch(40.0, 6, 3.0)];
+    let merged = merge(&surfaces, None, MergeMode::First).unwrap();
+    assert_eq!(merged.header.ncol, 10);
+    assert_eq!(merged.header.nrow, 5);
+    assert_eq!(merged.header.xori, 0.0);
+    assert_eq!(merged.header.xmax, 90.0);
+    assert_eq!(
+        row(&merged, 2),
+        [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 3.0, 3.0, 3.0, 3.0]
+    );
+
+    let reversed = [surfaces[1].clone(), surfaces[0].clone()];
+    let merged = merge(&reversed, None, MergeMode::First).unwrap();
+    assert_eq!(merged.header.xori, 0.0);
+    assert_eq!(
+        row(&merged, 2),
+        [1.0, 1.0, 1.0, 1.0, 3.0, 3.0, 3.0, 3.0, 3.0, 3.0]
+    );
+}
+
+#[test]
+fn test_merge_mean() {
+    let surfaces = [patch(0.0, 6, 1.0), patch(40.0, 6, 3.0)];
+    let merged = merge(&surfaces, None, MergeMode::Mean).unwrap();
+    assert_eq!(
+        row(&merged, 0),
+        [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0]
+    );
+}
+
+#[test]
+fn test_merge_onto_target_lattice() {
+    let surfaces = [patch(0.0, 6, 1.0)];
+    let target = IrapHeader {
+        ncol: 4,
+        nrow: 4,
+        xori: 35.0,
+        yori: 105.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    let merged = merge(&surfaces, Some(&target), MergeMode::First).unwrap();
+    assert_eq!(merged.header, target);
+    assert_eq!(merged.values[merged.header.index(0, 0)], 1.0);
+    assert_eq!(merged.values[merged.header.index(1, 2)], 1.0);
+    assert!(merged.values[merged.header.index(2, 0)].is_nan());
+    assert_eq!(merged.values[merged.header.index(1, 3)], 1.0);
+    assert!(merged.values[merged.header.index(3, 3)].is_nan());
+}
+
+#[test]
+fn test_merge_feather_blends_across_overlap() {
+    let surfaces = [patch(0.0, 11, 0.0), patch(50.0, 11, 10.0)];
+    let merged = merge(&surfaces, None, MergeMode::Feather { width: 40.0 }).unwrap();
+    let values = row(&merged, 2);
+    assert_eq!(values.len(), 16);
+    // Outside the overlap each surface is used alone
+    assert_eq!(values[..5], [0.0; 5]);
+    assert_eq!(values[11..], [10.0; 5]);
+    // Across the overlap of columns 5-10 the blend increases monotonically
+    for pair in values[4..12].windows(2) {
+        assert!(pair[1] > pair[0], "{:?}", values);
+    }
+    assert!((values[7] + values[8] - 10.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_merge_rejects_invalid_input() {
+    assert!(merge(&[], None, MergeMode::First).is_err());
+    let surfaces = [patch(0.0, 6, 1.0)];
+    assert!(merge(&surfaces, None, MergeMode::Feather { width: 0.0 }).is_err());
+}
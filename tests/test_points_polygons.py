@@ -0,0 +1,62 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def test_reading_points_from_string():
+    points = surfio.Points.from_string(
+        """\
+        1.0 2.0 3.0
+        4.0 5.0 6.0
+        """
+    )
+    assert len(points) == 2
+    assert points.values.tolist() == [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+
+
+def test_points_import_and_export_are_inverse(tmp_path):
+    points = surfio.Points(np.arange(12, dtype=np.float64).reshape((4, 3)))
+    points.to_file(str(tmp_path / "test.poi"))
+    roundtrip = surfio.Points.from_file(str(tmp_path / "test.poi"))
+    assert np.array_equal(roundtrip.values, points.values)
+
+
+def test_points_with_wrong_shape_results_in_value_error():
+    points = surfio.Points(np.zeros((2, 2), dtype=np.float64))
+    with pytest.raises(ValueError, match="shape"):
+        points.to_string()
+
+
+def test_reading_truncated_points_results_in_value_error():
+    with pytest.raises(ValueError, match="end of file"):
+        _ = surfio.Points.from_string("1.0 2.0 3.0\n4.0 5.0")
+
+
+def test_reading_polygons_from_string():
+    polygons = surfio.Polygons.from_string(
+        """\
+        0.0 0.0 1.0
+        1.0 0.0 1.0
+        999.0 999.0 999.0
+        5.0 5.0 2.0
+        999.0 999.0 999.0
+        """
+    )
+    assert len(polygons) == 2
+    assert polygons.values[0].tolist() == [[0.0, 0.0, 1.0], [1.0, 0.0, 1.0]]
+    assert polygons.values[1].tolist() == [[5.0, 5.0, 2.0]]
+
+
+def test_polygons_import_and_export_are_inverse(tmp_path):
+    polygons = surfio.Polygons(
+        [
+            np.arange(9, dtype=np.float64).reshape((3, 3)),
+            np.arange(6, dtype=np.float64).reshape((2, 3)),
+        ]
+    )
+    polygons.to_file(str(tmp_path / "test.pol"))
+    roundtrip = surfio.Polygons.from_file(str(tmp_path / "test.pol"))
+    assert len(roundtrip) == 2
+    for a, b in zip(roundtrip.values, polygons.values):
+        assert np.array_equal(a, b)
@@ -0,0 +1,34 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def plane(slope):
+    header = surfio.IrapHeader(ncol=11, nrow=11, xinc=10.0, yinc=10.0)
+    cols, rows = np.meshgrid(np.arange(11.0), np.arange(11.0), indexing="ij")
+    values = 1000.0 + slope * 10.0 * cols + 10.0 * rows
+    return surfio.IrapSurface(header, values.astype(np.float32))
+
+
+def test_profile():
+    line = np.array([[0.0, 50.0], [100.0, 50.0]])
+    distance, x, y, values = plane(2.0).profile(line, 10.0)
+    assert np.allclose(distance, np.arange(0.0, 101.0, 10.0))
+    assert np.allclose(y, 50.0)
+    assert np.allclose(values, 1000.0 + 2.0 * x + y)
+
+
+def test_profiles_table():
+    line = np.array([[0.0, 0.0, 0.0], [30.0, 40.0, 0.0]])
+    distance, x, y, values = surfio.IrapSurface.profiles(
+        [plane(1.0), plane(2.0)], line, 10.0
+    )
+    assert distance.shape == (6,)
+    assert values.shape == (6, 2)
+    assert np.allclose(values[:, 1], 1000.0 + 2.0 * x + y)
+
+
+def test_profile_invalid_spacing():
+    with pytest.raises(ValueError):
+        plane(1.0).profile(np.array([[0.0, 0.0], [1.0, 1.0]]), -1.0)
@@ -0,0 +1,92 @@
+use surfio_rs::profile::profiles;
+use surfio_rs::{Irap, IrapHeader};
+
+fn plane(rot: f64, slope: f64) -> Irap {
+    let header = IrapHeader {
+        ncol: 21,
+        nrow: 21,
+        xinc: 10.0,
+        yinc: 10.0,
+        rot,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..21 {
+        for row in 0..21 {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = (1000.0 + slope * x + y) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_profile_positions_include_vertices() {
+    let irap = plane(0.0, 2.0);
+    let line = [[0.0, 50.0], [25.0, 50.0], [25.0, 80.0]];
+    let profile = irap.profile(&line, 10.0).unwrap();
+    assert_eq!(
+        profile.distance,
+        [0.0, 10.0, 20.0, 25.0, 30.0, 40.0, 50.0, 55.0]
+    );
+    assert_eq!(profile.x, [0.0, 10.0, 20.0, 25.0, 25.0, 25.0, 25.0, 25.0]);
+    assert_eq!(profile.y, [50.0, 50.0, 50.0, 50.0, 55.0, 65.0, 75.0, 80.0]);
+}
+
+#[test]
+fn test_profile_samples_rotated_surface() {
+    let irap = plane(30.0, 2.0);
+    // The line starts outside the rotated lattice
+    let line = [[-50.0, 0.0], [80.0, 120.0]];
+    let profile = irap.profile(&line, 7.0).unwrap();
+    let mut outside = 0;
+    for k in 0..profile.distance.len() {
+        let (col, row) = irap.header.world_to_grid(profile.x[k], profile.y[k]);
+        let v = profile.values[k];
+        if col < 0.0 || row < 0.0 {
+            assert!(v.is_nan());
+            outside += 1;
+        } else {
+            let expected = 1000.0 + 2.0 * profile.x[k] + profile.y[k];
+            assert!((v - expected).abs() < 1e-3, "{} != {}", v, expected);
+        }
+    }
+    assert!(outside > 0);
+}
+
+#[test]
+fn test_profile_breaks_at_undefined_nodes() {
+    let mut irap = plane(0.0, 1.0);
+    let idx = irap.header.index(5, 5);
+    irap.values[idx] = f32::NAN;
+    let profile = irap.profile(&[[0.0, 50.0], [100.0, 50.0]], 5.0).unwrap();
+    let undefined: Vec<f64> = profile
+        .distance
+        .iter()
+        .zip(&profile.values)
+        .filter(|(_, v)| v.is_nan())
+        .map(|(d, _)| *d)
+        .collect();
+    assert_eq!(undefined, [45.0, 50.0, 55.0]);
+}
+
+#[test]
+fn test_profiles_are_aligned() {
+    let surfaces = [plane(0.0, 1.0), plane(0.0, 3.0)];
+    let line = [[0.0, 0.0], [100.0, 100.0]];
+    let table = profiles(&surfaces, &line, 10.0).unwrap();
+    assert_eq!(table.values.len(), 2);
+    for column in &table.values {
+        assert_eq!(column.len(), table.distance.len());
+    }
+    let single = surfaces[1].profile(&line, 10.0).unwrap();
+    assert_eq!(single.values, table.values[1]);
+    assert_eq!(single.distance, table.distance);
+}
+
+#[test]
+fn test_profile_rejects_invalid_input() {
+    let irap = plane(0.0, 1.0);
+    assert!(irap.profile(&[[0.0, 0.0], [10.0, 0.0]], 0.0).is_err());
+    assert!(irap.profile(&[], 1.0).is_err());
+}
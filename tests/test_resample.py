@@ -0,0 +1,52 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def plane(rot=0.0):
+    header = surfio.IrapHeader(ncol=11, nrow=11, xinc=10.0, yinc=10.0, rot=rot)
+    cols, rows = np.meshgrid(np.arange(11.0), np.arange(11.0), indexing="ij")
+    return surfio.IrapSurface(header, (2.0 * cols + 3.0 * rows).astype(np.float32))
+
+
+def test_rotate_to():
+    rotated = plane().rotate_to(45.0)
+    assert rotated.header.rot == 45.0
+    assert rotated.header.xinc == 10.0
+    assert rotated.values.shape == (16, 16)
+    assert np.isnan(rotated.values).any()
+
+
+def test_rotate_to_same_orientation_keeps_values():
+    srf = plane(rot=20.0)
+    same = srf.rotate_to(20.0)
+    assert np.allclose(same.values, srf.values, atol=1e-3)
+
+
+def test_rotate_to_rejects_bad_increment():
+    with pytest.raises(ValueError):
+        plane().rotate_to(10.0, xinc=0.0)
+
+
+@pytest.mark.parametrize("agg", ["mean", "min", "max", "median"])
+def test_coarsen(agg):
+    coarse = plane().coarsen(2, agg=agg)
+    assert coarse.values.shape == (6, 6)
+    assert coarse.header.xinc == 20.0
+    assert coarse.header.xmax == 100.0
+
+
+def test_refine():
+    srf = plane()
+    for interp in ["bilinear", "bicubic"]:
+        fine = srf.refine(2, 3, interp=interp)
+        assert fine.values.shape == (21, 31)
+        assert np.allclose(fine.values[::2, ::3], srf.values)
+
+
+def test_resample_factors_must_be_positive():
+    with pytest.raises(ValueError):
+        plane().coarsen(0)
+    with pytest.raises(ValueError):
+        plane().refine(2, interp="nearest")
@@ -0,0 +1,253 @@
+use surfio_rs::resample::{Aggregation, Interpolation};
+use surfio_rs::{Irap, IrapHeader};
+
+fn plane(rot: f64) -> Irap {
+    let header = IrapHeader {
+        ncol: 11,
+        nrow: 11,
+        xori: 500.0,
+        yori: 800.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        rot,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..11 {
+        for row in 0..11 {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = (2.0 * (x - 500.0) + 3.0 * (y - 800.0)) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_rotate_to_covers_source_extent() {
+    let rotated = plane(0.0).rotate_to(45.0, 10.0, 10.0).unwrap();
+    assert_eq!(rotated.header.rot, 45.0);
+    assert_eq!(rotated.header.ncol, 16);
+    assert_eq!(rotated.header.nrow, 16);
+    assert_eq!(rotated.header.xrot, rotated.header.xori);
+
+    // Corners of the source lattice are inside the target lattice
+    for (x, y) in [
+        (500.0, 800.0),
+        (600.0, 800.0),
+        (500.0, 900.0),
+        (600.0, 900.0),
+    ] {
+        let (col, row) = rotated.header.world_to_grid(x, y);
+        assert!(col > -1e-9 && col < 15.0 + 1e-9, "{}", col);
+        assert!(row > -1e-9 && row < 15.0 + 1e-9, "{}", row);
+    }
+}
+
+#[test]
+fn test_rotate_to_resamples_values() {
+    let rotated = plane(0.0).rotate_to(30.0, 5.0, 7.5).unwrap();
+    let nrow = rotated.header.nrow as usize;
+    let mut defined = 0;
+    for (idx, &v) in rotated.values.iter().enumerate() {
+        if v.is_nan() {
+            continue;
+        }
+        defined += 1;
+        let (x, y) = rotated
+            .header
+            .node_xy((idx / nrow) as f64, (idx % nrow) as f64);
+        let expected = 2.0 * (x - 500.0) + 3.0 * (y - 800.0);
+        assert!((v as f64 - expected).abs() < 1e-3, "{} != {}", v, expected);
+    }
+    assert!(defined > 0);
+    assert!(defined < rotated.values.len());
+}
+
+#[test]
+fn test_rotate_to_unrotates() {
+    let source = plane(30.0);
+    let unrotated = source.rotate_to(0.0, 10.0, 10.0).unwrap();
+    assert_eq!(unrotated.header.rot, 0.0);
+
+    let corners: Vec<(f64, f64)> = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0)]
+        .iter()
+        .map(|&(col, row)| source.header.node_xy(col, row))
+        .collect();
+    let xmin = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+    let xmax = corners
+        .iter()
+        .map(|c| c.0)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let ymin = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+    let ymax = corners
+        .iter()
+        .map(|c| c.1)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let h = &unrotated.header;
+    assert!((h.xori - xmin).abs() < 1e-9);
+    assert!((h.yori - ymin).abs() < 1e-9);
+    assert!(h.xmax >= xmax - 1e-9 && h.xmax < xmax + 10.0);
+    assert!(h.ymax >= ymax - 1e-9 && h.ymax < ymax + 10.0);
+}
+
+#[test]
+fn test_rotate_to_same_orientation_is_identity() {
+    let source = plane(20.0);
+    let same = source.rotate_to(20.0, 10.0, 10.0).unwrap();
+    assert_eq!(same.header.ncol, 11);
+    assert_eq!(same.header.nrow, 11);
+    assert!((same.header.xori - 500.0).abs() < 1e-9);
+    assert!((same.header.yori - 800.0).abs() < 1e-9);
+    for (a, b) in same.values.iter().zip(&source.values) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn test_rotate_to_keeps_values_in_place() {
+    // Rotated around its origin away from (0, 0), with xrot and yrot left at zero
+    let source = plane(30.0);
+    let rotated = source.rotate_to(-15.0, 5.0, 5.0).unwrap();
+
+    for (col, row) in [(2.5, 3.5), (5.0, 5.0), (8.25, 1.75), (1.0, 9.0)] {
+        let (x, y) = source.header.node_xy(col, row);
+        let (a, b) = (rotated.sample(x, y), source.sample(x, y));
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn test_rotate_to_rejects_invalid_increments() {
+    let source = plane(10.0);
+    assert!(source.rotate_to(0.0, 0.0, 10.0).is_err());
+    assert!(source.rotate_to(0.0, 10.0, -1.0).is_err());
+    assert!(source.rotate_to(0.0, f64::NAN, 10.0).is_err());
+    assert!(source.rotate_to(0.0, 1e-300, 10.0).is_err());
+}
+
+fn ramp(ncol: u32, nrow: u32) -> Irap {
+    let header = IrapHeader {
+        ncol,
+        nrow,
+        xinc: 10.0,
+        yinc: 20.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..ncol as usize {
+        for row in 0..nrow as usize {
+            values[header.index(col, row)] = (col + 100 * row) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_coarsen_updates_header() {
+    let coarse = ramp(10, 7).coarsen(3, 2, Aggregation::Mean, 0.0).unwrap();
+    assert_eq!(coarse.header.ncol, 4);
+    assert_eq!(coarse.header.nrow, 4);
+    assert_eq!(coarse.header.xinc, 30.0);
+    assert_eq!(coarse.header.yinc, 40.0);
+    assert_eq!(coarse.header.xmax, 90.0);
+    assert_eq!(coarse.header.ymax, 120.0);
+}
+
+#[test]
+fn test_coarsen_aggregates_centred_blocks() {
+    let irap = ramp(10, 7);
+    let mean = irap.coarsen(3, 3, Aggregation::Mean, 0.0).unwrap();
+    let min = irap.coarsen(3, 3, Aggregation::Min, 0.0).unwrap();
+    let max = irap.coarsen(3, 3, Aggregation::Max, 0.0).unwrap();
+    let median = irap.coarsen(3, 3, Aggregation::Median, 0.0).unwrap();
+    // Interior block of node (3, 3) covers columns 2..=4 and rows 2..=4
+    let idx = mean.header.index(1, 1);
+    assert_eq!(mean.values[idx], 303.0);
+    assert_eq!(min.values[idx], 202.0);
+    assert_eq!(max.values[idx], 404.0);
+    assert_eq!(median.values[idx], 303.0);
+    // Edge block of node (0, 0) is clipped to columns 0..=1 and rows 0..=1
+    assert_eq!(mean.values[0], 50.5);
+    assert_eq!(median.values[0], 50.5);
+}
+
+#[test]
+fn test_coarsen_minimum_defined_fraction() {
+    let mut irap = ramp(9, 9);
+    for col in 2..5 {
+        for row in 2..4 {
+            let idx = irap.header.index(col, row);
+            irap.values[idx] = f32::NAN;
+        }
+    }
+    // Six of the nine nodes in the block around (3, 3) are undefined
+    let idx = |c: &Irap| c.header.index(1, 1);
+    let lenient = irap.coarsen(3, 3, Aggregation::Mean, 0.3).unwrap();
+    assert_eq!(lenient.values[idx(&lenient)], 403.0);
+    let strict = irap.coarsen(3, 3, Aggregation::Mean, 0.5).unwrap();
+    assert!(strict.values[idx(&strict)].is_nan());
+}
+
+#[test]
+fn test_refine_keeps_nodes_and_updates_header() {
+    let irap = ramp(4, 3);
+    for interp in [Interpolation::Bilinear, Interpolation::Bicubic] {
+        let fine = irap.refine(2, 4, interp).unwrap();
+        assert_eq!(fine.header.ncol, 7);
+        assert_eq!(fine.header.nrow, 9);
+        assert_eq!(fine.header.xinc, 5.0);
+        assert_eq!(fine.header.yinc, 5.0);
+        assert_eq!(fine.header.xmax, 30.0);
+        for col in 0..4 {
+            for row in 0..3 {
+                let v = fine.values[fine.header.index(2 * col, 4 * row)];
+                assert_eq!(v, irap.values[irap.header.index(col, row)]);
+            }
+        }
+        // The ramp is linear, which both methods reproduce
+        assert_eq!(fine.values[fine.header.index(3, 2)], 51.5);
+    }
+}
+
+#[test]
+fn test_refine_bicubic_is_smoother_than_bilinear() {
+    let header = IrapHeader {
+        ncol: 6,
+        nrow: 2,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..6 {
+        for row in 0..2 {
+            values[header.index(col, row)] = (col * col) as f32;
+        }
+    }
+    let irap = Irap { header, values };
+    let bilinear = irap.refine(2, 1, Interpolation::Bilinear).unwrap();
+    let bicubic = irap.refine(2, 1, Interpolation::Bicubic).unwrap();
+    // Halfway between columns 2 and 3 the parabola is 6.25
+    let idx = bilinear.header.index(5, 0);
+    assert_eq!(bilinear.values[idx], 6.5);
+    assert_eq!(bicubic.values[idx], 6.25);
+}
+
+#[test]
+fn test_refine_bicubic_falls_back_to_bilinear_near_undefined() {
+    let mut irap = ramp(6, 6);
+    let idx = irap.header.index(0, 0);
+    irap.values[idx] = f32::NAN;
+    let fine = irap.refine(2, 2, Interpolation::Bicubic).unwrap();
+    assert!(fine.values[fine.header.index(1, 1)].is_nan());
+    assert_eq!(fine.values[fine.header.index(3, 3)], 151.5);
+}
+
+#[test]
+fn test_resample_factors_must_be_valid() {
+    let irap = ramp(6, 6);
+    assert!(irap.coarsen(0, 2, Aggregation::Mean, 0.0).is_err());
+    assert!(irap.coarsen(2, 0, Aggregation::Mean, 0.0).is_err());
+    assert!(irap.refine(0, 2, Interpolation::Bilinear).is_err());
+    assert!(irap.refine(2, 0, Interpolation::Bilinear).is_err());
+    // 5 cells of 2^30 nodes each do not fit in the header
+    assert!(irap.refine(1 << 30, 1, Interpolation::Bilinear).is_err());
+}
@@ -0,0 +1,40 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+HEADER = surfio.IrapHeader(ncol=20, nrow=15, xori=1000.0, yori=2000.0, xinc=10.0, yinc=10.0)
+MODEL = surfio.VariogramModel("exponential", sill=4.0, range=100.0)
+
+
+def test_simulate_is_reproducible():
+    three = surfio.IrapSurface.simulate(HEADER, MODEL, count=3, mean=50.0, seed=7)
+    five = surfio.IrapSurface.simulate(HEADER, MODEL, count=5, mean=50.0, seed=7)
+    assert len(three) == 3
+    assert three[0].values.shape == (20, 15)
+    for a, b in zip(three, five):
+        np.testing.assert_array_equal(a.values, b.values)
+    assert not np.array_equal(three[0].values, three[1].values)
+
+
+def test_simulate_honours_conditioning_data():
+    xs, ys, zs = np.array([1050.0, 1120.0]), np.array([2040.0, 2100.0]), np.array([40.0, 60.0])
+    fields = surfio.IrapSurface.simulate(HEADER, MODEL, count=4, mean=50.0, xs=xs, ys=ys, zs=zs)
+    for field in fields:
+        assert field.values[5, 4] == pytest.approx(40.0, abs=1e-3)
+        assert field.values[12, 10] == pytest.approx(60.0, abs=1e-3)
+
+    with pytest.raises(ValueError, match="Conditioning"):
+        surfio.IrapSurface.simulate(HEADER, MODEL, xs=xs, ys=ys)
+
+
+def test_simulate_to_files(tmp_path):
+    pattern = str(tmp_path / "realization_{}.gri")
+    paths = surfio.IrapSurface.simulate_to_files(pattern, HEADER, MODEL, 3, seed=1)
+    assert paths == [str(tmp_path / f"realization_{k}.gri") for k in range(3)]
+    expected = surfio.IrapSurface.simulate(HEADER, MODEL, count=3, seed=1)
+    for path, field in zip(paths, expected):
+        np.testing.assert_array_equal(surfio.IrapSurface.from_binary_file(path).values, field.values)
+
+    with pytest.raises(ValueError):
+        surfio.IrapSurface.simulate_to_files(str(tmp_path / "realization.gri"), HEADER, MODEL, 1)
@@ -0,0 +1,184 @@
+use surfio_rs::gridding::Anisotropy;
+use surfio_rs::simulation::{Conditioning, SimulationOptions, simulate, simulate_to_files};
+use surfio_rs::variogram::{ModelKind, VariogramModel};
+use surfio_rs::{IrapHeader, irap};
+
+fn header(ncol: u32, nrow: u32) -> IrapHeader {
+    IrapHeader {
+        ncol,
+        nrow,
+        xori: 1000.0,
+        yori: 2000.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    }
+}
+
+fn options(kind: ModelKind) -> SimulationOptions<'static> {
+    SimulationOptions {
+        model: VariogramModel {
+            kind,
+            nugget: 0.0,
+            sill: 4.0,
+            range: 100.0,
+        },
+        anisotropy: None,
+        mean: 50.0,
+        seed: 42,
+        conditioning: None,
+    }
+}
+
+/// Ensemble covariance between two nodes.
+fn covariance(fields: &[Vec<f32>], a: usize, b: usize, mean: f64) -> f64 {
+    fields
+        .iter()
+        .map(|f| (f[a] as f64 - mean) * (f[b] as f64 - mean))
+        .sum::<f64>()
+        / fields.len() as f64
+}
+
+#[test]
+fn test_realizations_are_reproducible() {
+    let h = header(30, 20);
+    let options = options(ModelKind::Spherical);
+    let three = simulate(&h, &options, 3).unwrap();
+    let five = simulate(&h, &options, 5).unwrap();
+    assert_eq!(three.len(), 3);
+    assert_eq!(five.len(), 5);
+    assert_eq!(three[..], five[..3]);
+    assert_ne!(three[0], three[1]);
+    assert_eq!(three[0].header, h);
+
+    let mut other = options;
+    other.seed = 43;
+    assert_ne!(simulate(&h, &other, 1).unwrap()[0], three[0]);
+}
+
+#[test]
+fn test_ensemble_covariance_follows_model() {
+    let h = header(30, 30);
+    for kind in [
+        ModelKind::Spherical,
+        ModelKind::Exponential,
+        ModelKind::Gaussian,
+    ] {
+        let options = options(kind);
+        let fields: Vec<Vec<f32>> = simulate(&h, &options, 1000)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.values)
+            .collect();
+        let centre = h.index(15, 15);
+        for (col, row) in [(15, 15), (18, 15), (15, 20), (25, 15)] {
+            let h_distance = 10.0 * ((col as f64 - 15.0).hypot(row as f64 - 15.0));
+            let expected = options.model.covariance(h_distance);
+            let actual = covariance(&fields, centre, h.index(col, row), 50.0);
+            assert!(
+                (actual - expected).abs() < 0.4,
+                "{:?} at {}: {} != {}",
+                kind,
+                h_distance,
+                actual,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn test_anisotropy() {
+    let h = header(30, 30);
+    let mut options = options(ModelKind::Exponential);
+    // Long correlation north-south
+    options.anisotropy = Some(Anisotropy {
+        azimuth: 0.0,
+        minor_range: 20.0,
+    });
+    let fields: Vec<Vec<f32>> = simulate(&h, &options, 600)
+        .unwrap()
+        .into_iter()
+        .map(|f| f.values)
+        .collect();
+    let centre = h.index(15, 15);
+    let north = covariance(&fields, centre, h.index(15, 19), 50.0);
+    let east = covariance(&fields, centre, h.index(19, 15), 50.0);
+    assert!((north - 4.0 * (-1.2f64).exp()).abs() < 0.4, "{}", north);
+    assert!(east.abs() < 0.4, "{}", east);
+}
+
+#[test]
+fn test_conditional_simulation_honours_data() {
+    let h = header(40, 30);
+    let (xs, ys, zs) = (
+        [1100.0, 1250.0, 1300.0, 5000.0],
+        [2100.0, 2150.0, 2250.0, 2000.0],
+        [40.0, 60.0, 55.0, 0.0],
+    );
+    let mut options = options(ModelKind::Spherical);
+    options.conditioning = Some(Conditioning {
+        xs: &xs,
+        ys: &ys,
+        zs: &zs,
+        radius: f64::INFINITY,
+        max_points: 16,
+    });
+    let fields = simulate(&h, &options, 6).unwrap();
+    for field in &fields {
+        for k in 0..3 {
+            assert!((field.sample(xs[k], ys[k]) - zs[k]).abs() < 1e-3);
+        }
+    }
+    // Far from the data the realizations still differ
+    let far = h.index(0, 29);
+    assert_ne!(fields[0].values[far], fields[1].values[far]);
+}
+
+#[test]
+fn test_simulate_to_files() {
+    let h = header(12, 9);
+    let options = options(ModelKind::Exponential);
+    let dir = std::env::temp_dir().join(format!("surfio_simulation_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pattern = dir
+        .join("realization_{}.gri")
+        .to_string_lossy()
+        .into_owned();
+
+    let count = 20;
+    let paths = simulate_to_files(&h, &options, count, &pattern).unwrap();
+    assert_eq!(paths.len(), count);
+    assert!(paths[17].ends_with("realization_17.gri"));
+    let expected = simulate(&h, &options, count).unwrap();
+    for k in [0, 16, 19] {
+        let irap = irap::binary::from_file(paths[k].clone()).unwrap();
+        assert_eq!(irap.values, expected[k].values);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(simulate_to_files(&h, &options, 1, "realization.gri").is_err());
+}
+
+#[test]
+fn test_invalid_options() {
+    let h = header(10, 10);
+    let mut invalid = options(ModelKind::Spherical);
+    invalid.model.range = -5.0;
+    assert!(simulate(&h, &invalid, 1).is_err());
+    assert!(simulate(&header(0, 10), &options(ModelKind::Spherical), 1).is_err());
+
+    let mut flat = header(10, 10);
+    flat.yinc = 0.0;
+    assert!(simulate(&flat, &options(ModelKind::Spherical), 1).is_err());
+    flat.yinc = f64::NAN;
+    assert!(simulate(&flat, &options(ModelKind::Spherical), 1).is_err());
+    let mut long = options(ModelKind::Gaussian);
+    long.model.range = 1e9;
+    assert!(simulate(&h, &long, 1).is_err());
+    assert!(
+        simulate(&h, &options(ModelKind::Spherical), 0)
+            .unwrap()
+            .is_empty()
+    );
+}
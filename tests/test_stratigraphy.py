@@ -0,0 +1,46 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def surface(values):
+    values = np.array([values], dtype=np.float32).T
+    header = surfio.IrapHeader(ncol=values.shape[0], nrow=1, xinc=10.0, yinc=10.0)
+    return surfio.IrapSurface(header, values)
+
+
+def stack():
+    return [
+        surface([100.0, 100.0, 130.0]),
+        surface([110.0, 120.0, 120.0]),
+        surface([120.0, 105.0, 125.0]),
+    ]
+
+
+def test_erosion_rule():
+    result = surfio.IrapSurface.apply_stack_rule(stack(), "erosion")
+    assert np.array_equal(result.surfaces[2].values[:, 0], [120.0, 120.0, 130.0])
+    assert result.adjusted_nodes == [0, 1, 2]
+    assert result.max_changes[2] == pytest.approx(15.0)
+    assert np.array_equal(result.changes[2].values[:, 0], [0.0, 15.0, 5.0])
+    assert len(result.isochores) == 2
+    assert all((i.values >= 0).all() for i in result.isochores)
+
+
+def test_onlap_and_minimum_thickness_rules():
+    onlap = surfio.IrapSurface.apply_stack_rule(stack(), "onlap")
+    assert np.array_equal(onlap.surfaces[0].values[:, 0], [100.0, 100.0, 120.0])
+
+    thick = surfio.IrapSurface.apply_stack_rule(stack(), "minimum_thickness", 8.0)
+    assert all((i.values >= 8.0).all() for i in thick.isochores)
+
+
+def test_isochores():
+    result = surfio.IrapSurface.isochores(stack())
+    assert np.array_equal(result[0].values[:, 0], [10.0, 20.0, -10.0])
+
+
+def test_unknown_rule():
+    with pytest.raises(ValueError):
+        surfio.IrapSurface.apply_stack_rule(stack(), "unconformity")
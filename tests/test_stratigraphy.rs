@@ -0,0 +1,84 @@
+use surfio_rs::stratigraphy::{StackRule, apply_rule, isochores};
+use surfio_rs::{Irap, IrapHeader};
+
+fn surface(values: &[f32]) -> Irap {
+    let header = IrapHeader {
+        ncol: values.len() as u32,
+        nrow: 1,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    Irap {
+        header,
+        values: values.to_vec(),
+    }
+}
+
+fn stack() -> Vec<Irap> {
+    vec![
+        surface(&[100.0, 100.0, 130.0, f32::NAN]),
+        surface(&[110.0, 120.0, 120.0, 115.0]),
+        surface(&[120.0, 105.0, 125.0, 110.0]),
+    ]
+}
+
+#[test]
+fn test_erosion_moves_older_surfaces_down() {
+    let result = apply_rule(&stack(), StackRule::Erosion).unwrap();
+    assert_eq!(result.surfaces[0].values[..3], [100.0, 100.0, 130.0]);
+    assert_eq!(result.surfaces[1].values, [110.0, 120.0, 130.0, 115.0]);
+    assert_eq!(result.surfaces[2].values, [120.0, 120.0, 130.0, 115.0]);
+
+    assert_eq!(result.adjustments[0].nodes, 0);
+    assert_eq!(result.adjustments[1].nodes, 1);
+    assert_eq!(result.adjustments[1].max_change, 10.0);
+    assert_eq!(result.adjustments[2].nodes, 3);
+    assert_eq!(result.adjustments[2].max_change, 15.0);
+    assert_eq!(result.adjustments[2].change.values, [0.0, 15.0, 5.0, 5.0]);
+    assert!(result.adjustments[0].change.values[3].is_nan());
+}
+
+#[test]
+fn test_onlap_moves_younger_surfaces_up() {
+    let result = apply_rule(&stack(), StackRule::Onlap).unwrap();
+    assert_eq!(result.surfaces[2].values, [120.0, 105.0, 125.0, 110.0]);
+    assert_eq!(result.surfaces[1].values, [110.0, 105.0, 120.0, 110.0]);
+    assert_eq!(result.surfaces[0].values[..3], [100.0, 100.0, 120.0]);
+    assert_eq!(result.adjustments[0].nodes, 1);
+    assert_eq!(result.adjustments[1].nodes, 2);
+    assert_eq!(result.adjustments[2].nodes, 0);
+}
+
+#[test]
+fn test_minimum_thickness() {
+    let result = apply_rule(&stack(), StackRule::MinimumThickness(8.0)).unwrap();
+    assert_eq!(result.surfaces[1].values, [110.0, 120.0, 138.0, 115.0]);
+    assert_eq!(result.surfaces[2].values, [120.0, 128.0, 146.0, 123.0]);
+    for isochore in &result.isochores {
+        assert!(isochore.values.iter().all(|&t| t.is_nan() || t >= 8.0));
+    }
+    assert!(apply_rule(&stack(), StackRule::MinimumThickness(-1.0)).is_err());
+}
+
+#[test]
+fn test_isochores_between_consecutive_surfaces() {
+    let result = isochores(&stack()).unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].values[..3], [10.0, 20.0, -10.0]);
+    assert!(result[0].values[3].is_nan());
+    assert_eq!(result[1].values, [10.0, -15.0, 5.0, -5.0]);
+
+    let adjusted = apply_rule(&stack(), StackRule::Erosion).unwrap();
+    for isochore in &adjusted.isochores {
+        assert!(isochore.values.iter().all(|&t| t.is_nan() || t >= 0.0));
+    }
+}
+
+#[test]
+fn test_surfaces_must_share_lattice() {
+    let mut surfaces = stack();
+    surfaces[1].header.xori = 5.0;
+    assert!(apply_rule(&surfaces, StackRule::Erosion).is_err());
+    assert!(isochores(&surfaces).is_err());
+}
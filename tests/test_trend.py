@@ -0,0 +1,35 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def plane_surface():
+    header = surfio.IrapHeader(ncol=31, nrow=21, xori=456000.0, yori=6780000.0, xinc=25.0, yinc=25.0)
+    cols, rows = np.meshgrid(np.arange(31.0), np.arange(21.0), indexing="ij")
+    values = 1500.0 + 2.5 * cols - 1.25 * rows
+    return surfio.IrapSurface(header, values.astype(np.float32))
+
+
+def test_fit_trend():
+    trend, fitted, residual = plane_surface().fit_trend(1)
+    assert trend.order == 1
+    assert trend.coefficients[1:] == pytest.approx([0.1, -0.05], abs=1e-6)
+    assert trend.r_squared == pytest.approx(1.0)
+    assert np.abs(residual.values).max() < 1e-3
+    assert np.allclose(fitted.values, plane_surface().values, atol=1e-3)
+    assert trend.evaluate(np.array([456000.0]), np.array([6780000.0])) == pytest.approx([1500.0])
+    assert np.allclose(trend.to_surface(fitted.header).values, fitted.values)
+
+
+def test_fit_trend_points():
+    xs, ys = np.meshgrid(np.arange(10.0), np.arange(10.0))
+    zs = 3.0 + xs * xs - ys
+    trend = surfio.IrapSurface.fit_trend_points(xs.ravel(), ys.ravel(), zs.ravel(), order=2)
+    assert len(trend.coefficients) == 6
+    assert trend.r_squared == pytest.approx(1.0)
+
+
+def test_invalid_order():
+    with pytest.raises(ValueError, match="order"):
+        plane_surface().fit_trend(4)
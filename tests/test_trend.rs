@@ -0,0 +1,112 @@
+use surfio_rs::trend::Trend;
+use surfio_rs::{Irap, IrapHeader};
+
+fn header(rot: f64) -> IrapHeader {
+    IrapHeader {
+        ncol: 31,
+        nrow: 21,
+        xori: 456000.0,
+        yori: 6780000.0,
+        xinc: 25.0,
+        yinc: 25.0,
+        rot,
+        ..Default::default()
+    }
+}
+
+fn surface(header: IrapHeader, f: impl Fn(f64, f64) -> f64) -> Irap {
+    let mut values = vec![0.0; header.len()];
+    for col in 0..header.ncol as usize {
+        for row in 0..header.nrow as usize {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = f(x, y) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+fn plane(x: f64, y: f64) -> f64 {
+    1500.0 + 0.1 * (x - 456000.0) - 0.05 * (y - 6780000.0)
+}
+
+#[test]
+fn test_plane_is_reproduced() {
+    let mut irap = surface(header(30.0), plane);
+    irap.values[7] = f32::NAN;
+    let fit = irap.fit_trend(1).unwrap();
+    assert_eq!(fit.trend.coefficients.len(), 3);
+    assert!((fit.trend.coefficients[1] - 0.1).abs() < 1e-6);
+    assert!((fit.trend.coefficients[2] + 0.05).abs() < 1e-6);
+    assert!((fit.trend.r_squared - 1.0).abs() < 1e-9);
+    assert!((fit.trend.evaluate(456100.0, 6780200.0) - 1500.0).abs() < 1e-3);
+
+    assert_eq!(fit.surface.header, irap.header);
+    assert!(!fit.surface.values[7].is_nan());
+    assert!(fit.residual.values[7].is_nan());
+    let worst = fit
+        .residual
+        .values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .fold(0.0f32, |m, v| m.max(v.abs()));
+    assert!(worst < 1e-3);
+}
+
+#[test]
+fn test_cubic_from_points() {
+    let cubic = |x: f64, y: f64| {
+        let (u, v) = ((x - 456300.0) / 100.0, (y - 6780200.0) / 100.0);
+        10.0 + u - 2.0 * v + 0.5 * u * v + u * u * u - 0.25 * u * v * v
+    };
+    let (mut xs, mut ys, mut zs) = (vec![], vec![], vec![]);
+    for i in 0..15 {
+        for j in 0..12 {
+            let (x, y) = (456000.0 + 40.0 * i as f64, 6780000.0 + 35.0 * j as f64);
+            xs.push(x);
+            ys.push(y);
+            zs.push(cubic(x, y));
+        }
+    }
+    let trend = Trend::fit(&xs, &ys, &zs, 3).unwrap();
+    assert_eq!(trend.coefficients.len(), 10);
+    assert!((trend.r_squared - 1.0).abs() < 1e-9);
+    for (x, y) in [(456123.0, 6780321.0), (456500.0, 6780050.0)] {
+        assert!((trend.evaluate(x, y) - cubic(x, y)).abs() < 1e-6);
+    }
+
+    // A plane cannot follow the cubic
+    let linear = Trend::fit(&xs, &ys, &zs, 1).unwrap();
+    assert!(linear.r_squared < 0.99);
+}
+
+#[test]
+fn test_symmetric_bowl_has_no_linear_trend() {
+    let bowl = surface(header(0.0), |x, y| {
+        ((x - 456375.0) / 25.0).powi(2) + ((y - 6780250.0) / 25.0).powi(2)
+    });
+    let linear = bowl.fit_trend(1).unwrap().trend;
+    assert!(linear.coefficients[1].abs() < 1e-6);
+    assert!(linear.coefficients[2].abs() < 1e-6);
+    assert!(linear.r_squared.abs() < 1e-9);
+
+    let quadratic = bowl.fit_trend(2).unwrap().trend;
+    assert!((quadratic.r_squared - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_invalid_fits() {
+    let irap = surface(header(0.0), plane);
+    assert!(irap.fit_trend(0).is_err());
+    assert!(irap.fit_trend(4).is_err());
+
+    let (xs, ys, zs) = (
+        [0.0, 1.0, 2.0, 3.0],
+        [0.0, 1.0, 2.0, 3.0],
+        [1.0, 2.0, 3.0, 4.0],
+    );
+    // Collinear points
+    assert!(Trend::fit(&xs, &ys, &zs, 1).is_err());
+    // Too few points
+    assert!(Trend::fit(&xs, &ys, &zs, 2).is_err());
+    assert!(Trend::fit(&xs, &ys[..3], &zs, 1).is_err());
+}
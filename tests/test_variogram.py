@@ -0,0 +1,38 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def striped():
+    header = surfio.IrapHeader(ncol=30, nrow=30, xori=1000.0, yori=2000.0, xinc=10.0, yinc=10.0)
+    cols = np.repeat(np.arange(30.0)[:, None], 30, axis=1)
+    return surfio.IrapSurface(header, (0.5 * cols).astype(np.float32))
+
+
+def test_variogram():
+    variogram = striped().variogram(10.0, 5, azimuth=90.0, tolerance=10.0)
+    assert variogram.pairs[1] == 29 * 30
+    assert variogram.gamma[1] == pytest.approx(0.125)
+    assert np.isnan(variogram.gamma[0])
+    north = striped().variogram(10.0, 5, azimuth=0.0, tolerance=10.0)
+    assert np.all(north.gamma[1:] == 0.0)
+
+
+def test_variogram_points_and_fit():
+    model = surfio.VariogramModel("exponential", nugget=0.2, sill=1.5, range=350.0)
+    lags = 25.0 * np.arange(1, 21)
+    gamma = model.gamma(lags)
+    assert gamma[-1] == pytest.approx(0.2 + 1.5 * (1 - np.exp(-3 * 500 / 350)))
+
+    xs = np.array([0.0, 10.0, 20.0])
+    variogram = surfio.IrapSurface.variogram_points(xs, np.zeros(3), np.array([0.0, 1.0, 3.0]), 10.0, 3)
+    assert list(variogram.pairs) == [0, 2, 1]
+    fitted = variogram.fit("spherical")
+    assert fitted.model == "spherical"
+    assert fitted.range > 0
+
+
+def test_unknown_model():
+    with pytest.raises(ValueError, match="Unknown variogram model"):
+        surfio.VariogramModel("cubic")
@@ -0,0 +1,163 @@
+use surfio_rs::variogram::{
+    self, Direction, ModelKind, Variogram, VariogramModel, VariogramOptions,
+};
+use surfio_rs::{Irap, IrapHeader};
+
+fn options(lag: f64, nlags: usize) -> VariogramOptions {
+    VariogramOptions {
+        lag,
+        nlags,
+        direction: None,
+        max_points: None,
+        seed: 0,
+    }
+}
+
+/// Values varying along x only.
+fn striped() -> Irap {
+    let header = IrapHeader {
+        ncol: 30,
+        nrow: 30,
+        xori: 1000.0,
+        yori: 2000.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    let values = (0..header.len())
+        .map(|idx| (idx / 30) as f32 * 0.5)
+        .collect();
+    Irap { header, values }
+}
+
+#[test]
+fn test_experimental_bins() {
+    let result = variogram::from_points(
+        &[0.0, 10.0, 20.0, f64::NAN],
+        &[0.0; 4],
+        &[0.0, 1.0, 3.0, 5.0],
+        &options(10.0, 3),
+    )
+    .unwrap();
+    assert_eq!(result.pairs, [0, 2, 1]);
+    assert!(result.lags[0].is_nan() && result.gamma[0].is_nan());
+    assert_eq!(result.lags[1..], [10.0, 20.0]);
+    assert_eq!(result.gamma[1..], [1.25, 4.5]);
+}
+
+#[test]
+fn test_directional_variogram() {
+    let irap = striped();
+    let along = |azimuth| {
+        let mut options = options(10.0, 5);
+        options.direction = Some(Direction {
+            azimuth,
+            tolerance: 10.0,
+            bandwidth: None,
+        });
+        irap.variogram(&options).unwrap()
+    };
+    // No variation northwards, and bins hold only pairs along the azimuth
+    let north = along(0.0);
+    assert!(north.gamma[1..].iter().all(|&g| g == 0.0));
+    let east = along(90.0);
+    assert_eq!(east.gamma[1], 0.125);
+    assert_eq!(east.pairs[1], 29 * 30);
+    let west = along(270.0);
+    assert_eq!(west.pairs, east.pairs);
+    assert_eq!(west.gamma[1..], east.gamma[1..]);
+
+    let omni = irap.variogram(&options(10.0, 5)).unwrap();
+    assert!(omni.pairs[1] > east.pairs[1]);
+    assert!(omni.gamma[1] > 0.0 && omni.gamma[1] < east.gamma[1]);
+
+    // A narrow band keeps only pairs on the same row
+    let mut banded = options(10.0, 5);
+    banded.direction = Some(Direction {
+        azimuth: 90.0,
+        tolerance: 45.0,
+        bandwidth: Some(1.0),
+    });
+    assert_eq!(irap.variogram(&banded).unwrap().pairs[2], 28 * 30);
+}
+
+#[test]
+fn test_subsampling_is_reproducible() {
+    let irap = striped();
+    let mut options = options(50.0, 20);
+    options.max_points = Some(100);
+    let first = irap.variogram(&options).unwrap();
+    assert_eq!(first.pairs.iter().sum::<usize>(), 100 * 99 / 2);
+    let again = irap.variogram(&options).unwrap();
+    assert_eq!(again.pairs, first.pairs);
+    for (a, b) in again.gamma.iter().zip(&first.gamma) {
+        assert!((a - b).abs() <= 1e-9 * a.abs() || (a.is_nan() && b.is_nan()));
+    }
+    options.seed = 1;
+    assert_ne!(irap.variogram(&options).unwrap().pairs, first.pairs);
+}
+
+#[test]
+fn test_fit_recovers_models() {
+    for kind in [
+        ModelKind::Spherical,
+        ModelKind::Exponential,
+        ModelKind::Gaussian,
+    ] {
+        let model = VariogramModel {
+            kind,
+            nugget: 0.2,
+            sill: 1.5,
+            range: 350.0,
+        };
+        let lags: Vec<f64> = (1..=20).map(|k| 25.0 * k as f64).collect();
+        let experimental = Variogram {
+            gamma: lags.iter().map(|&h| model.gamma(h)).collect(),
+            pairs: vec![100; lags.len()],
+            lags,
+        };
+        let fitted = experimental.fit(kind).unwrap();
+        assert!((fitted.nugget - 0.2).abs() < 1e-3, "{:?}", fitted);
+        assert!((fitted.sill - 1.5).abs() < 1e-3, "{:?}", fitted);
+        assert!((fitted.range - 350.0).abs() < 0.5, "{:?}", fitted);
+    }
+}
+
+#[test]
+fn test_model_values() {
+    let model = VariogramModel {
+        kind: ModelKind::Spherical,
+        nugget: 0.1,
+        sill: 0.9,
+        range: 100.0,
+    };
+    assert_eq!(model.gamma(0.0), 0.0);
+    assert!((model.gamma(50.0) - (0.1 + 0.9 * 0.6875)).abs() < 1e-12);
+    assert_eq!(model.gamma(150.0), 1.0);
+    assert_eq!(model.covariance(0.0), 1.0);
+    assert_eq!(model.covariance(150.0), 0.0);
+
+    let exponential = VariogramModel {
+        kind: ModelKind::Exponential,
+        ..model
+    };
+    assert!((exponential.gamma(100.0) - (0.1 + 0.9 * 0.95)).abs() < 1e-3);
+}
+
+#[test]
+fn test_invalid_options() {
+    let irap = striped();
+    assert!(irap.variogram(&options(0.0, 5)).is_err());
+    assert!(irap.variogram(&options(10.0, 0)).is_err());
+    let mut wide = options(10.0, 5);
+    wide.direction = Some(Direction {
+        azimuth: 0.0,
+        tolerance: 120.0,
+        bandwidth: None,
+    });
+    assert!(irap.variogram(&wide).is_err());
+
+    let sparse =
+        variogram::from_points(&[0.0, 10.0], &[0.0; 2], &[1.0, 2.0], &options(10.0, 3)).unwrap();
+    assert!(sparse.fit(ModelKind::Spherical).is_err());
+}
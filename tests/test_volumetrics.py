@@ -0,0 +1,43 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def flat(value, rot=0.0):
+    header = surfio.IrapHeader(ncol=11, nrow=11, xinc=10.0, yinc=10.0, rot=rot)
+    return surfio.IrapSurface(header, np.full((11, 11), value, dtype=np.float32))
+
+
+@pytest.mark.parametrize("rot", [0.0, 30.0])
+def test_volumetrics_between_flat_surfaces(rot):
+    result = flat(100.0, rot).volumetrics(flat(150.0, rot))
+    assert result.area == pytest.approx(10000.0)
+    assert result.volume == pytest.approx(500000.0)
+    assert result.hypsometry.shape == (0, 3)
+
+
+def test_volumetrics_with_contact_level_and_surface():
+    top, base = flat(100.0), flat(150.0)
+    assert top.volumetrics(base, contact=120.0).volume == pytest.approx(200000.0)
+    assert top.volumetrics(base, contact=flat(120.0)).volume == pytest.approx(200000.0)
+
+
+def test_volumetrics_in_polygon():
+    polygon = np.array([[-5.0, -5.0], [50.0, -5.0], [50.0, 105.0], [-5.0, 105.0]])
+    result = flat(100.0).volumetrics(flat(150.0), polygon=polygon)
+    assert result.area == pytest.approx(5000.0)
+
+
+def test_volumetrics_hypsometry_table():
+    result = flat(100.0).volumetrics(flat(150.0), slice_interval=10.0)
+    depths, areas, volumes = result.hypsometry.T
+    assert np.allclose(depths, [100.0, 110.0, 120.0, 130.0, 140.0, 150.0])
+    assert np.allclose(volumes, 10000.0 * (depths - 100.0))
+    assert np.allclose(areas[1:], 10000.0)
+
+
+def test_volumetrics_rejects_mismatched_lattice():
+    base = flat(150.0, rot=10.0)
+    with pytest.raises(ValueError):
+        flat(100.0).volumetrics(base)
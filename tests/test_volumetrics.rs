@@ -0,0 +1,194 @@
+use surfio_rs::volumetrics::{Contact, VolumeOptions, volumetrics};
+use surfio_rs::{Irap, IrapHeader};
+
+fn header() -> IrapHeader {
+    IrapHeader {
+        ncol: 11,
+        nrow: 11,
+        xori: 1000.0,
+        yori: 2000.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        rot: 30.0,
+        ..Default::default()
+    }
+}
+
+fn surface(f: impl Fn(usize, usize) -> f32) -> Irap {
+    let header = header();
+    let mut values = vec![0.0; header.len()];
+    for col in 0..11 {
+        for row in 0..11 {
+            values[header.index(col, row)] = f(col, row);
+        }
+    }
+    Irap { header, values }
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-6 * b.abs().max(1.0), "{} != {}", a, b);
+}
+
+#[test]
+fn test_volume_between_flat_surfaces() {
+    let top = surface(|_, _| 100.0);
+    let base = surface(|_, _| 150.0);
+    let result = volumetrics(&top, &base, &VolumeOptions::default()).unwrap();
+    assert_close(result.area, 10000.0);
+    assert_close(result.volume, 500000.0);
+    assert!(result.hypsometry.is_empty());
+}
+
+#[test]
+fn test_volume_is_truncated_at_constant_contact() {
+    let top = surface(|_, _| 100.0);
+    let base = surface(|_, _| 150.0);
+    let options = VolumeOptions {
+        contact: Some(Contact::Level(120.0)),
+        ..Default::default()
+    };
+    let result = volumetrics(&top, &base, &options).unwrap();
+    assert_close(result.volume, 200000.0);
+}
+
+#[test]
+fn test_partial_cells_where_surfaces_cross() {
+    // Thickness 5 - col where positive, so the rock pinches out mid-cell
+    let top = surface(|col, _| 100.0 + col as f32);
+    let base = surface(|_, _| 105.0);
+    let result = volumetrics(&top, &base, &VolumeOptions::default()).unwrap();
+    assert_close(result.area, 5000.0);
+    assert_close(result.volume, 12500.0);
+
+    let base = surface(|_, _| 104.5);
+    let result = volumetrics(&top, &base, &VolumeOptions::default()).unwrap();
+    assert_close(result.area, 4500.0);
+    assert_close(result.volume, 0.5 * 4.5 * 4.5 * 10.0 * 100.0);
+}
+
+#[test]
+fn test_contact_surface_crossing_base() {
+    let top = surface(|_, _| 100.0);
+    let base = surface(|_, _| 110.0);
+    // Contact rises from 120 to 100 across the grid, so it cuts off the base
+    // for col > 5 and the whole column at col 10
+    let contact = surface(|col, _| 120.0 - 2.0 * col as f32);
+    let options = VolumeOptions {
+        contact: Some(Contact::Surface(&contact)),
+        ..Default::default()
+    };
+    let result = volumetrics(&top, &base, &options).unwrap();
+    assert_close(result.area, 10000.0);
+    assert_close(
+        result.volume,
+        (5.0 * 10.0 + 0.5 * 5.0 * 10.0) * 10.0 * 100.0,
+    );
+}
+
+#[test]
+fn test_polygon_restricts_volume() {
+    let top = surface(|_, _| 100.0);
+    let base = surface(|_, _| 150.0);
+    let h = header();
+    let corner = |col: f64, row: f64| {
+        let (x, y) = h.node_xy(col, row);
+        [x, y]
+    };
+    // Covers columns 0 to 5 of the rotated lattice
+    let polygon = [
+        corner(-1.0, -1.0),
+        corner(5.0, -1.0),
+        corner(5.0, 11.0),
+        corner(-1.0, 11.0),
+        corner(-1.0, -1.0),
+    ];
+    let options = VolumeOptions {
+        polygon: Some(&polygon),
+        ..Default::default()
+    };
+    let result = volumetrics(&top, &base, &options).unwrap();
+    assert_close(result.area, 5000.0);
+    assert_close(result.volume, 250000.0);
+}
+
+#[test]
+fn test_degenerate_polygons_contain_nothing() {
+    let top = surface(|_, _| 100.0);
+    let base = surface(|_, _| 150.0);
+    let h = header();
+    let (x, y) = h.node_xy(2.0, 2.0);
+    for polygon in [vec![], vec![[x, y]], vec![[x, y], [x + 10.0, y]]] {
+        let options = VolumeOptions {
+            polygon: Some(&polygon),
+            ..Default::default()
+        };
+        let result = volumetrics(&top, &base, &options).unwrap();
+        assert_eq!(result.area, 0.0);
+        assert_eq!(result.volume, 0.0);
+    }
+}
+
+#[test]
+fn test_coincident_surfaces_have_no_area() {
+    // Top and base meet over the first five columns of cells
+    let top = surface(|_, _| 100.0);
+    let base = surface(|col, _| if col <= 5 { 100.0 } else { 110.0 });
+    let options = VolumeOptions {
+        slice_interval: Some(5.0),
+        ..Default::default()
+    };
+    let result = volumetrics(&top, &base, &options).unwrap();
+    assert_close(result.area, 5000.0);
+    assert_close(result.volume, 45000.0);
+    assert_close(result.hypsometry.last().unwrap().area, 5000.0);
+
+    let result = volumetrics(&top, &top, &options).unwrap();
+    assert_eq!(result.area, 0.0);
+}
+
+#[test]
+fn test_hypsometry_accumulates_with_depth() {
+    let top = surface(|col, _| 100.0 + col as f32);
+    let base = surface(|_, _| 130.0);
+    let options = VolumeOptions {
+        slice_interval: Some(5.0),
+        ..Default::default()
+    };
+    let result = volumetrics(&top, &base, &options).unwrap();
+    let depths: Vec<f64> = result.hypsometry.iter().map(|r| r.depth).collect();
+    assert_eq!(depths.first(), Some(&100.0));
+    assert_eq!(depths.last(), Some(&130.0));
+
+    let row = result.hypsometry.iter().find(|r| r.depth == 105.0).unwrap();
+    assert_close(row.area, 5000.0);
+    assert_close(row.volume, 12500.0);
+
+    let last = result.hypsometry.last().unwrap();
+    assert_close(last.area, result.area);
+    assert_close(last.volume, result.volume);
+    for pair in result.hypsometry.windows(2) {
+        assert!(pair[1].volume >= pair[0].volume);
+    }
+}
+
+#[test]
+fn test_undefined_cells_are_excluded() {
+    let top = surface(|col, row| {
+        if col == 0 && row == 0 {
+            f32::NAN
+        } else {
+            100.0
+        }
+    });
+    let base = surface(|_, _| 150.0);
+    let result = volumetrics(&top, &base, &VolumeOptions::default()).unwrap();
+    assert_close(result.area, 9900.0);
+}
+
+#[test]
+fn test_mismatched_lattices_are_rejected() {
+    let top = surface(|_, _| 100.0);
+    let mut base = surface(|_, _| 150.0);
+    base.header.xinc = 20.0;
+    assert!(volumetrics(&top, &base, &VolumeOptions::default()).is_err());
+}
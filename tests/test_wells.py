@@ -0,0 +1,32 @@
+import numpy as np
+import pytest
+
+import surfio_rs as surfio
+
+
+def flat(depth):
+    header = surfio.IrapHeader(ncol=31, nrow=31, xori=1000.0, yori=2000.0, xinc=10.0, yinc=10.0)
+    return surfio.IrapSurface(header, np.full((31, 31), depth, dtype=np.float32))
+
+
+TRAJECTORY = np.array([[1123.0, 2077.0, 0.0, 25.0], [1123.0, 2077.0, 3000.0, 3025.0]])
+
+
+def test_intersect_well():
+    crossings = flat(1500.0).intersect_well(TRAJECTORY)
+    assert crossings.shape == (1, 5)
+    assert crossings[0] == pytest.approx([1525.0, 1123.0, 2077.0, 1500.0, 1500.0])
+
+
+def test_intersect_well_batch():
+    crossings = surfio.IrapSurface.intersect_well_batch([flat(1500.0), flat(9000.0)], TRAJECTORY)
+    assert len(crossings) == 2
+    assert crossings[0][0, 0] == pytest.approx(1525.0)
+    assert crossings[1].shape == (0, 5)
+
+
+def test_invalid_trajectory():
+    with pytest.raises(ValueError, match="shape"):
+        flat(1500.0).intersect_well(TRAJECTORY[:, :3])
+    with pytest.raises(ValueError, match="Measured depth decreases"):
+        flat(1500.0).intersect_well(TRAJECTORY[::-1].copy())
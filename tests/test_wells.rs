@@ -0,0 +1,120 @@
+use surfio_rs::wells::{intersect_well, intersect_well_batch};
+use surfio_rs::{Irap, IrapHeader};
+
+fn surface(f: impl Fn(f64, f64) -> f64) -> Irap {
+    let header = IrapHeader {
+        ncol: 31,
+        nrow: 31,
+        xori: 1000.0,
+        yori: 2000.0,
+        xinc: 10.0,
+        yinc: 10.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..31 {
+        for row in 0..31 {
+            let (x, y) = header.node_xy(col as f64, row as f64);
+            values[header.index(col, row)] = f(x, y) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_vertical_well() {
+    let flat = surface(|_, _| 1500.0);
+    let trajectory = [
+        [1123.0, 2077.0, 0.0, 25.0],
+        [1123.0, 2077.0, 3000.0, 3025.0],
+    ];
+    let crossings = intersect_well(&flat, &trajectory).unwrap();
+    assert_eq!(crossings.len(), 1);
+    let c = crossings[0];
+    assert!((c.md - 1525.0).abs() < 1e-9);
+    assert_eq!((c.x, c.y), (1123.0, 2077.0));
+    assert!((c.z - 1500.0).abs() < 1e-9);
+    assert_eq!(c.value, 1500.0);
+}
+
+#[test]
+fn test_deviated_well_through_dipping_plane() {
+    // Deepening by 1 per 10 m eastwards
+    let plane = surface(|x, _| 1500.0 + (x - 1000.0) / 10.0);
+    // Descending 1 per 2 m eastwards, crossing where (x - 1000) / 10 = (x - 1000) / 2 - 20
+    let trajectory = [
+        [1000.0, 2150.0, 1480.0, 0.0],
+        [1100.0, 2150.0, 1530.0, 111.8],
+        [1300.0, 2150.0, 1630.0, 335.4],
+    ];
+    let crossings = intersect_well(&plane, &trajectory).unwrap();
+    assert_eq!(crossings.len(), 1);
+    let c = crossings[0];
+    assert!((c.x - 1050.0).abs() < 1e-6);
+    assert!((c.z - 1505.0).abs() < 1e-6);
+    assert!((c.md - 55.9).abs() < 1e-6);
+}
+
+#[test]
+fn test_well_reentering_surface() {
+    // A ridge along y, crossed by a horizontal well just below its crest
+    let ridge = surface(|x, _| 1500.0 + ((x - 1150.0) / 10.0).powi(2));
+    let trajectory = [
+        [1000.0, 2100.0, 1509.0, 0.0],
+        [1300.0, 2100.0, 1509.0, 300.0],
+    ];
+    let crossings = intersect_well(&ridge, &trajectory).unwrap();
+    assert_eq!(crossings.len(), 2);
+    // The bilinear surface is exact at nodes, 1509 is reached at x = 1150 +- 30
+    assert!((crossings[0].x - 1120.0).abs() < 1e-6);
+    assert!((crossings[1].x - 1180.0).abs() < 1e-6);
+    assert!(crossings[0].md < crossings[1].md);
+    for c in &crossings {
+        assert!((c.value - c.z).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_crossing_on_station_and_cell_edge_is_found_once() {
+    let flat = surface(|_, _| 1500.0);
+    let trajectory = [
+        [1100.0, 2100.0, 1400.0, 0.0],
+        [1100.0, 2100.0, 1500.0, 100.0],
+        [1120.0, 2100.0, 1600.0, 200.0],
+    ];
+    let crossings = intersect_well(&flat, &trajectory).unwrap();
+    assert_eq!(crossings.len(), 1);
+    assert_eq!(crossings[0].md, 100.0);
+}
+
+#[test]
+fn test_undefined_and_outside() {
+    let mut flat = surface(|_, _| 1500.0);
+    for row in 0..31 {
+        let idx = flat.header.index(10, row);
+        flat.values[idx] = f32::NAN;
+    }
+    let vertical = |x: f64| [[x, 2100.0, 0.0, 0.0], [x, 2100.0, 3000.0, 3000.0]];
+    assert!(intersect_well(&flat, &vertical(1100.0)).unwrap().is_empty());
+    assert!(intersect_well(&flat, &vertical(900.0)).unwrap().is_empty());
+    assert_eq!(intersect_well(&flat, &vertical(1200.0)).unwrap().len(), 1);
+}
+
+#[test]
+fn test_batch_and_invalid_trajectory() {
+    let surfaces = [
+        surface(|_, _| 1500.0),
+        surface(|_, _| 1600.0),
+        surface(|_, _| 9000.0),
+    ];
+    let trajectory = [[1100.0, 2100.0, 0.0, 0.0], [1100.0, 2100.0, 3000.0, 3000.0]];
+    let crossings = intersect_well_batch(&surfaces, &trajectory).unwrap();
+    assert_eq!(crossings.len(), 3);
+    assert_eq!(crossings[0][0].md, 1500.0);
+    assert_eq!(crossings[1][0].md, 1600.0);
+    assert!(crossings[2].is_empty());
+
+    let backwards = [[1100.0, 2100.0, 0.0, 10.0], [1100.0, 2100.0, 3000.0, 0.0]];
+    assert!(intersect_well(&surfaces[0], &backwards).is_err());
+    assert!(intersect_well_batch(&surfaces, &[[f64::NAN; 4]]).is_err());
+}
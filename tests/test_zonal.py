@@ -0,0 +1,33 @@
+import numpy as np
+
+import surfio_rs as surfio
+
+
+def surface():
+    header = surfio.IrapHeader(ncol=4, nrow=3, xinc=10.0, yinc=5.0)
+    cols, rows = np.meshgrid(np.arange(4.0), np.arange(3.0), indexing="ij")
+    return surfio.IrapSurface(header, (10.0 * cols + rows).astype(np.float32))
+
+
+def test_zonal_stats_by_zone_surface():
+    srf = surface()
+    zones = np.where(np.arange(4)[:, None] < 2, 2.0, 7.0) * np.ones((4, 3))
+    zone_srf = surfio.IrapSurface(srf.header, zones.astype(np.float32))
+    stats = srf.zonal_stats(zone_srf)
+    assert list(stats.zone) == [2, 7]
+    assert list(stats.count) == [6, 6]
+    assert np.allclose(stats.area, [300.0, 300.0])
+    assert np.allclose(stats.mean, [6.0, 26.0])
+    assert np.allclose(stats.max, [12.0, 32.0])
+
+
+def test_zonal_stats_by_polygons():
+    square = np.array([[-1.0, -1.0], [15.0, -1.0], [15.0, 11.0], [-1.0, 11.0]])
+    far = np.array([[100.0, 100.0], [110.0, 100.0], [110.0, 110.0]])
+    stats = surface().zonal_stats([square, far])
+    assert list(stats.count) == [6, 0]
+    assert stats.mean[0] == 6.0
+    assert np.isnan(stats.mean[1])
+
+    stats = surface().zonal_stats(surfio.Polygons([square]))
+    assert list(stats.zone) == [0]
@@ -0,0 +1,109 @@
+use surfio_rs::zonal::{Zones, zonal_stats};
+use surfio_rs::{Irap, IrapHeader, Polygons};
+
+fn surface() -> Irap {
+    let header = IrapHeader {
+        ncol: 4,
+        nrow: 3,
+        xinc: 10.0,
+        yinc: 5.0,
+        ..Default::default()
+    };
+    let mut values = vec![0.0; header.len()];
+    for col in 0..4 {
+        for row in 0..3 {
+            values[header.index(col, row)] = (col * 10 + row) as f32;
+        }
+    }
+    Irap { header, values }
+}
+
+#[test]
+fn test_zonal_stats_by_zone_surface() {
+    let irap = surface();
+    // Columns 0-1 are zone 2, columns 2-3 are zone 7, with one unzoned node
+    let mut zones = irap.clone();
+    for col in 0..4 {
+        for row in 0..3 {
+            zones.values[irap.header.index(col, row)] = if col < 2 { 2.0 } else { 7.0 };
+        }
+    }
+    let idx = irap.header.index(3, 2);
+    zones.values[idx] = f32::NAN;
+
+    let stats = zonal_stats(&irap, &Zones::Surface(&zones)).unwrap();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].zone, 2);
+    assert_eq!(stats[0].count, 6);
+    assert_eq!(stats[0].area, 300.0);
+    assert_eq!(stats[0].min, 0.0);
+    assert_eq!(stats[0].max, 12.0);
+    assert_eq!(stats[0].sum, 36.0);
+    assert!((stats[0].mean - 6.0).abs() < 1e-12);
+    // Values 0, 1, 2, 10, 11, 12
+    let variance = (36.0 + 25.0 + 16.0 + 16.0 + 25.0 + 36.0) / 6.0f64;
+    assert!((stats[0].std - variance.sqrt()).abs() < 1e-12);
+
+    assert_eq!(stats[1].zone, 7);
+    assert_eq!(stats[1].count, 5);
+    assert_eq!(stats[1].max, 31.0);
+}
+
+#[test]
+fn test_zonal_stats_ignores_undefined_values() {
+    let mut irap = surface();
+    irap.values[0] = f32::NAN;
+    let zones = Irap {
+        header: irap.header.clone(),
+        values: vec![1.0; 12],
+    };
+    let stats = zonal_stats(&irap, &Zones::Surface(&zones)).unwrap();
+    assert_eq!(stats[0].count, 11);
+    assert_eq!(stats[0].min, 1.0);
+}
+
+#[test]
+fn test_zonal_stats_by_polygons() {
+    let irap = surface();
+    let polygons = Polygons {
+        polygons: vec![
+            vec![
+                [-1.0, -1.0, 0.0],
+                [15.0, -1.0, 0.0],
+                [15.0, 11.0, 0.0],
+                [-1.0, 11.0, 0.0],
+            ],
+            vec![
+                [100.0, 100.0, 0.0],
+                [110.0, 100.0, 0.0],
+                [110.0, 110.0, 0.0],
+            ],
+            vec![
+                [5.0, 4.0, 0.0],
+                [35.0, 4.0, 0.0],
+                [35.0, 6.0, 0.0],
+                [5.0, 6.0, 0.0],
+            ],
+        ],
+    };
+    let stats = zonal_stats(&irap, &Zones::Polygons(&polygons)).unwrap();
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[0].count, 6);
+    assert_eq!(stats[0].mean, 6.0);
+
+    assert_eq!(stats[1].count, 0);
+    assert_eq!(stats[1].area, 0.0);
+    assert!(stats[1].mean.is_nan());
+
+    // Row 1 of columns 1-3, overlapping the first polygon at column 1
+    assert_eq!(stats[2].count, 3);
+    assert_eq!(stats[2].sum, 11.0 + 21.0 + 31.0);
+}
+
+#[test]
+fn test_zone_surface_must_share_lattice() {
+    let irap = surface();
+    let mut zones = irap.clone();
+    zones.header.ncol = 2;
+    assert!(zonal_stats(&irap, &Zones::Surface(&zones)).is_err());
+}